@@ -17,5 +17,5 @@ fn main() {
         VkVersion(1, 0),
         &["VK_KHR_surface"],
         Default::default()
-    );
+    ).unwrap();
 }
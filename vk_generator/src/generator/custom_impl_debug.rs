@@ -1,3 +1,3 @@
 impl fmt::Debug for {0} {{
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> ::std::result::Result<(), fmt::Error> {{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> ::{core}::result::Result<(), fmt::Error> {{
         fmt.debug_struct("{0}")
\ No newline at end of file
@@ -1,19 +1,43 @@
 #[macro_export]
-macro_rules! vk_make_version {
-    ($major: expr, $minor: expr, $patch: expr) => ((($major as uint32_t) << 22) | (($minor as uint32_t) << 12) | $patch as uint32_t)
-}
+macro_rules! vk_make_version {{
+    ($major: expr, $minor: expr, $patch: expr) => ((($major as {0}) << 22) | (($minor as {0}) << 12) | $patch as {0})
+}}
 
 #[macro_export]
-macro_rules! vk_version_major {
-    ($major: expr) => (($major as uint32_t) << 22)
-}
+macro_rules! vk_version_major {{
+    ($major: expr) => (($major as {0}) << 22)
+}}
 
 #[macro_export]
-macro_rules! vk_version_minor {
-    ($minor: expr) => ((($minor as uint32_t) << 12) & 0x3ff)
-}
+macro_rules! vk_version_minor {{
+    ($minor: expr) => ((($minor as {0}) << 12) & 0x3ff)
+}}
 
 #[macro_export]
-macro_rules! vk_version_patch {
-    ($minor: expr) => (($minor as uint32_t) & 0xfff)
-}
+macro_rules! vk_version_patch {{
+    ($minor: expr) => (($minor as {0}) & 0xfff)
+}}
+
+/// Pack a version number in the same way as `VK_MAKE_VERSION`.
+#[allow(dead_code)]
+{vis} const fn make_version(major: u32, minor: u32, patch: u32) -> u32 {{
+    (major << 22) | (minor << 12) | patch
+}}
+
+/// Extract the major version from a packed version number, like `VK_VERSION_MAJOR`.
+#[allow(dead_code)]
+{vis} const fn version_major(version: u32) -> u32 {{
+    version >> 22
+}}
+
+/// Extract the minor version from a packed version number, like `VK_VERSION_MINOR`.
+#[allow(dead_code)]
+{vis} const fn version_minor(version: u32) -> u32 {{
+    (version >> 12) & 0x3ff
+}}
+
+/// Extract the patch version from a packed version number, like `VK_VERSION_PATCH`.
+#[allow(dead_code)]
+{vis} const fn version_patch(version: u32) -> u32 {{
+    version & 0xfff
+}}
@@ -1,5 +1,5 @@
 #[repr(C)]
 #[doc(hidden)]
-pub struct {0}_T (u8);
-pub type {0} = *mut {0}_T;
+{vis} struct {0}_T (u8);
+{vis} type {0} = *mut {0}_T;
 
@@ -0,0 +1,323 @@
+//! JSON descriptions for tooling: one of the registry exactly as the crawler parsed it, and one of
+//! the bindings, built from the same selection and processing as the Rust output, for tools that want
+//! to know what was generated without parsing Rust. Only built with the `serde_json` feature.
+
+use registry::*;
+use generator::{GenConfig, GenPreproc, GenRegistry};
+use to_option;
+
+use std::fmt::Write as FmtWrite;
+use std::io::{self, Write};
+
+use serde_json::{self, Value};
+
+/// `ptr` as a JSON string, or `null` if it's null.
+fn json_opt(ptr: *const str) -> Value {
+    to_option(ptr).map_or(Value::Null, Value::from)
+}
+
+/// A registry element type, as `{"kind": ..., "type": ...}` with the pointer `depth` or array `len`
+/// depending on the kind.
+fn json_el_type(typ: &VkElType) -> Value {
+    use registry::VkElType::*;
+
+    match *typ {
+        Var(t)               => json!({"kind": "var", "type": json_opt(t)}),
+        Const(t)             => json!({"kind": "const", "type": json_opt(t)}),
+        ConstPtr(t, depth)   => json!({"kind": "const_ptr", "type": json_opt(t), "depth": depth}),
+        MutPtr(t, depth)     => json!({"kind": "mut_ptr", "type": json_opt(t), "depth": depth}),
+        ConstArray(t, len)   => json!({"kind": "const_array", "type": json_opt(t), "len": len}),
+        MutArray(t, len)     => json!({"kind": "mut_array", "type": json_opt(t), "len": len}),
+        ConstArrayEnum(t, c) => json!({"kind": "const_array", "type": json_opt(t), "len": json_opt(c)}),
+        MutArrayEnum(t, c)   => json!({"kind": "mut_array", "type": json_opt(t), "len": json_opt(c)}),
+        Void                 => json!({"kind": "void"}),
+        Unknown              => json!({"kind": "unknown"})
+    }
+}
+
+/// A registry enum variant, keeping bit positions as they are.
+fn json_raw_variant(variant: &VkVariant) -> Value {
+    let mut described = json!({
+        "name": unsafe{ &*variant.name() },
+        "alias_of": variant.alias_of(),
+        "comment": variant.comment()
+    });
+    match *variant {
+        VkVariant::Value{value, ..}   => described["value"] = json!(value),
+        VkVariant::Bitpos{bitpos, ..} => described["bitpos"] = json!(bitpos)
+    }
+    described
+}
+
+/// The `require` or `remove` list of a feature or extension.
+fn json_interfaces(interfaces: &[VkInterface]) -> Value {
+    use registry::VkInterface::*;
+
+    interfaces.iter()
+        .map(|i| match *i {
+            Command{name, profile}  =>
+                json!({"kind": "command", "name": json_opt(name), "profile": json_opt(profile)}),
+            Type{name, profile}     =>
+                json!({"kind": "type", "name": json_opt(name), "profile": json_opt(profile)}),
+            ApiConst{name, profile} =>
+                json!({"kind": "api_const", "name": json_opt(name), "profile": json_opt(profile)}),
+            ConstDef{name, value, profile} =>
+                json!({"kind": "const_def", "name": json_opt(name), "value": json_opt(value), "profile": json_opt(profile)}),
+            ExtnEnum{extends, profile, ref variant} =>
+                json!({"kind": "enum", "extends": json_opt(extends), "profile": json_opt(profile), "variant": json_raw_variant(variant)})
+        })
+        .collect()
+}
+
+/// A registry type, as `{"name": ..., "kind": ...}` along with everything the crawler recorded for
+/// that kind. `None` for the types the crawler doesn't handle.
+fn json_raw_type(name: &str, typ: &VkType) -> Option<Value> {
+    use registry::VkType::*;
+
+    let raw_members = |members: &[VkMember]| -> Value {
+        members.iter()
+            .map(|m| json!({
+                "name": json_opt(m.field_name),
+                "type": json_el_type(&m.field_type),
+                "optional": m.optional,
+                "values": json_opt(m.values)
+            }))
+            .collect()
+    };
+    let raw_variants = |variants: &[VkVariant]| -> Value {
+        variants.iter().map(json_raw_variant).collect()
+    };
+
+    let mut described = match *typ {
+        Struct{ref fields, ref extends, comment, ..} => {
+            let extends: Vec<_> = extends.iter().map(|e| json_opt(*e)).collect();
+            json!({"kind": "struct", "fields": raw_members(fields), "extends": extends, "comment": json_opt(comment)})
+        }
+        Union{ref variants, comment, ..} =>
+            json!({"kind": "union", "fields": raw_members(variants), "comment": json_opt(comment)}),
+        Enum{ref variants, comment, ..} =>
+            json!({"kind": "enum", "variants": raw_variants(variants), "comment": json_opt(comment)}),
+        Bitmask{ref variants, comment, bitwidth, ..} =>
+            json!({"kind": "bitmask", "variants": raw_variants(variants), "bitwidth": bitwidth, "comment": json_opt(comment)}),
+        Handle{dispatchable, objtypeenum, ..} =>
+            json!({"kind": "handle", "dispatchable": dispatchable, "objtypeenum": json_opt(objtypeenum)}),
+        TypeDef{typ, requires, ..} =>
+            json!({"kind": "typedef", "type": json_opt(typ), "requires": json_opt(requires)}),
+        ApiConst{value, ..} =>
+            json!({"kind": "api_const", "value": json_opt(value)}),
+        Define{..} => json!({"kind": "define"}),
+        FuncPointer{ref ret, ref params, ..} => {
+            let params: Vec<_> = params.iter().map(json_el_type).collect();
+            json!({"kind": "funcpointer", "params": params, "return": json_el_type(ret)})
+        }
+        ExternType{requires, ..} =>
+            json!({"kind": "extern", "requires": json_opt(requires)}),
+        Alias{alias_of, ..} =>
+            json!({"kind": "alias", "alias_of": json_opt(alias_of)}),
+        Unhandled => return None
+    };
+    described["name"] = json!(name);
+    Some(described)
+}
+
+/// `{"name": ..., "type": ...}` for each of `members`, with the types written the way struct fields
+/// are.
+fn json_members(members: &[VkMember]) -> Value {
+    members.iter()
+        .map(|m| {
+            let mut field_type = String::new();
+            unsafe{ gen_member_type!(field_type, m) };
+            json!({"name": unsafe{ &*m.field_name }, "type": field_type})
+        })
+        .collect()
+}
+
+/// `{"name": ..., "value": ...}` for each of `variants`, with bit positions turned into values.
+fn json_variants(variants: &[VkVariant]) -> Value {
+    variants.iter()
+        .map(|v| {
+            let value = match *v {
+                VkVariant::Value{value, ..}   => json!(value),
+                VkVariant::Bitpos{bitpos, ..} => json!(1u64 << bitpos)
+            };
+            json!({"name": unsafe{ &*v.name() }, "value": value})
+        })
+        .collect()
+}
+
+/// A parameter or return type, written the way commands take it.
+fn json_param_type(typ: &VkElType) -> Value {
+    let mut rust_type = String::new();
+    unsafe{ gen_func_param!(rust_type, typ).unwrap() };
+    Value::String(rust_type)
+}
+
+/// Write `document` to `write`, indented and with a trailing newline.
+fn write_document<W: Write>(write: &mut W, document: &Value) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut *write, document)?;
+    writeln!(write)
+}
+
+impl<'a> VkRegistry<'a> {
+    /// Write everything the registry was parsed into to `write` as JSON, before any selection or
+    /// processing is done. This is meant for debugging the crawler, diffing registry versions and
+    /// feeding the registry to tools written in other languages. The document has four arrays:
+    ///
+    /// * `types`, with the `name` and `kind` of every type and whatever the registry defines for
+    ///   that kind, like the `fields` of a struct or the `variants` of an enum.
+    /// * `commands`, with the `name`, `params`, `return` type, `success_codes`, `comment` and
+    ///   `alias_of` of every command.
+    /// * `features`, with the `name`, `version` and `require` and `remove` lists of every feature.
+    /// * `extensions`, with the `name`, `number` and `require` and `remove` lists of every
+    ///   extension.
+    ///
+    /// Element types are objects with a `kind` (`var`, `const`, `const_ptr`, `mut_ptr`,
+    /// `const_array`, `mut_array`, `void` or `unknown`), the `type` they're made of, and the pointer
+    /// `depth` or array `len`. Missing optional values are `null`. Types and commands are sorted by
+    /// name, features by version and extensions by number, so that the output is the same every
+    /// time for the same registry.
+    ///
+    /// Only available with the `serde_json` feature.
+    pub fn dump_json<W: Write>(&self, write: &mut W) -> io::Result<()> {
+        let mut type_names: Vec<_> = self.types().keys().collect();
+        type_names.sort();
+        let types: Vec<_> = type_names.into_iter()
+            .filter_map(|name| json_raw_type(name, &self.types()[name]))
+            .collect();
+
+        let mut command_names: Vec<_> = self.commands().keys().collect();
+        command_names.sort();
+        let commands: Vec<_> = command_names.into_iter()
+            .map(|name| {
+                let c = &self.commands()[name];
+                let params: Vec<_> = c.params.iter()
+                    .map(|p| json!({
+                        "name": json_opt(p.name),
+                        "type": json_el_type(&p.typ),
+                        "externsync": json_opt(p.externsync),
+                        "noautovalidity": p.noautovalidity
+                    }))
+                    .collect();
+                let success_codes: Vec<_> = c.success_codes.iter().map(|code| json_opt(*code)).collect();
+                json!({
+                    "name": name,
+                    "params": params,
+                    "return": json_el_type(&c.ret),
+                    "success_codes": success_codes,
+                    "comment": json_opt(c.comment),
+                    "alias_of": json_opt(c.alias_of)
+                })
+            })
+            .collect();
+
+        let mut features: Vec<_> = self.features().values().collect();
+        features.sort_by_key(|f| (f.version.0, f.version.1, f.patch));
+        let features: Vec<_> = features.into_iter()
+            .map(|f| json!({
+                "name": json_opt(f.name),
+                "version": format!("{}.{}.{}", f.version.0, f.version.1, f.patch),
+                "require": json_interfaces(&f.require),
+                "remove": json_interfaces(&f.remove)
+            }))
+            .collect();
+
+        let mut extensions: Vec<_> = self.extns().values().collect();
+        extensions.sort_by_key(|e| e.num);
+        let extensions: Vec<_> = extensions.into_iter()
+            .map(|e| json!({
+                "name": json_opt(e.name),
+                "number": e.num,
+                "require": json_interfaces(&e.require),
+                "remove": json_interfaces(&e.remove)
+            }))
+            .collect();
+
+        write_document(write, &json!({
+            "types": types,
+            "commands": commands,
+            "features": features,
+            "extensions": extensions
+        }))
+    }
+
+    /// Write a JSON description of the bindings that [`gen_global()`] or [`gen_struct()`] would
+    /// generate for `version`, `extensions` and `config` to `write`. The document has three arrays:
+    ///
+    /// * `types`, with the `name`, `raw_name` and `kind` of every type, along with its `fields`,
+    ///   `variants`, `params` or aliased `type`, depending on the kind.
+    /// * `commands`, with the `name`, `raw_name`, `params` and `return` type of every command.
+    /// * `constants`, with the `name` and registry `value` of every API constant.
+    ///
+    /// Names are the ones in the generated Rust, and types are written the way the Rust output
+    /// writes them. Only available with the `serde_json` feature.
+    ///
+    /// [`gen_global()`]: #method.gen_global
+    /// [`gen_struct()`]: #method.gen_struct
+    pub fn describe_json<W: Write>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig) -> io::Result<()> {
+        use registry::VkType::*;
+
+        let preproc = GenPreproc::new(self, version, extensions, config);
+        let mut types = Vec::with_capacity(preproc.type_ord.len());
+        let mut constants = Vec::new();
+
+        for key in &preproc.type_ord {
+            let t = preproc.types.get(key).unwrap();
+            let name = match t.name() {
+                Some(name) => unsafe{ &*name },
+                None       => continue
+            };
+            let mut described = match *t {
+                Struct{ref fields, ..} =>
+                    json!({"kind": "struct", "fields": json_members(fields)}),
+                Union{ref variants, ..} =>
+                    json!({"kind": "union", "fields": json_members(variants)}),
+                Enum{ref variants, ..} =>
+                    json!({"kind": "enum", "variants": json_variants(variants)}),
+                Bitmask{ref variants, ..} =>
+                    json!({"kind": "bitmask", "variants": json_variants(variants)}),
+                Handle{dispatchable, ..} =>
+                    json!({"kind": "handle", "dispatchable": dispatchable}),
+                TypeDef{typ, ..} =>
+                    json!({"kind": "typedef", "type": unsafe{ &*typ }}),
+                Alias{alias_of, ..} =>
+                    json!({"kind": "alias", "type": unsafe{ &*alias_of }}),
+                FuncPointer{ref ret, ref params, ..} => {
+                    let params: Vec<_> = params.iter().map(json_param_type).collect();
+                    json!({"kind": "funcpointer", "params": params, "return": json_param_type(ret)})
+                }
+                ExternType{..} => json!({"kind": "extern"}),
+                ApiConst{value, ..} => {
+                    constants.push(json!({"name": name, "value": unsafe{ &*value }.trim()}));
+                    continue;
+                }
+                Define{..} | Unhandled => continue
+            };
+            described["name"] = json!(name);
+            described["raw_name"] = json!(key);
+            types.push(described);
+        }
+
+        let mut commands = Vec::with_capacity(preproc.commands.len());
+        for (c, r) in preproc.commands.iter().zip(preproc.commands_raw.iter()) {
+            let params: Vec<_> = c.params.iter()
+                .map(|p| json!({"name": unsafe{ &*p.name }, "type": json_param_type(&p.typ)}))
+                .collect();
+            let mut command = json!({
+                "name": unsafe{ &*c.name },
+                "raw_name": r,
+                "params": params,
+                "return": json_param_type(&c.ret)
+            });
+            if let Some(alias_of) = to_option(c.alias_of) {
+                command["alias_of"] = json!(alias_of);
+            }
+            commands.push(command);
+        }
+
+        write_document(write, &json!({
+            "types": types,
+            "commands": commands,
+            "constants": constants
+        }))
+    }
+}
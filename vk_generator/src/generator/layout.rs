@@ -0,0 +1,253 @@
+//! Computes the `#[repr(C)]` size and alignment of registry types, for the parts of the generator
+//! that need to know how a type is laid out before `rustc` does.
+//!
+//! Layouts are computed from the raw registry, so typedefs are followed down to their concrete
+//! type with `VkRegistry::resolve_concrete()`. 64-bit scalars are assumed to be 8-byte aligned.
+
+// Not everything here is used by the built-in generators, but it's all exposed to custom ones.
+#![cfg_attr(not(feature = "unstable_generator_api"), allow(dead_code))]
+use registry::*;
+use generator::GenRegistry;
+
+use std::{env, mem};
+
+/// The size and alignment of a type, in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeLayout {
+    pub size: usize,
+    pub align: usize
+}
+
+impl TypeLayout {
+    fn scalar(size: usize) -> TypeLayout {
+        TypeLayout {
+            size,
+            align: size
+        }
+    }
+
+    fn array(self, len: usize) -> TypeLayout {
+        TypeLayout {
+            size: self.size * len,
+            align: self.align
+        }
+    }
+}
+
+/// The size of a pointer on the target the bindings are being generated for. Inside of a build script
+/// that's the target cargo is building for, which isn't necessarily the one the generator runs on.
+pub fn target_pointer_size() -> usize {
+    env::var("CARGO_CFG_TARGET_POINTER_WIDTH").ok()
+        .and_then(|w| w.parse::<usize>().ok())
+        .map(|w| w / 8)
+        .unwrap_or(mem::size_of::<usize>())
+}
+
+/// Rounds `offset` up to the next multiple of `align`.
+// `usize::div_ceil` needs Rust 1.73, which is newer than this crate otherwise needs.
+#[allow(clippy::manual_div_ceil)]
+fn align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) / align * align
+}
+
+/// Get the layout of the registry type `name`. Returns `None` if the type has no layout (like
+/// `void`) or one that can't be known by the generator (like platform types from WSI extensions).
+pub fn type_layout(registry: &VkRegistry, name: &str, pointer_size: usize) -> Option<TypeLayout> {
+    use registry::VkType::*;
+
+    let name = registry.resolve_concrete(name);
+    match *registry.types().get(name)? {
+        Struct{ref fields, ..} => {
+            let mut offset = 0;
+            let mut align = 1;
+            for f in fields {
+                let field = el_layout(registry, &f.field_type, pointer_size)?;
+                offset = align_up(offset, field.align) + field.size;
+                align = align.max(field.align);
+            }
+            Some(TypeLayout{ size: align_up(offset, align), align })
+        }
+        Union{ref variants, ..} => {
+            let mut size = 0;
+            let mut align = 1;
+            for v in variants {
+                let variant = el_layout(registry, &v.field_type, pointer_size)?;
+                size = size.max(variant.size);
+                align = align.max(variant.align);
+            }
+            Some(TypeLayout{ size: align_up(size, align), align })
+        }
+        Enum{..}    => Some(TypeLayout::scalar(4)),
+        Bitmask{bitwidth, ..} => Some(TypeLayout::scalar(bitwidth as usize / 8)),
+        Handle{dispatchable, ..} =>
+            if dispatchable {
+                Some(TypeLayout::scalar(pointer_size))
+            } else {
+                Some(TypeLayout::scalar(8))
+            },
+        FuncPointer{..} => Some(TypeLayout::scalar(pointer_size)),
+        ExternType{requires, ..} if "vk_platform" == unsafe{ &*requires } =>
+            match name {
+                "char"     |
+                "int8_t"   |
+                "uint8_t"  => Some(TypeLayout::scalar(1)),
+                "int16_t"  |
+                "uint16_t" => Some(TypeLayout::scalar(2)),
+                "int"      |
+                "float"    |
+                "int32_t"  |
+                "uint32_t" => Some(TypeLayout::scalar(4)),
+                "double"   |
+                "int64_t"  |
+                "uint64_t" => Some(TypeLayout::scalar(8)),
+                "size_t"   => Some(TypeLayout::scalar(pointer_size)),
+                _          => None
+            },
+        _ => None
+    }
+}
+
+/// Get the layout of a struct member or union variant.
+pub fn el_layout(registry: &VkRegistry, el: &VkElType, pointer_size: usize) -> Option<TypeLayout> {
+    use registry::VkElType::*;
+
+    match *el {
+        Var(t)                   |
+        Const(t)                => type_layout(registry, unsafe{ &*t }, pointer_size),
+        ConstPtr(..)             |
+        MutPtr(..)              => Some(TypeLayout::scalar(pointer_size)),
+        ConstArray(t, len)       |
+        MutArray(t, len)        => type_layout(registry, unsafe{ &*t }, pointer_size).map(|l| l.array(len)),
+        ConstArrayEnum(t, len)   |
+        MutArrayEnum(t, len)    => {
+            let len = match *registry.types().get(unsafe{ &*len })? {
+                VkType::ApiConst{value, ..} => unsafe{ &*value }.parse::<usize>().ok()?,
+                _                           => return None
+            };
+            type_layout(registry, unsafe{ &*t }, pointer_size).map(|l| l.array(len))
+        }
+        Void                     |
+        Unknown                 => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generator::GenConfig;
+
+    const XML: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type requires="vk_platform" name="uint64_t"/>
+            <type category="basetype">typedef <type>uint32_t</type> <name>VkFlags</name>;</type>
+            <type category="bitmask">typedef <type>VkFlags</type> <name>VkFooFlags</name>;</type>
+            <type category="struct" name="VkFoo">
+                <member><type>VkFlags</type> <name>flags</name></member>
+                <member><type>VkFooFlags</type> <name>foo_flags</name></member>
+                <member><type>uint64_t</type> <name>size</name></member>
+            </type>
+        </types>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <type name="VkFoo"/>
+            </require>
+        </feature>
+    </registry>"#;
+
+    #[test]
+    fn typedef_emits_name_but_lays_out_concrete() {
+        let registry = VkRegistry::new(XML);
+        assert_eq!(registry.resolve_name("VkFlags"), "uint32_t");
+        assert_eq!(registry.resolve_concrete("VkFlags"), "uint32_t");
+        assert_eq!(registry.resolve_name("VkFooFlags"), "VkFlags");
+        assert_eq!(registry.resolve_concrete("VkFooFlags"), "uint32_t");
+        assert_eq!(registry.resolve_concrete("VkFoo"), "VkFoo");
+
+        let mut out = Vec::new();
+        registry.gen_struct(&mut out, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("pub flags: VkFlags,"));
+        assert!(out.contains("pub type VkFooFlags = VkFlags;"));
+
+        if let Some(VkType::Struct{fields, ..}) = registry.types().get("VkFoo") {
+            assert_eq!(el_layout(&registry, &fields[0].field_type, 8), Some(TypeLayout{ size: 4, align: 4 }));
+        } else {panic!("VkFoo not a struct")}
+        assert_eq!(type_layout(&registry, "VkFooFlags", 8), Some(TypeLayout{ size: 4, align: 4 }));
+        assert_eq!(type_layout(&registry, "VkFoo", 8), Some(TypeLayout{ size: 16, align: 8 }));
+    }
+
+    #[test]
+    fn array_of_structs() {
+        let registry = VkRegistry::new(br#"<registry>
+            <types>
+                <type requires="vk_platform" name="uint32_t"/>
+                <type requires="vk_platform" name="uint64_t"/>
+                <type category="struct" name="VkInner">
+                    <member><type>uint32_t</type> <name>a</name></member>
+                    <member><type>uint64_t</type> <name>b</name></member>
+                </type>
+                <type category="struct" name="VkOuter">
+                    <member><type>uint32_t</type> <name>count</name></member>
+                    <member><type>VkInner</type> <name>inners</name>[3]</member>
+                    <member>const <type>VkInner</type> <name>constInners</name>[<enum>VK_MAX_INNERS</enum>]</member>
+                </type>
+            </types>
+            <enums name="API Constants">
+                <enum value="2" name="VK_MAX_INNERS"/>
+            </enums>
+            <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+                <require>
+                    <type name="VkOuter"/>
+                </require>
+            </feature>
+        </registry>"#);
+
+        let mut out = Vec::new();
+        registry.gen_struct(&mut out, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("pub inners: [VkInner; 3],"));
+        assert!(out.contains("pub const_inners: [VkInner; MAX_INNERS],"));
+        assert!(out.contains("inners: self.inners,"));
+
+        assert_eq!(type_layout(&registry, "VkInner", 8), Some(TypeLayout{ size: 16, align: 8 }));
+        // 4 bytes of `count`, 4 bytes of padding, and then five `VkInner`s.
+        assert_eq!(type_layout(&registry, "VkOuter", 8), Some(TypeLayout{ size: 8 + 5 * 16, align: 8 }));
+    }
+
+    #[test]
+    fn native_union_forced_align() {
+        let registry = VkRegistry::new(br#"<registry>
+            <types>
+                <type requires="vk_platform" name="uint32_t"/>
+                <type requires="vk_platform" name="uint64_t"/>
+                <type category="union" name="VkNarrow">
+                    <member><type>uint32_t</type> <name>a</name></member>
+                    <member><type>uint32_t</type> <name>b</name>[2]</member>
+                </type>
+                <type category="union" name="VkWide">
+                    <member><type>uint32_t</type> <name>a</name></member>
+                    <member><type>uint64_t</type> <name>b</name></member>
+                </type>
+            </types>
+            <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+                <require>
+                    <type name="VkNarrow"/>
+                    <type name="VkWide"/>
+                </require>
+            </feature>
+        </registry>"#);
+
+        let mut out = Vec::new();
+        registry.gen_struct(&mut out, VkVersion(1, 0), &[], GenConfig {
+            use_native_unions: true,
+            ..GenConfig::default()
+        }).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("#[repr(C)]\n#[derive(Clone, Copy)]\npub union VkNarrow {"));
+        assert!(out.contains("#[repr(C, align(8))]\n#[derive(Clone, Copy)]\npub union VkWide {"));
+
+        assert_eq!(type_layout(&registry, "VkNarrow", 8), Some(TypeLayout{ size: 8, align: 4 }));
+        assert_eq!(type_layout(&registry, "VkWide", 8), Some(TypeLayout{ size: 8, align: 8 }));
+    }
+}
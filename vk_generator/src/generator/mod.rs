@@ -2,12 +2,19 @@ use to_option;
 use registry::*;
 use std::collections::{HashMap, HashSet};
 use std::iter::Iterator;
+use std::ops::Range;
+use std::borrow::Cow;
 use std::default;
-use std::fmt::Write as FmtWrite;
-use std::io::Write;
+use std::fmt::{self, Write as FmtWrite};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
 
 use boolinator::Boolinator;
 
+pub mod layout;
+#[cfg(feature = "proc-macro2")]
+mod tokens;
+
 /// Configuration for handling enum variants.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VariantPaddingConfig {
@@ -19,6 +26,125 @@ pub enum VariantPaddingConfig {
     Strip,
 }
 
+/// Visibility of the items emitted by the generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// Emit items as `pub`.
+    Public,
+    /// Emit items as `pub(crate)`, keeping the bindings internal to the crate that includes them.
+    PubCrate,
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Visibility::Public   => write!(f, "pub"),
+            Visibility::PubCrate => write!(f, "pub(crate)")
+        }
+    }
+}
+
+/// The crates the generated code reaches the standard library through: `std` for all of them, or
+/// `core` and `alloc` if `no_std` is set.
+#[derive(Debug, Clone, Copy)]
+struct StdPaths {
+    /// The crate holding `mem`, `fmt`, `ptr` and the rest of what's in `core`.
+    core: &'static str,
+    /// The crate holding `Vec`.
+    alloc: &'static str,
+    /// The module holding the C types, like `c_char`.
+    c_types: &'static str
+}
+
+/// How the generated `load_with()` functions report the commands that couldn't be loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadReporting {
+    /// Return the names of the unloaded commands, as `Result<(), Vec<&'static str>>`.
+    Full,
+    /// Return the number of unloaded commands, as `Result<(), usize>`.
+    CountOnly,
+    /// Only return whether or not every command was loaded, as `Result<(), ()>`.
+    None,
+}
+
+impl LoadReporting {
+    /// The name of the `vk_load_report!` arm for this mode.
+    fn macro_arm(self) -> &'static str {
+        match self {
+            LoadReporting::Full      => "Full",
+            LoadReporting::CountOnly => "CountOnly",
+            LoadReporting::None      => "None"
+        }
+    }
+}
+
+/// Which of the generated types `write_types()` emits, for splitting the bindings across crates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypesSubset {
+    /// Emit every type.
+    All,
+    /// Only emit the enums, bitmasks and API constants, along with the base typedefs they rely on.
+    EnumsAndConstsOnly,
+    /// Only emit the structs, unions, handles and function pointers, along with the base typedefs they
+    /// rely on. The enums and constants they use have to be brought into scope before the bindings
+    /// are included, like with a `pub use` of a crate generated with `EnumsAndConstsOnly`.
+    StructsOnly,
+}
+
+impl TypesSubset {
+    fn enums_and_consts(self) -> bool {
+        self != TypesSubset::StructsOnly
+    }
+
+    fn structs(self) -> bool {
+        self != TypesSubset::EnumsAndConstsOnly
+    }
+}
+
+/// What to do with a single enum or bitmask variant picked out by `GenConfig::variant_overrides`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantAction<'a> {
+    /// Emit the variant under this name. The name is used as-is, without any of the prefix removal
+    /// or casing the generator would otherwise apply.
+    Rename(&'a str),
+    /// Leave the variant out of the generated bindings entirely.
+    Remove,
+}
+
+/// The kinds of identifiers that `GenConfig::rename` gets passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentKind {
+    Command,
+    /// Structs, unions, enums, bitmasks, handles, typedefs and function pointers.
+    Type,
+    /// Enum and bitmask variants.
+    Variant,
+    /// Struct and union fields, along with command parameters.
+    Member,
+    /// API and extension constants.
+    Constant,
+}
+
+/// A function for `GenConfig::rename`, which takes an identifier after the generator has processed
+/// it along with what kind of identifier it is, and returns the name to use instead. Renamers are
+/// compared by address.
+#[derive(Clone, Copy)]
+pub struct Renamer<'a>(pub &'a dyn Fn(&str, IdentKind) -> String);
+
+impl<'a> fmt::Debug for Renamer<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Renamer({:p})", self.0)
+    }
+}
+
+impl<'a> PartialEq for Renamer<'a> {
+    fn eq(&self, other: &Renamer<'a>) -> bool {
+        ::std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl<'a> Eq for Renamer<'a> {}
+
 /// Configuration options fot the Vulkan generator.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct GenConfig<'a> {
@@ -95,6 +221,43 @@ pub struct GenConfig<'a> {
     ///
     /// Defaults to `true`.
     pub use_native_enums: bool,
+    /// Whether or not to wrap enums in an `i32` newtype when `use_native_enums` is `false`, with the
+    /// variants as associated constants. Unlike native enums this can hold any value Vulkan might
+    /// return, and unlike a plain integer typedef it keeps the enums distinct types. The newtypes are
+    /// `#[repr(transparent)]` and derive the same traits native enums do, aside from `Debug` which
+    /// prints the variant name.
+    ///
+    /// For example, setting this to `true` turns `VkResult` into `pub struct VkResult(pub i32)`, with
+    /// `VkResult::Success` as one of its constants.
+    ///
+    /// Defaults to `false`.
+    pub newtype_enums: bool,
+    /// Whether or not to implement `PartialEq<i32>` for newtype enums (and the reverse), so that they
+    /// can be compared against raw integers without going through `.0`. Has no effect unless
+    /// `newtype_enums` is in use. Setting this to `false` keeps the enums strictly typed.
+    ///
+    /// Defaults to `true`.
+    pub enum_int_eq: bool,
+    /// Whether or not to implement `Display` for native and newtype enums, printing the raw name of the
+    /// variant (like `VK_ERROR_DEVICE_LOST`). Aliased variants print as the variant they alias, and
+    /// values that aren't a known variant print as the integer. Has no effect on enums generated as
+    /// plain integer constants.
+    ///
+    /// Defaults to `false`.
+    pub impl_display_enums: bool,
+    /// Whether or not to implement `TryFrom<i32>` for native enums, so that raw values read back from
+    /// Vulkan can be checked before they're turned into an enum. Values that aren't a variant of the
+    /// enum are returned as the `Err`. Requires `use_native_enums`, since the other kinds of enums can
+    /// already hold any value.
+    ///
+    /// Defaults to `false`.
+    pub impl_tryfrom_enums: bool,
+    /// Whether or not to give native and newtype enums an associated `ALL` constant, a slice of every
+    /// variant in the order they're declared in. Aliases are left out so each value is only listed
+    /// once. Has no effect on enums generated as plain integer constants.
+    ///
+    /// Defaults to `false`.
+    pub enum_iter: bool,
     /// Whether or not to use the unstable `union` keyword to create native untagged unions.
     /// Currently relies on the `untagged_unions` feature.
     ///
@@ -106,17 +269,257 @@ pub struct GenConfig<'a> {
     ///
     /// Defaults to `true`.
     pub wrap_bitmasks: bool,
+    /// Whether or not to wrap bitmasks with the `bitflags!` macro from the
+    /// [bitflags](https://crates.io/crates/bitflags) crate (version 1.x) instead of the generator's
+    /// own macro. The flags become associated constants of the wrapper, and are also exported as
+    /// free constants like usual. Enabling this requires adding `bitflags` to the `Cargo.toml` of the
+    /// crate that includes the bindings. Has no effect unless `wrap_bitmasks` is `true`.
+    ///
+    /// Defaults to `false`.
+    pub bitflags_crate: bool,
     /// Whether or not to wrap Vulkan's non-dispatchable (i.e. integer) object handles in a wrapper
     /// struct.
     ///
     /// Defaults to `true`.
     pub wrap_non_dispatchable_handles: bool,
+    /// Whether or not to wrap Vulkan's dispatchable (i.e. pointer) object handles, like `VkInstance`
+    /// and `VkDevice`, in a wrapper struct. Like the non-dispatchable wrappers, these have a `NULL`
+    /// constant, and convert to and from their raw address with `as_raw()` and the `unsafe`
+    /// `from_raw()`. If this is `false` they're raw pointers, which can't have any of those.
+    ///
+    /// Defaults to `false`.
+    pub wrap_dispatchable_handles: bool,
+    /// Whether or not to build the non-dispatchable handle wrappers around a `NonZeroU64`, so that
+    /// `Option<Handle>` is the same size as the handle. Every place a handle is used is then typed as
+    /// `Option<Handle>`, with `None` standing in for `VK_NULL_HANDLE`. Handles can be converted to and
+    /// from their raw values with `as_raw()` and `from_raw()`.
+    ///
+    /// Only has an effect if `wrap_non_dispatchable_handles` is `true`. Defaults to `false`.
+    pub niche_handles: bool,
     /// The Vulkan library uses a lot of `C` types, as per it's nature of exposing a `C` ABI. There are
     /// a few ways we can handle using those types: either we can define the typedefs ourself or we can
     /// use the types provided by `libc`. Because `libc` isn't implicitly included in crates we default
     /// to defining the types ourself. Setting this to `true` makes the generated file import types
     /// from `libc` instead of defining them itself.
     pub use_libc_types: bool,
+    /// Whether or not to write the bindings so that they can be included in a `#![no_std]` crate, going
+    /// through `core` instead of `std`. `LoadReporting::Full` still needs a `Vec`, which comes from
+    /// `alloc`, so the including crate has to declare `extern crate alloc` unless `load_reporting` is
+    /// set to something that doesn't allocate.
+    ///
+    /// Defaults to `false`.
+    pub no_std: bool,
+    /// Whether or not to generate `OFFSET_<field>` associated constants on each struct, holding the
+    /// byte offset of that field in the same way as C's `offsetof`. These are useful when packing
+    /// buffers whose layout has to match a Vulkan struct.
+    ///
+    /// For example, setting this to `true` generates `VkApplicationInfo::OFFSET_p_next`, which is
+    /// equal to `8` on 64-bit targets due to the padding after `s_type`.
+    ///
+    /// Defaults to `false`.
+    pub field_offsets: bool,
+    /// Whether or not to generate a `Default` implementation for each struct, which zeroes every
+    /// field, so `pNext` is always null, and sets `sType` to the struct's own `VkStructureType`. That
+    /// makes it possible to write `VkApplicationInfo { apiVersion: ..., ..Default::default() }`.
+    /// Structs with a field that can't be zeroed, like a required function pointer, don't get an
+    /// implementation.
+    ///
+    /// Defaults to `false`.
+    pub derive_default: bool,
+    /// Whether or not to generate builders for assembling `pNext` chains. Every struct that another
+    /// struct can extend gets an `Extends<Struct>` trait, implemented by the structs that can go in
+    /// its chain, and a `<Struct>Builder` with a `push_next()` function that only accepts those.
+    ///
+    /// For example, setting this to `true` lets a `VkValidationFlagsEXT` be chained onto an
+    /// instance's create info with `create_info.builder().push_next(&mut validation_flags)`.
+    ///
+    /// Defaults to `false`.
+    pub chain_builders: bool,
+    /// Whether or not to generate unchecked `pNext` chaining for every struct with an `sType` and a
+    /// `pNext`. Those structs implement a `StructureChain` trait, with their `sType` value as
+    /// `STRUCTURE_TYPE`, and get an `unsafe` `link_next()` function that appends any other
+    /// `StructureChain` struct to the end of their chain. Unlike `chain_builders`, nothing checks that
+    /// the linked struct is allowed in the chain or that it outlives it.
+    ///
+    /// Defaults to `false`.
+    pub gen_chain_helpers: bool,
+    /// Whether or not to generate a mapping between structs and their `sType` values. Every struct
+    /// with an `sType` gets its value as a `STRUCTURE_TYPE` associated constant, and the whole mapping
+    /// is written out as `STRUCTURE_TYPES`, a slice of `(sType, struct name)` pairs, alongside a
+    /// `structure_type_name()` function that looks up the name of the struct an `sType` denotes.
+    ///
+    /// Defaults to `false`.
+    pub structure_type_table: bool,
+    /// Whether or not to generate a `new()` constructor for every struct with an `sType`, which sets
+    /// `sType`, leaves `pNext` null and takes the fields that the registry doesn't mark as optional as
+    /// arguments. Optional fields are zeroed. A `uint32_t` count followed by a pointer named after it,
+    /// like `queueCount` and `pQueuePriorities`, is taken as a single slice argument.
+    ///
+    /// For example, setting this to `true` generates `VkDeviceQueueCreateInfo::new(0, &[1.0])`. The
+    /// struct only holds pointers to the slices, so they have to outlive any use of it. Structs with a
+    /// field that can't be zeroed don't get a constructor.
+    ///
+    /// Defaults to `false`.
+    pub gen_constructors: bool,
+    /// Whether or not to generate helper functions for the small geometry structs: a `new()`
+    /// constructor and component-wise `min()` and `max()` for `VkExtent2D`, `VkExtent3D`,
+    /// `VkOffset2D` and `VkOffset3D`, and `new()`, `from_extent()`, `contains()` and
+    /// `contains_rect()` for `VkRect2D`. The 2D extents and offsets also convert `Into` their 3D
+    /// counterparts, with a depth of 1 and a `z` of 0.
+    ///
+    /// Defaults to `false`.
+    pub geometry_helpers: bool,
+    /// Whether or not to generate named accessors for the small fixed-size arrays in
+    /// `VkPhysicalDeviceLimits`. Arrays of two or three components get `_x`, `_y` and `_z` getters
+    /// (like `max_compute_work_group_count_x()`), except for the `*Range` arrays, which get `_min`
+    /// and `_max` getters.
+    ///
+    /// Defaults to `false`.
+    pub limit_accessors: bool,
+    /// Whether or not to generate `FEATURES_NONE` and `FEATURES_ALL` constants, which are
+    /// `VkPhysicalDeviceFeatures` structs with every feature turned off or on. They make a known
+    /// starting point for the features passed to `vkCreateDevice`.
+    ///
+    /// Defaults to `false`.
+    pub feature_presets: bool,
+    /// Whether or not to append a `#[cfg(test)] mod generated_abi_tests` to the bindings, which checks
+    /// the size of every handle and base typedef, and the size and alignment of every struct and union
+    /// whose layout the generator can work out. Running `cargo test` on the crate that includes the
+    /// bindings then catches a regeneration that got the ABI wrong.
+    ///
+    /// The expected layouts are worked out for the target the generator is run for, so inside of a
+    /// build script they match the target being built.
+    ///
+    /// Defaults to `false`.
+    pub emit_self_tests: bool,
+    /// Whether or not to implement a `VkHandle` trait for every handle, which holds the handle's
+    /// `VkObjectType` as `OBJECT_TYPE` and converts the handle to the `u64` that debug utilities take
+    /// with `as_raw()`. This allows functions like object naming to be written once for all handles.
+    /// If this is `false`, handles implement a `HasObjectType` trait with just `OBJECT_TYPE` instead.
+    ///
+    /// Handles only get either trait if they are distinct types, so neither is implemented for
+    /// non-dispatchable handles if `wrap_non_dispatchable_handles` is `false`.
+    ///
+    /// Defaults to `false`.
+    pub handle_trait: bool,
+    /// How `load_with()` reports the commands that the loader couldn't find. The default collects
+    /// their names into a `Vec`, which allocation-sensitive users can opt out of by just counting them
+    /// or not reporting them at all.
+    ///
+    /// Defaults to `LoadReporting::Full`.
+    pub load_reporting: LoadReporting,
+    /// Whether or not to generate a `VkCommands` trait with a method for every command, implemented
+    /// by `Vk` by forwarding to its own methods. Code that's generic over the trait, or that takes a
+    /// `&dyn VkCommands`, can then be run against a mock implementation in tests. Only `gen_struct()`
+    /// and `gen_struct_filtered()` generate the trait.
+    ///
+    /// Defaults to `false`.
+    pub wrap_commands_in_trait: bool,
+    /// Whether or not to generate a `<command>_is_success()` function for every command that returns
+    /// a `VkResult`, which checks a result against the success codes documented for that command.
+    /// Commands differ in which non-error results they can return, so this lets callers branch on
+    /// results like `VK_INCOMPLETE` for the commands that actually use them.
+    ///
+    /// For example, setting this to `true` generates `enumerate_physical_devices_is_success()`, which
+    /// is `true` for both `VK_SUCCESS` and `VK_INCOMPLETE`.
+    ///
+    /// Defaults to `false`.
+    pub per_command_success_checks: bool,
+    /// Whether or not the command bindings should take `VkBool32` parameters as a `bool`, converting
+    /// them to `VK_TRUE` or `VK_FALSE` when calling the command.
+    ///
+    /// Defaults to `false`.
+    pub bool_params: bool,
+    /// Whether or not the struct bindings should get a `<command>_slice()` wrapper for each command
+    /// that takes an array as a `uint32_t` count followed by a pointer, with the wrapper taking a
+    /// slice in place of the pair. Pairs are recognized by the pointer being named after the count,
+    /// like `regionCount` and `pRegions`. The raw commands are still generated alongside them.
+    ///
+    /// For example, setting this to `true` generates `vk.cmd_set_viewport_slice(command_buffer, 0,
+    /// &viewports)`. The wrappers are still `unsafe`, since the rest of the parameters go to the
+    /// command unchecked. Has no effect on the global bindings.
+    ///
+    /// Defaults to `false`.
+    pub safe_slice_wrappers: bool,
+    /// Whether or not to generate a `<command>_checked()` wrapper for every command that returns a
+    /// `VkResult`, which returns `Ok` if the result is one of the success codes documented for that
+    /// command and `Err` with the result otherwise, so that `VK_INCOMPLETE` counts as success for
+    /// `vkEnumeratePhysicalDevices` but not for `vkCreateInstance`. The `Err` is built in a `#[cold]`
+    /// function, since commands almost always succeed. If the command's last parameter is a lone
+    /// out-pointer, like the `pInstance` of `vkCreateInstance`, the wrapper doesn't take it and
+    /// returns what the command wrote there in the `Ok` instead.
+    ///
+    /// For example, setting this to `true` generates `vk.create_instance_checked(&info, ptr::null())`,
+    /// which returns a `Result<VkInstance, VkResult>`. The global bindings get them as free functions.
+    ///
+    /// Defaults to `false`.
+    pub result_wrappers: bool,
+    /// Whether or not to document the parameters of each command that have to be externally
+    /// synchronized, and those with valid usage rules that the registry says can't be checked
+    /// automatically. For example, the docs of `vkQueueWaitIdle` say that `queue` is externally synchronized.
+    ///
+    /// Defaults to `false`.
+    pub param_docs: bool,
+    /// Whether or not to turn the registry's `comment` attributes on structs, unions, enums, bitmasks,
+    /// their variants and commands into doc comments on the generated items.
+    ///
+    /// Defaults to `false`.
+    pub emit_docs: bool,
+    /// Whether or not to mark the items of extensions that the registry says were promoted,
+    /// deprecated or obsoleted as `#[deprecated]`, with a note saying what replaced them, like
+    /// `#[deprecated(note = "promoted to VK_VERSION_1_1")]`. This covers the extensions' commands,
+    /// their `_EXTENSION_NAME` and `_SPEC_VERSION` constants and their `group_by_extension` modules,
+    /// but not commands that a requested extension that isn't deprecated requires as well. Type
+    /// aliases that the registry marks as deprecated are pointed at the type they alias.
+    ///
+    /// Defaults to `false`.
+    pub emit_deprecated: bool,
+    /// Whether or not to place the API constants, like `VK_MAX_EXTENSION_NAME_SIZE`, in their own
+    /// `consts` module next to the `types` and `cmds` modules. The constants are still re-exported
+    /// from the root of the bindings either way.
+    ///
+    /// Defaults to `false`.
+    pub consts_module: bool,
+    /// Whether or not to give each requested extension a module named after it, like
+    /// `vk_khr_surface`, re-exporting the constants and types it introduces, along with its commands
+    /// when generating global bindings and its enum variants when those are generated as constants.
+    /// Everything is still defined once at the root of the bindings, so that the shared types and the
+    /// loaders stay where they are.
+    ///
+    /// Defaults to `false`.
+    pub group_by_extension: bool,
+    /// Whether or not to put the items that platform-specific extensions introduce, like the types
+    /// and commands of `VK_KHR_win32_surface`, behind a `#[cfg]` for the targets that have that
+    /// platform, such as `#[cfg(windows)]`. The bindings for every platform can then be generated once
+    /// and compiled anywhere. Extension names and enum variants are left in, and platforms without an
+    /// obvious Rust target (like `vi`) aren't gated.
+    ///
+    /// Defaults to `false`.
+    pub platform_cfgs: bool,
+    /// Whether or not to define the types that come from platform headers, like `HWND` from
+    /// `windows.h` or `Display` from `X11/Xlib.h`, the way those headers do. Handles are opaque
+    /// structs or `c_void` pointers, and IDs are the integers they're typedef'd to. Otherwise, they're
+    /// all the blind `*const ()` described in `extern_type_overrides`, which takes precedence over this.
+    /// The definitions are only behind a `#[cfg]` if `platform_cfgs` is enabled as well.
+    ///
+    /// Defaults to `false`.
+    pub define_platform_types: bool,
+    /// Whether or not to generate a `ResultSeverity` enum and a `severity()` function that sorts a
+    /// `VkResult` into `Success`, `Warning` or `Error`. Codes are classified by their sign, the same
+    /// way the specification does, so codes added by extensions are handled without being listed out.
+    ///
+    /// Defaults to `false`.
+    pub result_severity: bool,
+    /// Which types to emit. Pairs with `gen_types_only()` to split the bindings into several crates.
+    ///
+    /// Defaults to `TypesSubset::All`.
+    pub types_subset: TypesSubset,
+    /// The visibility given to every item the generator emits. Setting this to
+    /// `Visibility::PubCrate` keeps the bindings from leaking into the public API of the crate that
+    /// includes them.
+    ///
+    /// Defaults to `Visibility::Public`.
+    pub visibility: Visibility,
     /// This defines a set of type overrides, primarily intended for use with the WSI extensions. It
     /// takes a slice of (&str, &str) tuples, with the left side being the name of the type and the
     /// right side being the new definition of the type.
@@ -153,7 +556,74 @@ pub struct GenConfig<'a> {
     /// type HWND = winapi::HWND;
     /// type HINSTANCE = winapi::HINSTANCE;
     /// ```
-    pub extern_type_overrides: &'a [(&'a str, &'a str)]
+    pub extern_type_overrides: &'a [(&'a str, &'a str)],
+    /// A set of overrides for individual enum and bitmask variants, for when the generated name of
+    /// one variant collides with another's (or is otherwise unfortunate) and changing the global
+    /// variant settings isn't an option. It takes a slice of (&str, &str, VariantAction) tuples, with
+    /// the first two elements being the registry names of the enum and of the variant.
+    ///
+    /// ```
+    /// # use vk_generator::{GenConfig, VariantAction};
+    /// let config = GenConfig {
+    ///     variant_overrides: &[
+    ///         ("VkResult", "VK_ERROR_OUT_OF_POOL_MEMORY_KHR", VariantAction::Remove),
+    ///         ("VkFormat", "VK_FORMAT_UNDEFINED", VariantAction::Rename("Unknown"))
+    ///     ],
+    ///     ..GenConfig::default()
+    /// };
+    /// ```
+    ///
+    /// Defaults to `&[]`.
+    pub variant_overrides: &'a [(&'a str, &'a str, VariantAction<'a>)],
+    /// The path from the crate root to the module the bindings get included into, like `"vk"` or
+    /// `"ffi::vk"`. If this is set, the exported `vk_make_version!` family of macros refer to the
+    /// generated types through `$crate::<path>`, so that they work when called from any module.
+    /// Otherwise the types are left unqualified and have to be in scope wherever the macros are used.
+    ///
+    /// Defaults to `None`.
+    pub macro_root: Option<&'a str>,
+    /// A prefix to put in front of the name of every generated type, constant and command, after the
+    /// other naming options have been applied. For example, with a prefix of `"ext_"` the struct
+    /// `VkInstanceCreateInfo` becomes `ext_VkInstanceCreateInfo` and the command `vkCreateInstance`
+    /// becomes `ext_create_instance`. This lets two sets of bindings be glob-imported next to each
+    /// other without their names colliding.
+    ///
+    /// Types from C headers, like `uint32_t` or `HWND`, and the generator's own helper items, like
+    /// `Vk` and `VkCommands`, keep their names.
+    ///
+    /// Defaults to `None`.
+    pub symbol_prefix: Option<&'a str>,
+    /// A function to rename the generated commands, types, variants, members and constants with,
+    /// for naming conventions that the other options don't cover. It gets each identifier once the
+    /// other naming options have been applied, except for `symbol_prefix`, which still goes in front
+    /// of what it returns. Types from C headers and variants renamed by `variant_overrides` aren't
+    /// passed to it. For example, this uppercases every type name:
+    ///
+    /// ```
+    /// # use vk_generator::{GenConfig, IdentKind, Renamer};
+    /// let uppercase = |name: &str, kind| match kind {
+    ///     IdentKind::Type => name.to_uppercase(),
+    ///     _               => name.to_owned()
+    /// };
+    /// let config = GenConfig {
+    ///     rename: Some(Renamer(&uppercase)),
+    ///     ..GenConfig::default()
+    /// };
+    /// ```
+    ///
+    /// Generation panics if the function returns something that isn't a legal identifier, or that
+    /// is a keyword. Members are the exception, since keywords are escaped for them like they are
+    /// for the names in the registry.
+    ///
+    /// Defaults to `None`.
+    pub rename: Option<Renamer<'a>>,
+    /// Whether or not to check, once the types and commands to generate have been picked out, that
+    /// every type they reference gets generated as well. If one doesn't, generation returns an
+    /// `InvalidData` error naming the items holding the dangling references and the types they're
+    /// missing, instead of leaving them for `rustc` to trip over. Nothing is written in that case.
+    ///
+    /// Defaults to `false`.
+    pub strict: bool
 }
 
 impl<'a> GenConfig<'a> {
@@ -161,6 +631,28 @@ impl<'a> GenConfig<'a> {
     pub fn new() -> Self {
         Default::default()
     }
+
+    fn std_paths(&self) -> StdPaths {
+        match self.no_std {
+            false => StdPaths{ core: "std", alloc: "std", c_types: "std::os::raw" },
+            true  => StdPaths{ core: "core", alloc: "alloc", c_types: "core::ffi" }
+        }
+    }
+
+    /// Start building a config from the defaults, setting only the options that differ from them:
+    ///
+    /// ```
+    /// # use vk_generator::GenConfig;
+    /// let config = GenConfig::builder()
+    ///     .remove_type_prefix(true)
+    ///     .wrap_bitmasks(false)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(config.remove_type_prefix && !config.wrap_bitmasks);
+    /// ```
+    pub fn builder() -> GenConfigBuilder<'a> {
+        GenConfigBuilder{ config: GenConfig::default() }
+    }
 }
 
 impl<'a> default::Default for GenConfig<'a> {
@@ -177,12 +669,162 @@ impl<'a> default::Default for GenConfig<'a> {
             snake_case_members: true,
             debug_c_strings: true,
             use_native_enums: true,
+            newtype_enums: false,
+            enum_int_eq: true,
+            impl_display_enums: false,
+            impl_tryfrom_enums: false,
+            enum_iter: false,
             use_native_unions: false,
 
             wrap_bitmasks: true,
+            bitflags_crate: false,
             wrap_non_dispatchable_handles: true,
+            wrap_dispatchable_handles: false,
+            niche_handles: false,
             use_libc_types: false,
-            extern_type_overrides: &[]
+            no_std: false,
+            field_offsets: false,
+            derive_default: false,
+            chain_builders: false,
+            gen_chain_helpers: false,
+            structure_type_table: false,
+            gen_constructors: false,
+            geometry_helpers: false,
+            limit_accessors: false,
+            feature_presets: false,
+            emit_self_tests: false,
+            handle_trait: false,
+            load_reporting: LoadReporting::Full,
+            wrap_commands_in_trait: false,
+            per_command_success_checks: false,
+            bool_params: false,
+            safe_slice_wrappers: false,
+            result_wrappers: false,
+            param_docs: false,
+            emit_docs: false,
+            emit_deprecated: false,
+            consts_module: false,
+            group_by_extension: false,
+            platform_cfgs: false,
+            define_platform_types: false,
+            result_severity: false,
+            types_subset: TypesSubset::All,
+            visibility: Visibility::Public,
+            extern_type_overrides: &[],
+            variant_overrides: &[],
+            macro_root: None,
+            symbol_prefix: None,
+            rename: None,
+            strict: false
+        }
+    }
+}
+
+/// A builder for [`GenConfig`], created with [`GenConfig::builder()`]. There's a setter for every
+/// field of the config, named after the field.
+///
+/// [`GenConfig`]: ./struct.GenConfig.html
+/// [`GenConfig::builder()`]: ./struct.GenConfig.html#method.builder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenConfigBuilder<'a> {
+    config: GenConfig<'a>
+}
+
+macro_rules! gen_config_setters {
+    ($($field: ident: $typ: ty),*) => {$(
+        #[doc = concat!("Set [`GenConfig::", stringify!($field), "`](./struct.GenConfig.html#structfield.", stringify!($field), ").")]
+        pub fn $field(&mut self, $field: $typ) -> &mut Self {
+            self.config.$field = $field;
+            self
+        }
+    )*}
+}
+
+impl<'a> GenConfigBuilder<'a> {
+    gen_config_setters!{
+        remove_type_prefix: bool,
+        remove_vk_result_prefix: bool,
+        remove_command_prefix: bool,
+        remove_bitmask_prefix: bool,
+        remove_const_prefix: bool,
+        variant_padding: VariantPaddingConfig,
+        snake_case_commands: bool,
+        camel_case_variants: bool,
+        snake_case_members: bool,
+        debug_c_strings: bool,
+        use_native_enums: bool,
+        newtype_enums: bool,
+        enum_int_eq: bool,
+        impl_display_enums: bool,
+        impl_tryfrom_enums: bool,
+        enum_iter: bool,
+        use_native_unions: bool,
+        wrap_bitmasks: bool,
+        bitflags_crate: bool,
+        wrap_non_dispatchable_handles: bool,
+        wrap_dispatchable_handles: bool,
+        niche_handles: bool,
+        use_libc_types: bool,
+        no_std: bool,
+        field_offsets: bool,
+        derive_default: bool,
+        chain_builders: bool,
+        gen_chain_helpers: bool,
+        structure_type_table: bool,
+        gen_constructors: bool,
+        geometry_helpers: bool,
+        limit_accessors: bool,
+        feature_presets: bool,
+        emit_self_tests: bool,
+        handle_trait: bool,
+        load_reporting: LoadReporting,
+        wrap_commands_in_trait: bool,
+        per_command_success_checks: bool,
+        bool_params: bool,
+        safe_slice_wrappers: bool,
+        result_wrappers: bool,
+        param_docs: bool,
+        emit_docs: bool,
+        emit_deprecated: bool,
+        consts_module: bool,
+        group_by_extension: bool,
+        platform_cfgs: bool,
+        define_platform_types: bool,
+        result_severity: bool,
+        types_subset: TypesSubset,
+        visibility: Visibility,
+        extern_type_overrides: &'a [(&'a str, &'a str)],
+        variant_overrides: &'a [(&'a str, &'a str, VariantAction<'a>)],
+        macro_root: Option<&'a str>,
+        symbol_prefix: Option<&'a str>,
+        rename: Option<Renamer<'a>>,
+        strict: bool
+    }
+
+    /// Finish the config. Returns an error describing the problem if it sets options that contradict
+    /// each other:
+    ///
+    /// * `newtype_enums` with `use_native_enums`, since the newtypes replace the native enums.
+    /// * `bitflags_crate` without `wrap_bitmasks`, since it changes how bitmasks are wrapped.
+    /// * `niche_handles` without `wrap_non_dispatchable_handles`, since it changes how handles are
+    ///   wrapped.
+    /// * `impl_tryfrom_enums` without `use_native_enums`, since only native enums can't hold every
+    ///   value.
+    ///
+    /// Configs written out as struct literals aren't checked, and just ignore the option that has no
+    /// effect.
+    pub fn build(&self) -> Result<GenConfig<'a>, &'static str> {
+        let config = self.config;
+        if config.newtype_enums && config.use_native_enums {
+            Err("`newtype_enums` requires `use_native_enums` to be `false`")
+        } else if config.bitflags_crate && !config.wrap_bitmasks {
+            Err("`bitflags_crate` requires `wrap_bitmasks` to be `true`")
+        } else if config.niche_handles && !config.wrap_non_dispatchable_handles {
+            Err("`niche_handles` requires `wrap_non_dispatchable_handles` to be `true`")
+        } else if config.impl_tryfrom_enums && !config.use_native_enums {
+            Err("`impl_tryfrom_enums` requires `use_native_enums` to be `true`")
+        } else {
+            Ok(config)
         }
     }
 }
@@ -200,58 +842,191 @@ pub struct GenPreproc<'a, 'b> {
     /// the type contains an array, and as such can't derive Clone (because arrays only implement Clone
     /// if the types they contain are Copy, which all of the generated types aren't) and Debug.
     pub custom_impls: HashSet<&'a str>,
+    /// The processed names of the generated enum and bitmask variants, keyed by their registry names.
+    pub variant_names: HashMap<&'a str, *const str>,
     pub commands: Vec<VkCommand>,
     /// A vector of the unprocessed command names
     pub commands_raw: Vec<&'a str>,
+    /// The processed names of the `<extension>_EXTENSION_NAME` constants of the included extensions
+    pub extension_names: Vec<&'a str>,
+    /// The included extensions, in the order they were requested in, along with the range of
+    /// `type_ord` holding the types and constants that each of them was the first to pull in.
+    pub extensions: Vec<(&'a VkExtn, Range<usize>)>,
+    /// The `#[cfg]` predicates of the platform-specific types, commands and extensions, keyed by raw
+    /// name. Only filled in if `platform_cfgs` is enabled.
+    pub platform_cfgs: HashMap<&'a str, &'static str>,
+    /// The notes of the `#[deprecated]` attributes of the commands, extension constants and
+    /// extensions that deprecated extensions introduce, keyed by raw name. Only filled in if
+    /// `emit_deprecated` is enabled.
+    pub deprecations: HashMap<&'a str, String>,
     pub registry: &'a VkRegistry<'a>,
+    /// How long it took to select the types and commands from the registry.
+    pub select_time: Duration,
     pub config: GenConfig<'b>,
     /// An internal buffer that contains all relevant identifier strings
-    pub string_buffer: String
+    pub string_buffer: StringArena
 }
 
 impl<'a, 'b> GenPreproc<'a, 'b> {
     pub fn new(registry: &'a VkRegistry<'a>, version: VkVersion, extensions: &[&str], config: GenConfig<'b>) -> Self {
+        GenPreproc::new_filtered(registry, version, extensions, config, |_| true)
+    }
+
+    /// Select the commands, types and constants required by `version` and `extensions` whose raw
+    /// names are accepted by `filter`, along with everything they depend on, accepted or not.
+    pub fn new_filtered<F: Fn(&str) -> bool>(registry: &'a VkRegistry<'a>, version: VkVersion, extensions: &[&str], config: GenConfig<'b>, filter: F) -> Self {
+        let start = Instant::now();
         let mut gen = GenPreproc {
-            string_buffer: String::with_capacity(registry.buffer_cap()),
+            string_buffer: StringArena::with_capacity(registry.buffer_cap()),
             types: HashMap::with_capacity(registry.types().len()),
             type_ord: Vec::with_capacity(registry.types().len()),
             const_types: HashMap::with_capacity(registry.core_consts().len()),
             custom_impls: HashSet::with_capacity(32),
+            variant_names: HashMap::with_capacity(2usize.pow(11)),
             commands: Vec::with_capacity(registry.commands().len()),
             commands_raw: Vec::with_capacity(registry.commands().len()),
+            extension_names: Vec::with_capacity(extensions.len()),
+            extensions: Vec::with_capacity(extensions.len()),
+            platform_cfgs: HashMap::new(),
+            deprecations: HashMap::new(),
             registry: registry,
+            select_time: Duration::from_secs(0),
             config: config,
         };
 
         let feature = gen.registry.features().get(&version).unwrap();
 
-        for c in registry.core_consts() {
+        for c in registry.core_consts().iter().filter(|c| filter(c)) {
             gen.add_const(c);
         }
 
+        let mut deferred = Vec::new();
         for req in &feature.require {
-            gen.add_interface(req);
+            gen.add_filtered(req, &filter, &mut deferred);
+        }
+        // Extensions the requested ones depend on go first, so that the types they introduce are
+        // attributed to them.
+        let dependencies = registry.extension_dependencies(version, extensions);
+        if !dependencies.is_empty() {
+            gen.registry.diagnose(Diagnostic::DependenciesAdded{ extensions: dependencies.iter().map(|&e| e.to_owned()).collect() });
         }
-        for e in extensions {
-            let ex = gen.registry.extns().get(e).unwrap();
+        for e in dependencies.iter().chain(extensions.iter()) {
+            let ex = gen.registry.extns().get(&**e).unwrap();
+            let first_type = gen.type_ord.len();
+            let first_command = gen.commands_raw.len();
             for req in &ex.require {
-                gen.add_interface(req);
+                if !gen.add_filtered(req, &filter, &mut deferred) {
+                    continue;
+                }
+
+                if let VkInterface::ConstDef{name, ..} = *req {
+                    let name = unsafe{ &*name };
+                    if name.ends_with("_EXTENSION_NAME") {
+                        let name = gen.process_const_name(name);
+                        gen.extension_names.push(name);
+                    }
+                }
+            }
+            if let (true, Some(cfg)) = (gen.config.platform_cfgs, ex.platform().and_then(platform_cfg)) {
+                let items = gen.type_ord[first_type..].iter().chain(gen.commands_raw[first_command..].iter());
+                gen.platform_cfgs.extend(items.map(|i| (*i, cfg)));
+                gen.platform_cfgs.insert(unsafe{ &*ex.name }, cfg);
+            }
+            if let (true, Some(note)) = (gen.config.emit_deprecated, deprecation_note(ex)) {
+                let consts = gen.type_ord[first_type..].iter().filter(|c| c.ends_with("_EXTENSION_NAME") || c.ends_with("_SPEC_VERSION"));
+                let items = consts.chain(gen.commands_raw[first_command..].iter());
+                gen.deprecations.extend(items.map(|i| (*i, note.clone())));
+                gen.deprecations.insert(unsafe{ &*ex.name }, note);
+            }
+            gen.extensions.push((ex, first_type..gen.type_ord.len()));
+        }
+        // Commands are only attributed to the first extension that requires them, so they can be
+        // shared with later extensions that aren't deprecated and still need them.
+        if !gen.deprecations.is_empty() {
+            for e in dependencies.iter().chain(extensions.iter()) {
+                let ex = gen.registry.extns().get(&**e).unwrap();
+                if deprecation_note(ex).is_some() {
+                    continue;
+                }
+                for req in &ex.require {
+                    if let VkInterface::Command{name, ..} = *req {
+                        gen.deprecations.remove(unsafe{ &*name });
+                    }
+                }
+            }
+        }
+        for req in deferred {
+            if let VkInterface::ExtnEnum{extends, ..} = *req {
+                if gen.types.contains_key(unsafe{ &*extends }) {
+                    gen.add_interface(req);
+                }
+            }
+        }
+
+        // Platform types can also be pulled in by extensions that aren't specific to the platform.
+        if gen.config.platform_cfgs {
+            for key in &gen.type_ord {
+                if let Some(&VkType::ExternType{requires, ..}) = gen.types.get(key) {
+                    if let Some(cfg) = header_platform(unsafe{ &*requires }).and_then(platform_cfg) {
+                        gen.platform_cfgs.entry(*key).or_insert(cfg);
+                    }
+                }
             }
         }
 
+        gen.select_time = start.elapsed();
+        gen.registry.diagnose(Diagnostic::Selected{ types: gen.types.len(), commands: gen.commands.len() });
         gen
     }
 
+    /// Add `interface` if `filter` accepts its raw name, returning whether or not it was added. Enum
+    /// variants aren't filtered, and wait in `deferred` if the type they extend hasn't been pulled in.
+    fn add_filtered<F: Fn(&str) -> bool>(&mut self, interface: &'a VkInterface, filter: &F, deferred: &mut Vec<&'a VkInterface>) -> bool {
+        use registry::VkInterface::*;
+        let name = match *interface {
+            Command{name, ..}  |
+            Type{name, ..}     |
+            ApiConst{name, ..} |
+            ConstDef{name, ..} => name,
+            ExtnEnum{extends, ..} => {
+                if self.types.contains_key(unsafe{ &*extends }) {
+                    self.add_interface(interface);
+                } else {
+                    deferred.push(interface);
+                }
+                return true;
+            }
+        };
+
+        if filter(unsafe{ &*name }) {
+            self.add_interface(interface);
+            true
+        } else {false}
+    }
+
     fn add_interface(&mut self, interface: &VkInterface) {
         use registry::VkInterface::*;
         match *interface {
             Command{name, ..} => {
                 let name = unsafe{ &*name };
-                let mut command = self.registry.commands().get(name).unwrap().clone();
+                // Extensions can require the same command, like `vkGetDeviceGroupPresentCapabilitiesKHR`.
+                if self.commands_raw.contains(&name) {
+                    return;
+                }
+                // Aliases of commands that aren't in the registry are dropped when it's crawled.
+                let mut command = match self.registry.commands().get(name) {
+                    Some(command) => command.clone(),
+                    None          => return
+                };
                 self.commands_raw.push(unsafe{ &*command.name });
 
                 for p in command.params.iter_mut() {
                     p.name = self.process_member_name(p.name);
+                    // `"true"` stands for the parameter itself, which was just processed.
+                    match to_option(p.externsync) {
+                        Some("true") | None => (),
+                        Some(externsync)    => p.externsync = self.process_externsync(externsync)
+                    }
                     self.add_type_recurse(&mut p.typ);
                 }
                 self.add_type_recurse(&mut command.ret);
@@ -272,15 +1047,18 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
             }
             ExtnEnum{extends, ref variant, ..} => {
                 let extends = unsafe{ &*extends };
-                let mut variant = variant.clone();
+                let mut new_variants = vec![variant.clone()];
                 match *self.types.get(extends).unwrap() {
-                    VkType::Enum{name, ..} => self.process_enum_variant(&mut variant, name),
-                    VkType::Bitmask{..}    => self.process_bitmask_variant(&mut variant),
-                    _                      => ()
+                    VkType::Enum{..}    => {
+                        let enum_name = self.builtin_type_ident(extends);
+                        self.process_variants(extends, &mut new_variants, Some(enum_name))
+                    }
+                    VkType::Bitmask{..} => self.process_variants(extends, &mut new_variants, None),
+                    _                   => ()
                 }
                 match *self.types.get_mut(extends).unwrap() {
                     VkType::Enum{ref mut variants, ..}     |
-                    VkType::Bitmask{ref mut variants, ..} => variants.push(variant),
+                    VkType::Bitmask{ref mut variants, ..} => variants.extend(new_variants),
                     _                                     => ()
                 }
             }
@@ -297,12 +1075,25 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
         typ.set_type(self.process_type_ident(type_ptr));
 
         let type_ptr = unsafe{ &*type_ptr };
+        if self.config.niche_handles && self.config.wrap_non_dispatchable_handles {
+            let registry = self.registry;
+            if let Some(&Handle{dispatchable: false, ..}) = registry.types().get(type_ptr) {
+                let ident = unsafe{ &*typ.type_ptr().unwrap() };
+                typ.set_type(unsafe{ self.append_char_func(|s| {
+                    s.push_str("Option<");
+                    s.push_str(ident);
+                    s.push('>');
+                })});
+            }
+        }
         match *typ {
             VkElType::ConstArrayEnum(_, ref mut c) |
             VkElType::MutArrayEnum(_, ref mut c)  => {
                 let mut cons = unsafe{ &**c };
-                if self.config.remove_const_prefix && cons.starts_with("VK_") {
-                    cons = &cons[3..];
+                // The length may have been left out by a filter, but it's needed all the same.
+                if self.registry.types().contains_key(cons) {
+                    self.add_const(cons);
+                    cons = self.process_const_name(cons);
                     *c = cons as *const _;
                 }
                 self.const_types.insert(cons, ConstType::USize);
@@ -330,7 +1121,7 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
                             _ => ()
                         };
 
-                        if let FuncPointer{..} = *self.registry.types().get(&*m.field_type.type_ptr().unwrap()).unwrap() {
+                        if let Some(&FuncPointer{..}) = self.registry.types().get(&*m.field_type.type_ptr().unwrap()) {
                             custom_impl = true;
                         }
 
@@ -343,12 +1134,17 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
                     self.add_type_recurse(ret)
                 },
                 TypeDef{..} =>
-                    if let TypeDef{typ, requires, ..} = *self.registry.types().get(type_ptr).unwrap() {
-                        self.add_type(&*typ);
+                    if let TypeDef{requires, ..} = *self.registry.types().get(type_ptr).unwrap() {
+                        // Recursing picks up typedefs of typedefs, like `VkFlags64` for the 64-bit flags.
+                        self.add_type_recurse(&mut VkElType::Var(self.registry.resolve_name(type_ptr)));
                         if let Some(requires) = to_option(requires) {
                             self.add_type(requires);
                         }
                     } else {panic!("Registry type does not match up with modified type")},
+                Alias{..} =>
+                    if let Alias{alias_of, ..} = *self.registry.types().get(type_ptr).unwrap() {
+                        self.add_type_recurse(&mut VkElType::Var(alias_of));
+                    } else {panic!("Registry type does not match up with modified type")},
                 _ => ()
             }
 
@@ -358,6 +1154,54 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
         }
     }
 
+    /// Make sure that every type referenced by the selected types and commands is going to be
+    /// generated too, returning an `InvalidData` error listing the items holding dangling references
+    /// if not.
+    pub fn check_references(&self) -> io::Result<()> {
+        use registry::VkType::*;
+
+        let emitted: HashSet<&str> = self.types.values().filter_map(|t| t.name()).map(|n| unsafe{ &*n }).collect();
+        let mut dangling = Vec::new();
+        {
+            let mut check = |item: *const str, typ: &VkElType| {
+                if let Some(t) = typ.type_ptr().and_then(to_option) {
+                    if !emitted.contains(t) {
+                        dangling.push(format!("`{}` references `{}`", unsafe{ &*item }, t));
+                    }
+                }
+            };
+
+            for t in self.types.values() {
+                match *t {
+                    Struct{name, fields: ref members, ..} |
+                    Union{name, variants: ref members, ..} =>
+                        for m in members {
+                            check(name, &m.field_type);
+                        },
+                    TypeDef{name, typ, ..}               => check(name, &VkElType::Var(typ)),
+                    Alias{name, alias_of, ..}            => check(name, &VkElType::Var(alias_of)),
+                    FuncPointer{name, ref ret, ref params} =>
+                        for p in params.iter().chain(Some(ret)) {
+                            check(name, p);
+                        },
+                    _ => ()
+                }
+            }
+            for c in &self.commands {
+                for p in c.params.iter().map(|p| &p.typ).chain(Some(&c.ret)) {
+                    check(c.name, p);
+                }
+            }
+        }
+
+        if !dangling.is_empty() {
+            dangling.sort();
+            let error = format!("Dangling type references in generated bindings: {}", dangling.join(", "));
+            return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+        }
+        Ok(())
+    }
+
     fn process_member_name(&mut self, name: *const str) -> *const str {
         let mut name = unsafe{ &*name };
 
@@ -374,29 +1218,81 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
             });
         }}
 
-        if "type" == name {
-            "typ"
-        } else {name}
+        let name = unsafe{ &*self.rename(name, IdentKind::Member) };
+        match name {
+            // `type` predates the escaping of keywords, and stays `typ` so that code using it keeps
+            // working.
+            "type"                              => "typ",
+            // These can't be raw identifiers.
+            "self" | "Self" | "super" | "crate" => unsafe{ self.append_char_func(|s| {
+                s.push_str(name);
+                s.push('_');
+            })},
+            _ if RUST_KEYWORDS.contains(&name)  => unsafe{ self.append_char_func(|s| {
+                s.push_str("r#");
+                s.push_str(name);
+            })},
+            _                                   => name
+        }
+    }
+
+    /// Processes the parameter and member names in the `externsync` expression of a parameter, like
+    /// `pSubmits[].pWaitSemaphores[]`, so that it names them the way they're generated.
+    fn process_externsync(&mut self, externsync: &str) -> *const str {
+        let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let mut processed = String::with_capacity(externsync.len());
+        for part in externsync.split_inclusive(|c: char| !is_ident_char(c)) {
+            let (ident, rest) = part.split_at(part.trim_end_matches(|c: char| !is_ident_char(c)).len());
+            if !ident.is_empty() {
+                processed.push_str(unsafe{ &*self.process_member_name(ident) });
+            }
+            processed.push_str(rest);
+        }
+        unsafe{ self.append_char_func(|s| s.push_str(&processed)) }
+    }
+
+    /// The name that the constant `name` is generated under.
+    fn const_ident(&self, name: &'a str) -> Cow<'a, str> {
+        let name = if self.config.remove_const_prefix && name.starts_with("VK_") {
+            &name[3..]
+        } else {name};
+        let name = match self.config.rename {
+            Some(rename) => Cow::Owned(checked_rename(rename, name, IdentKind::Constant)),
+            None         => Cow::Borrowed(name)
+        };
+
+        match self.config.symbol_prefix {
+            Some(prefix) => Cow::Owned(format!("{}{}", prefix, name)),
+            None         => name
+        }
+    }
+
+    fn process_const_name(&mut self, name: &'a str) -> &'a str {
+        match self.const_ident(name) {
+            Cow::Borrowed(name) => name,
+            Cow::Owned(name)    => unsafe{ &*self.append_char_func(|s| s.push_str(&name)) }
+        }
     }
 
-    fn add_const(&mut self, mut name: &'a str) -> Option<*mut VkType> {
-        let mut typ = self.registry.types().get(name).unwrap().clone();
-        if self.config.remove_const_prefix && name.starts_with("VK_") {
-            name = &name[3..];
+    fn add_const(&mut self, raw_name: &'a str) -> Option<*mut VkType> {
+        let mut typ = self.registry.types().get(raw_name).unwrap().clone();
+        let name = self.process_const_name(raw_name);
+        if name != raw_name {
             typ.set_name(name as *const _).unwrap();
         }
         self.insert_type(name, typ)
     }
 
-    fn add_const_new(&mut self, mut name: &'a str, value: &'a str) -> Option<*mut VkType> {
-        if self.config.remove_const_prefix && name.starts_with("VK_") {
-            name = &name[3..];
-        }
+    fn add_const_new(&mut self, name: &'a str, value: &'a str) -> Option<*mut VkType> {
+        let name = self.process_const_name(name);
         self.insert_type(name, VkType::new_const(name, value))
     }
 
     fn add_type(&mut self, name: &'a str) -> Option<*mut VkType> {
-        self.insert_type(name, self.registry.types().get(name).unwrap().clone())
+        // Types that are missing from the registry are left out, and get reported by
+        // `check_references()` if `strict` is enabled.
+        let typ = self.registry.types().get(name)?.clone();
+        self.insert_type(name, typ)
     }
 
     fn insert_type(&mut self, key: &'a str, mut typ: VkType) -> Option<*mut VkType> {
@@ -414,15 +1310,16 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
                 }
             }
 
-            VkType::Enum{ref mut variants, name: enum_name} =>
-                for v in variants.iter_mut() {
-                    self.process_enum_variant(v, enum_name);
-                },
+            VkType::Alias{ref mut alias_of, ..} =>
+                *alias_of = self.process_type_ident(*alias_of),
+
+            VkType::Enum{ref mut variants, ..} => {
+                let enum_name = self.builtin_type_ident(key);
+                self.process_variants(key, variants, Some(enum_name))
+            }
 
             VkType::Bitmask{ref mut variants, ..} =>
-                for v in variants.iter_mut() {
-                    self.process_bitmask_variant(v);
-                },
+                self.process_variants(key, variants, None),
             _ => ()
         }
 
@@ -433,8 +1330,33 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
     }
 
     fn process_type_ident(&mut self, ident: *const str) -> *const str {
-        let mut ident = unsafe{ &*ident };
+        let raw = unsafe{ &*ident };
+        let mut ident = self.builtin_type_ident(raw);
+
+        // Types from C headers keep their names, and so do identifiers that have already been
+        // processed. Constants have been renamed by `process_const_name()` already.
+        let prefix = match self.registry.types().get(raw) {
+            Some(&VkType::ExternType{..}) |
+            Some(&VkType::Define{..})     |
+            None                          => None,
+            Some(&VkType::ApiConst{..})   => self.config.symbol_prefix,
+            Some(_)                       => {
+                ident = unsafe{ &*self.rename(ident, IdentKind::Type) };
+                self.config.symbol_prefix
+            }
+        };
+        match prefix {
+            Some(prefix) => unsafe{ self.append_char_func(|s| {
+                s.push_str(prefix);
+                s.push_str(ident);
+            })},
+            None         => ident
+        }
+    }
 
+    /// The name of the type `ident` with just the naming options applied, before `rename` and
+    /// `symbol_prefix`.
+    fn builtin_type_ident<'i>(&self, mut ident: &'i str) -> &'i str {
         match ident {
             "void"      => ident = "c_void",
             "char"      => ident = "c_char",
@@ -458,11 +1380,37 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
                 ident = &ident[2..];
             }
         }
-
         ident
     }
 
-    fn process_bitmask_variant(&self, variant: &mut VkVariant) {
+    /// Process the variants of the enum or bitmask registered under `key`. Variants with an entry in
+    /// `variant_overrides` are renamed or removed instead of going through the usual processing.
+    /// `enum_name` is the name of the enum before `rename` and `symbol_prefix` are applied, and is
+    /// `None` for bitmasks.
+    fn process_variants(&mut self, key: &str, variants: &mut Vec<VkVariant>, enum_name: Option<&str>) {
+        let overrides = self.config.variant_overrides;
+        let mut processed = Vec::with_capacity(variants.len());
+
+        for mut v in variants.drain(..) {
+            let raw_name = unsafe{ &*v.name() };
+            let action = overrides.iter().find(|o| o.0 == key && o.1 == raw_name).map(|o| o.2);
+
+            match action {
+                Some(VariantAction::Remove)       => continue,
+                Some(VariantAction::Rename(name)) => v.set_name(name),
+                None => match enum_name {
+                    Some(enum_name) => self.process_enum_variant(&mut v, enum_name),
+                    None            => self.process_bitmask_variant(&mut v)
+                }
+            }
+            self.variant_names.insert(raw_name, v.name());
+            processed.push(v);
+        }
+
+        *variants = processed;
+    }
+
+    fn process_bitmask_variant(&mut self, variant: &mut VkVariant) {
         let mut name = unsafe{ &*variant.name() };
 
         if self.config.remove_bitmask_prefix {
@@ -470,11 +1418,24 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
                 name = &name[3..];
             }
         }
+        let name = self.rename(name, IdentKind::Variant);
         variant.set_name(name);
+        self.prefix_variant(variant);
+    }
+
+    /// Put `symbol_prefix` in front of `variant`. Only variants that are generated as free-standing
+    /// constants get the prefix, since the rest are namespaced by their enum already.
+    fn prefix_variant(&mut self, variant: &mut VkVariant) {
+        if let Some(prefix) = self.config.symbol_prefix {
+            let name = unsafe{ &*variant.name() };
+            variant.set_name(unsafe{ &*self.append_char_func(|s| {
+                s.push_str(prefix);
+                s.push_str(name);
+            })});
+        }
     }
 
-    fn process_enum_variant(&mut self, variant: &mut VkVariant, enum_name: *const str) {
-        let enum_name = unsafe{ &*enum_name };
+    fn process_enum_variant(&mut self, variant: &mut VkVariant, enum_name: &str) {
         match self.config.variant_padding {
             VariantPaddingConfig::Strip => {
                 let name_parts: Vec<_> = enum_name
@@ -546,6 +1507,12 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
                 variant.set_name(vn_new);
             }
         }
+
+        let name = self.rename(variant.name(), IdentKind::Variant);
+        variant.set_name(name);
+        if !self.config.use_native_enums && !self.config.newtype_enums {
+            self.prefix_variant(variant);
+        }
     }
 
     fn add_command(&mut self, mut command: VkCommand) {
@@ -593,23 +1560,30 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
             )};
         }
 
+        ident = unsafe{ &*self.rename(ident, IdentKind::Command) };
+        if let Some(prefix) = self.config.symbol_prefix {
+            ident = unsafe{&*self.append_char_func(|s| {
+                s.push_str(prefix);
+                s.push_str(ident);
+            })};
+        }
+
         ident
     }
 
-    unsafe fn append_char_func<F: Fn(&mut String)>(&mut self, processor: F) -> *const str {
-        use std::{slice, str};
-
-        let prepushcap = self.string_buffer.capacity();
-        let prepushlen = self.string_buffer.len();
-        // We want to have all of the string in one block of memory in order to save heap allocation time.
-        processor(&mut self.string_buffer);
-
-        if prepushcap != self.string_buffer.capacity() {
-            panic!("Allocation detected in string buffer")
+    /// Pass `ident` through `GenConfig::rename`, if there is one.
+    fn rename(&mut self, ident: *const str, kind: IdentKind) -> *const str {
+        match self.config.rename {
+            Some(rename) => {
+                let renamed = checked_rename(rename, unsafe{ &*ident }, kind);
+                unsafe{ self.append_char_func(|s| s.push_str(&renamed)) }
+            }
+            None => ident
         }
-
-        let ptr = self.string_buffer.as_ptr().offset(prepushlen as isize);
-        str::from_utf8_unchecked(slice::from_raw_parts(ptr, self.string_buffer.len()-prepushlen)) as *const str
+    }
+
+    unsafe fn append_char_func<F: Fn(&mut String)>(&mut self, processor: F) -> *const str {
+        self.string_buffer.append_with(processor)
     }
 }
 
@@ -617,6 +1591,23 @@ impl<'a, 'b> GenPreproc<'a, 'b> {
 macro_rules! gen_func_param {
     ($write: expr, $el: expr) => {
         match *$el {
+            VkElType::Var(ident) => write!($write, "{}", &*ident),
+            VkElType::ConstPtr(ident, count) => write!($write, "{}{}", "*const ".repeat(count as usize), &*ident),
+            VkElType::MutPtr(ident, count)   => write!($write, "{}{}", "*mut ".repeat(count as usize), &*ident),
+            VkElType::ConstArray(ident, size) => write!($write, "*const [{}; {}]", &*ident, size),
+            VkElType::MutArray(ident, size)   => write!($write, "*mut [{}; {}]", &*ident, size),
+            VkElType::ConstArrayEnum(ident, size) => write!($write, "*const [{}; {}]", &*ident, &*size),
+            VkElType::MutArrayEnum(ident, size)   => write!($write, "*mut [{}; {}]", &*ident, &*size),
+            VkElType::Void     => write!($write, "()"),
+            VkElType::Const(_) => panic!("Unexpected raw const"),
+            VkElType::Unknown  => panic!("Unexpected Unknown")
+        }
+    }
+}
+
+macro_rules! gen_member_type {
+    ($write: expr, $member: expr) => {
+        match $member.field_type {
             VkElType::Var(ident) => write!($write, "{}", &*ident),
             VkElType::ConstPtr(ident, count) => {
                 for _ in 0..count {
@@ -630,17 +1621,22 @@ macro_rules! gen_func_param {
                 }
                 write!($write, "{}", &*ident)
             }
-            VkElType::ConstArray(ident, size) => write!($write, "*const [{}; {}]", &*ident, size),
-            VkElType::MutArray(ident, size)   => write!($write, "*mut [{}; {}]", &*ident, size),
-            VkElType::ConstArrayEnum(ident, size) => write!($write, "*const [{}; {}]", &*ident, &*size),
-            VkElType::MutArrayEnum(ident, size)   => write!($write, "*mut [{}; {}]", &*ident, &*size),
-            VkElType::Void     => write!($write, "()"),
-            VkElType::Const(_) => panic!("Unexpected raw const"),
-            VkElType::Unknown  => panic!("Unexpected Unknown")
+            // Arrays are stored inline, so whether or not the C declaration is `const` doesn't matter here.
+            VkElType::MutArray(ident, size)       |
+            VkElType::ConstArray(ident, size)     => write!($write, "[{}; {}]", &*ident, size),
+            VkElType::MutArrayEnum(ident, size)   |
+            VkElType::ConstArrayEnum(ident, size) => write!($write, "[{}; {}]", &*ident, &*size),
+            VkElType::Const(_)             => panic!("Unexpected raw const"),
+            VkElType::Void                 => panic!("Unexpected void"),
+            VkElType::Unknown              => panic!("Unexpected unknown")
         }.unwrap()
     }
 }
 
+// Declared after the macros above, which it uses to write types the same way the Rust output does.
+#[cfg(feature = "serde_json")]
+mod json;
+
 pub struct GenTypes<'a> {
     config:       &'a GenConfig<'a>,
     structs:      String,
@@ -652,12 +1648,18 @@ pub struct GenTypes<'a> {
     funcpointers: String,
     consts:       String,
     externs:      String,
-    libc_reexports: String
+    libc_reexports: String,
+    abi_tests:    String,
+    /// Whether or not the types use any of the helpers in `prelude_common.rs`. The types module only
+    /// imports its parent if they do, or if it needs the constants from `consts_module`.
+    uses_prelude: bool
 }
 
 impl<'a> GenTypes<'a> {
     /// Generate type definitions. Assumes presence of `./prelude_common.rs`.
     pub fn new(processed: &'a GenPreproc) -> Self {
+        let vis = processed.config.visibility;
+        let paths = processed.config.std_paths();
         let mut gen_types = GenTypes {
             config:       &processed.config,
             structs:      String::with_capacity(2usize.pow(17)),
@@ -669,72 +1671,299 @@ impl<'a> GenTypes<'a> {
             funcpointers: String::with_capacity(2usize.pow(11)),
             consts:       String::with_capacity(2usize.pow(10)),
             externs:      String::with_capacity(2usize.pow(10)),
-            libc_reexports: String::new()
+            libc_reexports: String::new(),
+            abi_tests:    String::new(),
+            uses_prelude: false
         };
 
+        // The structs that at least one of the included structs can extend, if `chain_builders` is enabled.
+        let mut extended = HashSet::new();
+        if gen_types.config.chain_builders {
+            for t in processed.types.values() {
+                if let VkType::Struct{ref extends, ..} = *t {
+                    extended.extend(extends.iter().map(|e| unsafe{ &**e }).filter(|e| processed.types.contains_key(e)));
+                }
+            }
+        }
+
+        // Whether or not the `HasObjectType` trait has been emitted, which happens alongside the first
+        // handle that has an object type.
+        let mut has_object_type = false;
+        // Likewise for the `StructureChain` trait and the first chainable struct, if `gen_chain_helpers` is enabled.
+        let mut has_structure_chain = false;
+        // The `sType` value and name of every struct that has one, for `structure_type_table`.
+        let mut structure_types: Vec<(String, &str)> = Vec::new();
+
         // Iterate over the types in an order defined by which types were loaded first
-        for t in processed.type_ord.iter().map(|k| processed.types.get(k).unwrap()) {
+        for (key, t) in processed.type_ord.iter().map(|k| (*k, processed.types.get(k).unwrap())) {
             use registry::VkType::*;
             use registry::VkElType::*;
             use registry::VkVariant::*;
 
+            let section_lens = gen_types.gated_sections().map(|s| s.len());
+
             match *t {
                 // Generate struct bindings
-                Struct{name, ref fields} => {
+                Struct{name, ref fields, ref extends, ..} => {
                     let name = unsafe{ &*name };
                     let structs = &mut gen_types.structs;
 
+                    if gen_types.config.emit_docs {
+                        structs.push_str(&doc_comment("", t.comment()));
+                    }
+
                     if !processed.custom_impls.contains(name) {
                         writeln!(structs, "#[derive(Debug, Clone, Copy)]").unwrap();
                     } else {
                         writeln!(structs, "#[derive(Copy)]").unwrap();
                     }
-                    writeln!(structs, "#[repr(C)]\npub struct {} {{", name).unwrap();
-                    let type_wrappable_by_option = |type_ident: &str| {
-                        match processed.registry.types().get(type_ident) {
-                            Some(&VkType::FuncPointer{..}) => true,
-                            _ => false
+                    writeln!(structs, "#[repr(C)]\n{} struct {} {{", vis, name).unwrap();
+
+                    // Every associated item of the struct, so that they all go in one `impl` block.
+                    let mut inherent = String::new();
+
+                    // The `OFFSET_*` constants, if `field_offsets` is enabled. Each offset is computed from the
+                    // previous field's offset and size, rounded up to the alignment of the field's type, which is
+                    // how `#[repr(C)]` lays out structs.
+                    let mut prev_field: Option<(&str, String)> = None;
+
+                    for f in fields { unsafe {
+                        let mut field_type = String::new();
+                        gen_member_type!(field_type, f);
+                        writeln!(structs, "    {vis} {}: {},", &*f.field_name, field_type, vis = vis).unwrap();
+
+                        if gen_types.config.field_offsets {
+                            gen_types.uses_prelude = true;
+                            let field_name = unraw(&*f.field_name);
+                            match prev_field {
+                                Some((prev_name, ref prev_type)) =>
+                                    writeln!(inherent, "    {vis} const OFFSET_{}: usize = vk_align_up(Self::OFFSET_{} + ::{core}::mem::size_of::<{}>(), ::{core}::mem::align_of::<{}>());",
+                                             field_name, prev_name, prev_type, field_type, vis = vis, core = paths.core),
+                                None => writeln!(inherent, "    {vis} const OFFSET_{}: usize = 0;", field_name, vis = vis)
+                            }.unwrap();
+                            prev_field = Some((field_name, field_type));
                         }
+                    }}
+                    structs.push_str("}\n\n");
+
+                    if gen_types.config.limit_accessors && key == "VkPhysicalDeviceLimits" {
+                        let raw_fields = match *processed.registry.types().get(key).unwrap() {
+                            Struct{fields: ref raw_fields, ..} => raw_fields,
+                            _                                  => unreachable!()
+                        };
+
+                        for (f, raw) in fields.iter().zip(raw_fields.iter()) { unsafe {
+                            let (t, len) = match f.field_type {
+                                MutArray(t, len)   |
+                                ConstArray(t, len) if len == 2 || len == 3 => (&*t, len),
+                                _                                          => continue
+                            };
+                            let components: &[&str] =
+                                if (&*raw.field_name).ends_with("Range") {&["min", "max"]}
+                                else {&["x", "y", "z"]};
+                            for (i, c) in components.iter().take(len).enumerate() {
+                                writeln!(inherent, "    {vis} fn {0}_{1}(&self) -> {2} {{ self.{0}[{3}] }}", &*f.field_name, c, t, i, vis = vis).unwrap();
+                            }
+                        }}
+                    }
+
+                    // Members are only renamed by the preprocessor, so the raw struct can be used to find `sType` and `pNext`.
+                    let raw_fields = match *processed.registry.types().get(key).unwrap() {
+                        Struct{fields: ref raw_fields, ..} => raw_fields,
+                        _                                  => unreachable!()
                     };
+                    // Registries that predate `values` on `sType` get the variant named after the struct.
+                    let s_type = raw_fields.iter().position(|f| "sType" == unsafe{ &*f.field_name })
+                        .and_then(|i| {
+                            let raw_variant = to_option(raw_fields[i].values).map(Cow::Borrowed)
+                                .unwrap_or_else(|| Cow::Owned(type_variant("VK_STRUCTURE_TYPE", key)));
+                            processed.variant_names.get(&*raw_variant).map(|v| (fields[i].field_name, v))
+                        })
+                        .and_then(|(field, variant)| processed.types.get("VkStructureType").map(|e| (field, *variant, e.name().unwrap())));
+                    let p_next = raw_fields.iter().position(|f| "pNext" == unsafe{ &*f.field_name }).map(|i| fields[i].field_name);
+
+                    if let (true, Some((_, variant, s_type_enum)), Some(p_next)) = (gen_types.config.gen_chain_helpers, s_type, p_next) { unsafe {
+                        let s_type_enum = &*s_type_enum;
+                        if !has_structure_chain {
+                            structs.push_str("/// Implemented by every struct that starts with an `sType` and a `pNext`, and so can be part of a\n");
+                            structs.push_str("/// `pNext` chain.\n");
+                            writeln!(structs, "{vis} unsafe trait StructureChain {{\n    /// The `sType` that identifies this struct in a chain.\n    const STRUCTURE_TYPE: {};\n}}\n", s_type_enum, vis = vis).unwrap();
+                            has_structure_chain = true;
+                        }
 
-                    for f in fields { unsafe {
-                        write!(structs, "    pub ").unwrap();
-                        match f.field_type {
-                            Var(ident) => {
-                                let wrap_with_option = f.optional & type_wrappable_by_option(&*ident);
-                                match wrap_with_option {
-                                    true => writeln!(structs, "{}: Option<{}>,", &*f.field_name, &*ident),
-                                    false => writeln!(structs, "{}: {},", &*f.field_name, &*ident)
-                                }
-                            },
-                            ConstPtr(ident, count) => {
-                                write!(structs, "{}: ", &*f.field_name).unwrap();
-                                for _ in 0..count {
-                                    write!(structs, "*const ").unwrap();
+                        if gen_types.config.use_native_enums || gen_types.config.newtype_enums {
+                            writeln!(structs, "unsafe impl StructureChain for {} {{\n    const STRUCTURE_TYPE: {1} = {1}::{2};\n}}\n", name, s_type_enum, &*variant)
+                        } else {
+                            writeln!(structs, "unsafe impl StructureChain for {} {{\n    const STRUCTURE_TYPE: {} = {};\n}}\n", name, s_type_enum, &*variant)
+                        }.unwrap();
+
+                        inherent.push_str("    /// Append `next` to the end of this struct's `pNext` chain.\n");
+                        inherent.push_str("    ///\n");
+                        inherent.push_str("    /// # Safety\n");
+                        inherent.push_str("    ///\n");
+                        inherent.push_str("    /// `next` has to be a struct that the specification allows in this struct's chain, and it\n");
+                        inherent.push_str("    /// has to stay alive and in place for as long as the chain is used. Its own `pNext` has to be\n");
+                        inherent.push_str("    /// null or point to a valid chain, which gets appended along with it.\n");
+                        writeln!(inherent, "    {vis} unsafe fn link_next<T: StructureChain>(&mut self, next: &mut T) {{", vis = vis).unwrap();
+                        gen_types.uses_prelude = true;
+                        writeln!(inherent, "        vk_chain_append(&mut self.{} as *mut _ as *mut *mut VkChainLink, next as *mut T as *mut VkChainLink);", &*p_next).unwrap();
+                        inherent.push_str("    }\n");
+                    }}
+
+                    if let (true, Some((_, variant, s_type_enum))) = (gen_types.config.structure_type_table, s_type) { unsafe {
+                        let s_type_enum = &*s_type_enum;
+                        let value = if gen_types.config.use_native_enums || gen_types.config.newtype_enums {
+                            format!("{}::{}", s_type_enum, &*variant)
+                        } else {
+                            (&*variant).to_owned()
+                        };
+                        writeln!(inherent, "    /// The `sType` that identifies this struct.\n    {vis} const STRUCTURE_TYPE: {} = {};", s_type_enum, value, vis = vis).unwrap();
+                        structure_types.push((value, name));
+                    }}
+
+                    if let (true, Some((field, variant, s_type_enum))) = (gen_types.config.gen_constructors, s_type) {
+                        if zeroable(processed.registry, key, gen_types.config.use_native_enums) { unsafe {
+                            let slices = slice_members(raw_fields);
+                            // Pointers that share the count of a slice, like `pWaitDstStageMask` with
+                            // `pWaitSemaphores`, are taken as slices too, and checked against that count.
+                            let shared_count = |f: &VkMember, raw: &VkMember| {
+                                let (elem, as_ptr) = match f.field_type {
+                                    ConstPtr(elem, 1) => (format!("&[{}]", &*elem), "as_ptr"),
+                                    MutPtr(elem, 1)   => (format!("&mut [{}]", &*elem), "as_mut_ptr"),
+                                    _                 => return None
+                                };
+                                to_option(raw.len)
+                                    .and_then(|len| len.split(',').next())
+                                    .and_then(|len| slices.iter().find(|&&s| !raw_fields[s].optional && &*raw_fields[s - 1].field_name == len))
+                                    .map(|&s| (&*fields[s - 1].field_name, elem, as_ptr))
+                            };
+                            let mut params = String::new();
+                            let mut body = String::new();
+                            let mut checks = String::new();
+                            for (i, (f, raw)) in fields.iter().zip(raw_fields.iter()).enumerate() {
+                                let field_name = &*f.field_name;
+                                let raw_name = &*raw.field_name;
+                                if "sType" == raw_name || "pNext" == raw_name || slices.contains(&(i + 1)) {
+                                    continue;
                                 }
-                                writeln!(structs, "{},", &*ident)
-                            }
-                            MutPtr(ident, count) => {
-                                write!(structs, "{}: ", &*f.field_name).unwrap();
-                                for _ in 0..count {
-                                    write!(structs, "*mut ").unwrap();
+
+                                if slices.contains(&i) {
+                                    if raw.optional {
+                                        continue;
+                                    }
+                                    let (split, elem) = match f.field_type {
+                                        ConstPtr(elem, depth) => ("slice_ptr_len", format!("&[{}{}]", "*const ".repeat(depth as usize - 1), &*elem)),
+                                        MutPtr(elem, depth)   => ("slice_ptr_len_mut", format!("&mut [{}{}]", "*mut ".repeat(depth as usize - 1), &*elem)),
+                                        _                     => unreachable!()
+                                    };
+                                    gen_types.uses_prelude = true;
+                                    write!(params, "{}{}: {}", if params.is_empty() {""} else {", "}, field_name, elem).unwrap();
+                                    writeln!(body, "        let ({0}, {1}) = {2}({0});", field_name, &*fields[i - 1].field_name, split).unwrap();
+                                    writeln!(body, "        this.{0} = {0};\n        this.{1} = {1};", &*fields[i - 1].field_name, field_name).unwrap();
+                                } else if let (false, Some((count, elem, as_ptr))) = (raw.optional, shared_count(f, raw)) {
+                                    write!(params, "{}{}: {}", if params.is_empty() {""} else {", "}, field_name, elem).unwrap();
+                                    writeln!(checks, "        assert_eq!({0}.len(), this.{1} as usize, \"`{0}` has to be as long as the slice counted by `{1}`\");", field_name, count).unwrap();
+                                    writeln!(checks, "        this.{0} = {0}.{1}();", field_name, as_ptr).unwrap();
+                                } else if !raw.optional {
+                                    let mut field_type = String::new();
+                                    gen_member_type!(field_type, f);
+                                    write!(params, "{}{}: {}", if params.is_empty() {""} else {", "}, field_name, field_type).unwrap();
+                                    writeln!(body, "        this.{0} = {0};", field_name).unwrap();
                                 }
-                                writeln!(structs, "{},", &*ident)
-                            }
-                            MutArray(ident, count) => {
-                                writeln!(structs, "{}: [{}; {}],", &*f.field_name, &*ident, count)
                             }
-                            MutArrayEnum(ident, cons) => {
-                                writeln!(structs, "{}: [{}; {}],", &*f.field_name, &*ident, &*cons)
+
+                            writeln!(inherent, "    /// A `{}` with `sType` set, the required fields taken from the arguments and every other field", name).unwrap();
+                            inherent.push_str("    /// zeroed. Slices are stored as pointers, so they have to outlive any use of the struct.\n");
+                            if !checks.is_empty() {
+                                inherent.push_str("    ///\n    /// Panics if slices that share a count aren't the same length.\n");
                             }
-                            ConstArray(_, _)      |
-                            ConstArrayEnum(_, _) => panic!("Unexpected const array in struct"),
-                            Const(_)             => panic!("Unexpected const {}", name),
-                            Void                 => panic!("Unexpected void"),
-                            Unknown              => panic!("Unexpected unknown")
+                            writeln!(inherent, "    {vis} fn new({}) -> {} {{", params, name, vis = vis).unwrap();
+                            writeln!(inherent, "        let mut this: {} = unsafe{{ ::{}::mem::zeroed() }};", name, paths.core).unwrap();
+                            if gen_types.config.use_native_enums || gen_types.config.newtype_enums {
+                                writeln!(inherent, "        this.{} = {}::{};", &*field, &*s_type_enum, &*variant)
+                            } else {
+                                writeln!(inherent, "        this.{} = {};", &*field, &*variant)
+                            }.unwrap();
+                            inherent.push_str(&body);
+                            inherent.push_str(&checks);
+                            inherent.push_str("        this\n    }\n");
+                        }}
+                    }
+
+                    if !inherent.is_empty() {
+                        writeln!(structs, "impl {} {{\n{}}}\n", name, inherent).unwrap();
+                    }
+
+                    if gen_types.config.derive_default && zeroable(processed.registry, key, gen_types.config.use_native_enums) {
+                        if p_next.is_some() {
+                            structs.push_str("/// `pNext` is null, so chaining any extension structs is left to the caller.\n");
+                        }
+                        writeln!(structs, "impl Default for {} {{\n    fn default() -> {0} {{", name).unwrap();
+                        match s_type {
+                            Some((field, variant, s_type_enum)) => unsafe {
+                                writeln!(structs, "        let mut default: {} = unsafe{{ ::{}::mem::zeroed() }};", name, paths.core).unwrap();
+                                if gen_types.config.use_native_enums || gen_types.config.newtype_enums {
+                                    writeln!(structs, "        default.{} = {}::{};", &*field, &*s_type_enum, &*variant)
+                                } else {
+                                    writeln!(structs, "        default.{} = {};", &*field, &*variant)
+                                }.unwrap();
+                                structs.push_str("        default\n");
+                            },
+                            None => writeln!(structs, "        unsafe{{ ::{}::mem::zeroed() }}", paths.core).unwrap()
+                        }
+                        structs.push_str("    }\n}\n\n");
+                    }
+
+                    if gen_types.config.geometry_helpers {
+                        let type_name = |raw: &str| unsafe{ &*processed.types.get(raw).unwrap().name().unwrap() };
+                        match key {
+                            "VkExtent2D" => writeln!(structs, "vk_geometry_helpers!({}, uint32_t, [width, height]);\n", name),
+                            "VkExtent3D" => writeln!(structs, "vk_geometry_helpers!({}, uint32_t, [width, height, depth]);\n", name),
+                            "VkOffset2D" => writeln!(structs, "vk_geometry_helpers!({}, int32_t, [x, y]);\n", name),
+                            "VkOffset3D" => writeln!(structs, "vk_geometry_helpers!({}, int32_t, [x, y, z]);\n", name),
+                            "VkRect2D"   => writeln!(structs, "vk_rect_helpers!({}, {}, {});\n", name, type_name("VkOffset2D"), type_name("VkExtent2D")),
+                            _            => Ok(())
                         }.unwrap();
-                    }}
-                    structs.push_str("}\n\n");
+
+                        // Widening conversions, as long as the narrower type has been pulled in too.
+                        match key {
+                            "VkExtent3D" if processed.types.contains_key("VkExtent2D") =>
+                                writeln!(structs, "vk_geometry_widen!({}, {}, [width, height], depth = 1);\n", type_name("VkExtent2D"), name),
+                            "VkOffset3D" if processed.types.contains_key("VkOffset2D") =>
+                                writeln!(structs, "vk_geometry_widen!({}, {}, [x, y], z = 0);\n", type_name("VkOffset2D"), name),
+                            _ => Ok(())
+                        }.unwrap();
+                    }
+
+                    if gen_types.config.feature_presets && key == "VkPhysicalDeviceFeatures" {
+                        let all_bools = match *processed.registry.types().get(key).unwrap() {
+                            Struct{fields: ref raw_fields, ..} => raw_fields.iter().all(|f| match f.field_type {
+                                Var(t) => "VkBool32" == unsafe{ &*t },
+                                _      => false
+                            }),
+                            _ => unreachable!()
+                        };
+                        if all_bools {
+                            for &(preset, value) in &[("NONE", "VK_FALSE"), ("ALL", "VK_TRUE")] {
+                                writeln!(structs, "{vis} const FEATURES_{}: {} = {} {{", preset, name, name, vis = vis).unwrap();
+                                for f in fields {
+                                    writeln!(structs, "    {}: {},", unsafe{ &*f.field_name }, processed.const_ident(value)).unwrap();
+                                }
+                                writeln!(structs, "}};\n").unwrap();
+                            }
+                        }
+                    }
+
+                    if gen_types.config.chain_builders {
+                        for base in extends.iter().filter_map(|e| processed.types.get(unsafe{ &**e })) {
+                            writeln!(structs, "unsafe impl Extends{} for {} {{}}", unsafe{ &*base.name().unwrap() }, name).unwrap();
+                        }
+
+                        if let (true, Some(p_next)) = (extended.contains(key), p_next) {
+                            writeln!(structs, "vk_chain_builder!({0}, {0}Builder, Extends{0}, {1});\n", name, unsafe{ &*p_next }).unwrap();
+                            gen_types.uses_prelude = true;
+                        }
+                    }
 
                     if processed.custom_impls.contains(name) {
                         // Write `Clone` implementation
@@ -745,32 +1974,34 @@ impl<'a> GenTypes<'a> {
 
                             // For some reason, extern "system" functions implement `Copy` and not `Clone`. Because of that, we have
                             // to make an exception in the custom Clone implementation so that these functions are *copied*, not
-                            // cloned. Also, we can used the "processed" type because function pointer types don't get processed
-                            if let Some(&FuncPointer{..}) = processed.registry.types().get(&*f.field_type.type_ptr().unwrap()) {
+                            // cloned. Also, we can used the "processed" type because function pointer types don't get processed.
+                            //
+                            // Arrays get copied too. Every generated type is `Copy`, so this works no matter what the elements are
+                            // or how long the array is.
+                            let copied = match f.field_type {
+                                MutArray(..)       |
+                                ConstArray(..)     |
+                                MutArrayEnum(..)   |
+                                ConstArrayEnum(..) => true,
+                                _                  => matches!(processed.registry.types().get(&*f.field_type.type_ptr().unwrap()),
+                                                               Some(&FuncPointer{..}))
+                            };
+                            if copied {
                                 writeln!(structs, "{0}: self.{0},", n).unwrap();
                             } else {
-                                match f.field_type {
-                                    MutArray(_, s)        |
-                                    ConstArray(_, s)     => writeln!(structs, include_str!("clone_array.rs"), n, s),
-                                    MutArrayEnum(_, s)    |
-                                    ConstArrayEnum(_, s) => writeln!(structs, include_str!("clone_array.rs"), n, &*s),
-                                    _                    => writeln!(structs, "{0}: self.{0}.clone(),", n)
-                                }.unwrap()
+                                writeln!(structs, "{0}: self.{0}.clone(),", n).unwrap();
                             }
                         }}
                         write!(structs, "        }}\n    }}\n}}\n\n").unwrap();
 
                         // Write `Debug` implementation
-                        writeln!(structs, include_str!("custom_impl_debug.rs"), name).unwrap();
+                        writeln!(structs, include_str!("custom_impl_debug.rs"), name, core = paths.core).unwrap();
                         for f in fields {unsafe{
                             write!(structs, "           ").unwrap();
                             let n = &* f.field_name;
 
                             if let Some(&FuncPointer{..}) = processed.registry.types().get(&*f.field_type.type_ptr().unwrap()) {
-                                match f.optional {
-                                    true => writeln!(structs, ".field(\"{0}\", &(self.{0}.map(|f| f as *const ())))", n),
-                                    false => writeln!(structs, ".field(\"{0}\", &(self.{0} as *const ()))", n)
-                                }.unwrap()
+                                writeln!(structs, ".field(\"{1}\", &(self.{0}.map(|f| f as *const ())))", n, unraw(n)).unwrap()
                             } else {
                                 match f.field_type {
                                     MutArray(t, _)        |
@@ -778,9 +2009,9 @@ impl<'a> GenTypes<'a> {
                                     MutArrayEnum(t, _)    |
                                     ConstArrayEnum(t, _) =>
                                         if gen_types.config.debug_c_strings && "c_char" == &*t {
-                                            writeln!(structs, ".field(\"{0}\", &unsafe{{ CStr::from_ptr(&self.{0}[0]) }})", n)
-                                        } else {writeln!(structs, ".field(\"{0}\", &&self.{0}[..])", n)},
-                                    _                    => writeln!(structs, ".field(\"{0}\", &self.{0})", n)
+                                            writeln!(structs, ".field(\"{1}\", &unsafe{{ CStr::from_ptr(&self.{0}[0]) }})", n, unraw(n))
+                                        } else {writeln!(structs, ".field(\"{1}\", &&self.{0}[..])", n, unraw(n))},
+                                    _                    => writeln!(structs, ".field(\"{1}\", &self.{0})", n, unraw(n))
                                 }.unwrap()
                             }
                         }}
@@ -798,51 +2029,37 @@ impl<'a> GenTypes<'a> {
                 // What they do have, however, is an approved RFC that is currently being implemented. Until those  |
                 // become reality the unions currently present in Vulkan are simply going to be hard-coded into the |
                 // generator with a fairly shitty, although functional, implementation.                             |
-                Union{name, ref variants} => unsafe {
+                Union{name, ref variants, ..} => unsafe {
                     let unions = &mut gen_types.unions;
+                    if processed.config.emit_docs {
+                        unions.push_str(&doc_comment("", t.comment()));
+                    }
                     if processed.config.use_native_unions {
-                        // Create base union type
-                        writeln!(unions, "#[repr(C)]").unwrap();
+                        // Create base union type. Rust gives a `#[repr(C)]` union the alignment of its most
+                        // aligned variant already, but 64-bit scalars are only 4-byte aligned on some 32-bit
+                        // targets where C aligns them to 8, so anything over 4 bytes gets forced.
+                        match layout::type_layout(processed.registry, key, layout::target_pointer_size()) {
+                            Some(layout) if layout.align > 4 => writeln!(unions, "#[repr(C, align({}))]", layout.align),
+                            _                                => writeln!(unions, "#[repr(C)]")
+                        }.unwrap();
                         writeln!(unions, "#[derive(Clone, Copy)]").unwrap();
-                        writeln!(unions, "pub union {} {{", &*name).unwrap();
+                        writeln!(unions, "{vis} union {} {{", &*name, vis = vis).unwrap();
 
                         for v in variants {
-                            write!(unions, "    pub {}: ", &*v.field_name).unwrap();
-
-                            match v.field_type {
-                                Var(ident) => writeln!(unions, "{},", &*ident),
-                                ConstPtr(ident, count) => {
-                                    for _ in 0..count {
-                                        write!(unions, "*const ").unwrap();
-                                    }
-                                    writeln!(unions, "{},", &*ident)
-                                }
-                                MutPtr(ident, count) => {
-                                    for _ in 0..count {
-                                        write!(unions, "*mut ").unwrap();
-                                    }
-                                    writeln!(unions, "{},", &*ident)
-                                }
-                                MutArray(ident, count)    => writeln!(unions, "[{}; {}],", &*ident, count),
-                                MutArrayEnum(ident, cons) => writeln!(unions, "[{}; {}],", &*ident, &*cons),
-
-                                ConstArray(_, _)      |
-                                ConstArrayEnum(_, _) => panic!("Unexpected const array in union"),
-                                Const(_)             => panic!("Unexpected const {}", &*name),
-                                Void                 => panic!("Unexpected void"),
-                                Unknown              => panic!("Unexpected unknown")
-                            }.unwrap();
+                            write!(unions, "    {vis} {}: ", &*v.field_name, vis = vis).unwrap();
+                            gen_member_type!(unions, v);
+                            unions.push_str(",\n");
                         }
                         writeln!(unions, "}}\n").unwrap();
 
                         // Write `Debug` implementation
-                        writeln!(unions, include_str!("./custom_impl_debug.rs"), &*name).unwrap();
+                        writeln!(unions, include_str!("./custom_impl_debug.rs"), &*name, core = paths.core).unwrap();
                         for v in variants {
                             write!(unions, "           ").unwrap();
                             let n = &* v.field_name;
 
                             if let Some(&FuncPointer{..}) = processed.registry.types().get(&*v.field_type.type_ptr().unwrap()) {
-                                writeln!(unions, ".field(\"{0}\", &(self.{0} as *const ()))", n).unwrap();
+                                writeln!(unions, ".field(\"{1}\", &unsafe{{ self.{0} }}.map(|f| f as *const ()))", n, unraw(n)).unwrap();
                             } else {
                                 match v.field_type {
                                     MutArray(t, _)        |
@@ -850,105 +2067,276 @@ impl<'a> GenTypes<'a> {
                                     MutArrayEnum(t, _)    |
                                     ConstArrayEnum(t, _) =>
                                         if gen_types.config.debug_c_strings && "c_char" == &*t {
-                                            writeln!(unions, ".field(\"{0}\", &unsafe{{ CStr::from_ptr(&self.{0}[0]) }})", n)
-                                        } else {writeln!(unions, ".field(\"{0}\", unsafe{{ &&self.{0}[..] }})", n)},
-                                    _                    => writeln!(unions, ".field(\"{0}\", unsafe{{ &self.{0} }})", n)
+                                            writeln!(unions, ".field(\"{1}\", &unsafe{{ CStr::from_ptr(&self.{0}[0]) }})", n, unraw(n))
+                                        } else {writeln!(unions, ".field(\"{1}\", unsafe{{ &&self.{0}[..] }})", n, unraw(n))},
+                                    _                    => writeln!(unions, ".field(\"{1}\", unsafe{{ &self.{0} }})", n, unraw(n))
                                 }.unwrap()
                             }
                         }
                         write!(unions, "            .finish()\n    }}\n}}\n\n").unwrap();
 
                     } else {
-                        if (&*name).contains("ClearColorValue") {
-                            writeln!(unions, include_str!("./hardcoded/union_ClearColorValue.rs"), &*name)
-                        } else if (&*name).contains("ClearValue") {
+                        if "VkClearColorValue" == key {
+                            writeln!(unions, include_str!("./hardcoded/union_ClearColorValue.rs"), &*name, vis = vis, core = paths.core)
+                        } else if "VkClearValue" == key {
                             writeln!(unions, include_str!("./hardcoded/union_ClearValue.rs"), &*name,
                                                                                               &*variants[0].field_type.type_ptr().unwrap(),
-                                                                                              &*variants[1].field_type.type_ptr().unwrap())
+                                                                                              &*variants[1].field_type.type_ptr().unwrap(), vis = vis, core = paths.core)
                         } else {panic!("Unexpected Union")}.unwrap()
                     }
                 },
 
                 // Generate enum bindings
-                Enum{name, ref variants} => {
+                Enum{name, ref variants, ..} => {
                     let enums = &mut gen_types.enums;
                     let ename = unsafe{ &*name };
+                    let emit_docs = gen_types.config.emit_docs;
+                    let all: Vec<_> = unique_variants(variants).into_iter().map(|(_, v)| unsafe{ &*v.name() }).collect();
+                    // Newtype enums are declared by a macro, which doesn't take docs.
+                    if emit_docs && (gen_types.config.use_native_enums || !gen_types.config.newtype_enums) {
+                        enums.push_str(&doc_comment("", t.comment()));
+                    }
                     if gen_types.config.use_native_enums {
-                        writeln!(enums, "#[repr(C)]\n#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]\npub enum {} {{", ename).unwrap();
+                        writeln!(enums, "#[repr(C)]\n#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]\n{} enum {} {{", vis, ename).unwrap();
 
-                        for v in variants {unsafe {
+                        // Native enums can't have two variants with the same value, so aliases are left out.
+                        for v in variants.iter().filter(|v| v.alias_of().is_none()) {unsafe {
+                            if emit_docs {
+                                enums.push_str(&doc_comment("    ", v.comment()));
+                            }
                             match *v {
-                                Value{name, value} => writeln!(enums, "    {} = {},", &*name, value),
-                                Bitpos{..}         => panic!("Found bitpos in non-biflags enum")
+                                Value{name, value, ..} => writeln!(enums, "    {} = {},", &*name, value),
+                                Bitpos{..}             => panic!("Found bitpos in non-biflags enum")
                             }.unwrap();
                         }}
 
                         enums.push_str("}\n\n");
+
+                        // ...so that names which were promoted out of an extension still resolve, they
+                        // become associated constants pointing at the variant they alias.
+                        let aliases: Vec<_> = variants.iter()
+                            .filter_map(|v| v.alias_of().map(|a| (v.name(), a)))
+                            .filter_map(|(name, a)| processed.variant_names.get(a).map(|a| (name, *a)))
+                            .filter(|&(name, a)| unsafe{ *name != *a })
+                            .collect();
+                        if !aliases.is_empty() || gen_types.config.enum_iter {
+                            writeln!(enums, "impl {} {{", ename).unwrap();
+                            for (name, a) in aliases {unsafe {
+                                writeln!(enums, "    {vis} const {1}: {0} = {0}::{2};", ename, &*name, &*a, vis = vis).unwrap();
+                            }}
+                            if gen_types.config.enum_iter {
+                                let all: Vec<_> = all.iter().map(|v| format!("{}::{}", ename, v)).collect();
+                                writeln!(enums, "    {vis} const ALL: &'static [{}] = &[{}];", ename, all.join(", "), vis = vis).unwrap();
+                            }
+                            enums.push_str("}\n\n");
+                        }
+                    } else if gen_types.config.newtype_enums {
+                        write!(enums, "vk_newtype_enum!({}, [", ename).unwrap();
+                        for (i, v) in variants.iter().enumerate() {unsafe {
+                            match *v {
+                                Value{name, value, ..} => write!(enums, "{}{} = {}", if i == 0 {""} else {", "}, &*name, value),
+                                Bitpos{..}             => panic!("Found bitpos in non-biflags enum")
+                            }.unwrap();
+                        }}
+                        if gen_types.config.enum_iter {
+                            write!(enums, "], ALL = [{}", all.join(", ")).unwrap();
+                        }
+                        writeln!(enums, "]);").unwrap();
+
+                        if gen_types.config.enum_int_eq {
+                            writeln!(enums, "vk_enum_int_eq!({});", ename).unwrap();
+                        }
+                        enums.push('\n');
                     } else {
-                        writeln!(enums, "pub type {} = u32;", ename).unwrap();
+                        writeln!(enums, "{vis} type {} = u32;", ename, vis = vis).unwrap();
                         for v in variants {
+                            if emit_docs {
+                                enums.push_str(&doc_comment("", v.comment()));
+                            }
                             match *v {
-                                Value{name, value} => {
+                                Value{name, value, ..} => {
                                     let vname = unsafe{ &*name };
                                     let (sign, v) = if value >= 0 {
                                         ("", value)
                                     } else {
                                         ("!", -value)
                                     };
-                                    writeln!(enums, "pub const {}: u32 = {}{};", vname, sign, v).unwrap();
+                                    writeln!(enums, "{vis} const {}: u32 = {}{};", vname, sign, v, vis = vis).unwrap();
                                 },
                                 Bitpos{..} => panic!("Found bitpos in non-biflags enum")
                             }
                         }
                     }
+
+                    if gen_types.config.impl_display_enums && (gen_types.config.use_native_enums || gen_types.config.newtype_enums) {
+                        let repr = if gen_types.config.use_native_enums {"native"} else {"newtype"};
+                        write!(enums, "vk_enum_display!({} {}, [", repr, ename).unwrap();
+                        for (i, (value, v)) in unique_variants(variants).into_iter().enumerate() {
+                            let raw = raw_variant_name(processed, key, value, unsafe{ &*v.name() });
+                            write!(enums, "{}{} => {:?}", if i == 0 {""} else {", "}, value, raw).unwrap();
+                        }
+                        writeln!(enums, "]);\n").unwrap();
+                    }
+
+                    if gen_types.config.impl_tryfrom_enums && gen_types.config.use_native_enums {
+                        write!(enums, "vk_enum_try_from!({}, [", ename).unwrap();
+                        for (i, (value, v)) in unique_variants(variants).into_iter().enumerate() {
+                            write!(enums, "{}{} => {}", if i == 0 {""} else {", "}, value, unsafe{ &*v.name() }).unwrap();
+                        }
+                        writeln!(enums, "]);\n").unwrap();
+                    }
+
+                    if gen_types.config.result_severity && key == "VkResult" {
+                        let value = if gen_types.config.newtype_enums && !gen_types.config.use_native_enums {
+                            "result.0"
+                        } else {
+                            "result as i32"
+                        };
+                        // Zero is success, positive codes are successful completions that still need
+                        // looking at, and negative codes are errors.
+                        writeln!(enums, "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n{} enum ResultSeverity {{\n    Success,\n    Warning,\n    Error\n}}\n", vis).unwrap();
+                        writeln!(enums, "{vis} fn severity(result: {}) -> ResultSeverity {{\n    let value = {};\n    if value == 0 {{\n        ResultSeverity::Success\n    }} else if value > 0 {{\n        ResultSeverity::Warning\n    }} else {{\n        ResultSeverity::Error\n    }}\n}}\n", ename, value, vis = vis).unwrap();
+                    }
                 }
 
                 // Generate bitmasks
-                Bitmask{name, ref variants} => {
+                Bitmask{name, ref variants, bitwidth, ..} => {
                     let bitmasks = &mut gen_types.bitmasks;
                     let name = unsafe{ &*name };
+                    let emit_docs = gen_types.config.emit_docs;
 
-                    let flags_name = unsafe{ &*processed.types.get("VkFlags").unwrap().name().unwrap() };
+                    let flags_name = match bitwidth {
+                        64 => processed.types.get("VkFlags64").map(|t| unsafe{ &*t.name().unwrap() }).unwrap_or("uint64_t"),
+                        _  => unsafe{ &*processed.types.get("VkFlags").unwrap().name().unwrap() }
+                    };
 
-                    if gen_types.config.wrap_bitmasks {
-                        let mut all_bits = 0;
+                    if gen_types.config.wrap_bitmasks && gen_types.config.bitflags_crate {
+                        // The free constants, so that flags can be named the same way whichever
+                        // macro wraps them.
+                        let mut free_flags = String::with_capacity(variants.len() * 64);
+                        writeln!(bitmasks, "::bitflags::bitflags! {{").unwrap();
+                        if emit_docs {
+                            bitmasks.push_str(&doc_comment("    ", t.comment()));
+                        }
+                        writeln!(bitmasks, "    #[repr(transparent)]\n    #[derive(Default)]\n    {vis} struct {}: {} {{", name, flags_name, vis = vis).unwrap();
+                        for v in variants {unsafe {
+                            let bits =
+                                match *v {
+                                    Value{value, ..}   => value as u64,
+                                    Bitpos{bitpos, ..} => 1u64 << bitpos
+                                };
+                            if emit_docs {
+                                bitmasks.push_str(&doc_comment("        ", v.comment()));
+                            }
+                            writeln!(bitmasks, "        const {} = 0b{:b};", &*v.name(), bits).unwrap();
+                            writeln!(free_flags, "{vis} const {0}: {1} = {1}::{0};", &*v.name(), name, vis = vis).unwrap();
+                        }}
+                        writeln!(bitmasks, "    }}\n}}\n").unwrap();
+                        bitmasks.push_str(&free_flags);
+                        bitmasks.push('\n');
+                    } else if gen_types.config.wrap_bitmasks {
+                        let mut all_bits = 0u64;
+                        // The flag names, passed to the macro so that `Debug` can print set flags by name
+                        let mut flag_names = String::with_capacity(variants.len() * 32);
                         for v in variants {unsafe {
                             let bits =
                                 match *v {
-                                    Value{value, ..}   => value,
-                                    Bitpos{bitpos, ..} => 2isize.pow(bitpos)
+                                    Value{value, ..}   => value as u64,
+                                    Bitpos{bitpos, ..} => 1u64 << bitpos
                                 };
-                            writeln!(bitmasks, "pub const {0}: {1} = {1} {{flags: 0b{2:b}}};", &*v.name(), name, bits).unwrap();
+                            if emit_docs {
+                                bitmasks.push_str(&doc_comment("", v.comment()));
+                            }
+                            writeln!(bitmasks, "{vis} const {0}: {1} = {1} {{flags: 0b{2:b}}};", &*v.name(), name, bits, vis = vis).unwrap();
                             all_bits |= bits;
+
+                            if !flag_names.is_empty() {
+                                flag_names.push_str(", ");
+                            }
+                            flag_names.push_str(&*v.name());
                         }}
 
-                        writeln!(bitmasks, "vk_bitflags_wrapped!({}, 0b{:b}, {});\n", name, all_bits, flags_name).unwrap();
+                        writeln!(bitmasks, "vk_bitflags_wrapped!({}, 0b{:b}, {}, [{}]);\n", name, all_bits, flags_name, flag_names).unwrap();
                     } else {
-                        writeln!(bitmasks, "pub type {} = {};", name, flags_name).unwrap();
+                        if emit_docs {
+                            bitmasks.push_str(&doc_comment("", t.comment()));
+                        }
+                        writeln!(bitmasks, "{vis} type {} = {};", name, flags_name, vis = vis).unwrap();
 
                         for v in variants {unsafe {
                             let bits =
                                 match *v {
-                                    Value{value, ..}   => value,
-                                    Bitpos{bitpos, ..} => 2isize.pow(bitpos)
+                                    Value{value, ..}   => value as u64,
+                                    Bitpos{bitpos, ..} => 1u64 << bitpos
                                 };
-                            writeln!(bitmasks, "pub const {0}: {1} = 0b{2:b};", &*v.name(), name, bits).unwrap();
+                            if emit_docs {
+                                bitmasks.push_str(&doc_comment("", v.comment()));
+                            }
+                            writeln!(bitmasks, "{vis} const {0}: {1} = 0b{2:b};", &*v.name(), name, bits, vis = vis).unwrap();
                         }}
                         bitmasks.push('\n');
                     }
                 }
 
                 // Generate handles
-                Handle{name, dispatchable, ..} => {
+                Handle{name, dispatchable, objtypeenum} => {
                     let handles = &mut gen_types.handles;
                     let name = unsafe{ &*name };
-                    if dispatchable {
-                        writeln!(handles, include_str!("handle_dispatchable.rs"), name).unwrap();
+                    if dispatchable && gen_types.config.wrap_dispatchable_handles {
+                        writeln!(handles, "handle_dispatchable!({0}, {0}_T);", name).unwrap();
+                    } else if dispatchable {
+                        writeln!(handles, include_str!("handle_dispatchable.rs"), name, vis = vis).unwrap();
                     } else {
-                        if gen_types.config.wrap_non_dispatchable_handles {
+                        if gen_types.config.wrap_non_dispatchable_handles && gen_types.config.niche_handles {
+                            writeln!(handles, "handle_nondispatchable!({}, niche);", name).unwrap();
+                        } else if gen_types.config.wrap_non_dispatchable_handles {
                             writeln!(handles, "handle_nondispatchable!({});", name).unwrap();
                         } else {
-                            writeln!(handles, "pub type {} = uint64_t;", name).unwrap();
+                            writeln!(handles, "{vis} type {} = uint64_t;", name, vis = vis).unwrap();
+                        }
+                    }
+
+                    // Unwrapped non-dispatchable handles are all `uint64_t`, so they can't each get an impl.
+                    if dispatchable || gen_types.config.wrap_non_dispatchable_handles {
+                        // Registries that predate `objtypeenum` name the object type after the handle.
+                        let derived_object_type;
+                        let raw_object_type = match to_option(objtypeenum) {
+                            Some(o) => o,
+                            None    => {
+                                derived_object_type = type_variant("VK_OBJECT_TYPE", key);
+                                &derived_object_type
+                            }
+                        };
+
+                        let object_type = processed.variant_names.get(raw_object_type);
+                        if let (Some(object_type), Some(obj_enum)) = (object_type, processed.types.get("VkObjectType")) {
+                            let obj_enum = unsafe{ &*obj_enum.name().unwrap() };
+                            let handle_trait = gen_types.config.handle_trait;
+                            let trait_name = if handle_trait {"VkHandle"} else {"HasObjectType"};
+                            if !has_object_type {
+                                if handle_trait {
+                                    writeln!(handles, "{vis} trait VkHandle: Copy {{\n    const OBJECT_TYPE: {};\n    fn as_raw(self) -> u64;\n}}\n", obj_enum, vis = vis)
+                                } else {
+                                    writeln!(handles, "{vis} trait HasObjectType {{\n    const OBJECT_TYPE: {};\n}}\n", obj_enum, vis = vis)
+                                }.unwrap();
+                                has_object_type = true;
+                            }
+
+                            let object_type = unsafe{ &**object_type };
+                            if gen_types.config.use_native_enums || gen_types.config.newtype_enums {
+                                writeln!(handles, "impl {} for {} {{\n    const OBJECT_TYPE: {2} = {2}::{3};", trait_name, name, obj_enum, object_type)
+                            } else {
+                                writeln!(handles, "impl {} for {} {{\n    const OBJECT_TYPE: {} = {};", trait_name, name, obj_enum, object_type)
+                            }.unwrap();
+                            if handle_trait {
+                                let as_raw =
+                                    if dispatchable && gen_types.config.wrap_dispatchable_handles {"self.0 as usize as u64"}
+                                    else if dispatchable {"self as usize as u64"}
+                                    else if gen_types.config.niche_handles {"self.0.get()"}
+                                    else {"self.0"};
+                                writeln!(handles, "    fn as_raw(self) -> u64 {{\n        {}\n    }}", as_raw).unwrap();
+                            }
+                            handles.push_str("}\n\n");
                         }
                     }
                 }
@@ -956,7 +2344,28 @@ impl<'a> GenTypes<'a> {
                 // Generate typedefs
                 TypeDef{name, typ, ..} => {
                     let (name, typ) = unsafe{ (&*name, &*typ) };
-                    writeln!(gen_types.typedefs, "pub type {} = {};", name, typ).unwrap();
+                    writeln!(gen_types.typedefs, "{vis} type {} = {};", name, typ, vis = vis).unwrap();
+                }
+
+                // Aliases go next to the type they alias, so that they're left out along with it when
+                // only a subset of the types is written.
+                Alias{name, alias_of, deprecated} => {
+                    let aliased = match *processed.registry.types().get(key).unwrap() {
+                        Alias{alias_of, ..} => processed.registry.types().get(processed.registry.resolve_concrete(unsafe{ &*alias_of })),
+                        _                   => unreachable!()
+                    };
+                    let out = match aliased {
+                        Some(&Struct{..})  => &mut gen_types.structs,
+                        Some(&Union{..})   => &mut gen_types.unions,
+                        Some(&Enum{..})    => &mut gen_types.enums,
+                        Some(&Bitmask{..}) => &mut gen_types.bitmasks,
+                        Some(&Handle{..})  => &mut gen_types.handles,
+                        _                  => &mut gen_types.typedefs
+                    };
+                    if let (true, Some(_)) = (processed.config.emit_deprecated, to_option(deprecated)) {
+                        writeln!(out, "#[deprecated(note = \"use {} instead\")]", unsafe{ &*alias_of }).unwrap();
+                    }
+                    writeln!(out, "{vis} type {} = {};\n", unsafe{ &*name }, unsafe{ &*alias_of }, vis = vis).unwrap();
                 }
 
                 // Generate API constants, inferring the type.
@@ -966,67 +2375,27 @@ impl<'a> GenTypes<'a> {
                     let consts = &mut gen_types.consts;
                     let (name, value) = unsafe{ (&*name, (&*value).trim()) };
 
-                    let mut typ = processed.const_types.get(name).map(|t| *t).unwrap_or(Unknown);
-                    let mut slice_indices = (0, value.len());
+                    let hint = processed.const_types.get(name).copied().unwrap_or(Unknown);
 
                     // Ignore enum variants that have been renamed in the Vulkan specs.
-                    if typ == Unknown && value.starts_with("VK_") {
+                    if hint == Unknown && value.starts_with("VK_") {
                         continue;
                     }
 
-                    for (b, c) in value.char_indices() {
-                        match c {
-                            ')'                    => slice_indices.1 = b,
-                            '(' if typ != Str      => slice_indices.0 = b+1,
-                            'U' if typ == Unsigned => (),
-                            'U' if typ != Str      => panic!("Unexpected U in {:?} {}; {}", typ, name, c),
-                            'L' if typ == Unsigned => typ = ULong,
-                            'L' if typ == ULong    => typ = ULongLong,
-                            '"'                    => typ = Str,
-                            '.' if typ == Unsigned => typ = Float,
-                            'f' if typ == Float    => (),
-                            _   if
-                                 typ == Unknown &&
-                                 c.is_digit(10)    => typ = Unsigned,
-                            '-' if
-                                 (typ == Unsigned  ||
-                                  typ == ULongLong ||
-                                  typ == USize     ||
-                                  typ == Float) => (),
-                            _   if
-                                 (typ == Unsigned  ||
-                                  typ == ULongLong ||
-                                  typ == USize     ||
-                                  typ == Float)    &&
-                                 !c.is_digit(10)   => panic!("Unexpected non-digit in {:?} {}; {}", typ, name, c),
-                            _ => ()
-                        }
-                    }
-
-                    let sliced_value = &value[slice_indices.0..slice_indices.1];
-                    match typ {
-                        Unsigned   => write!(consts, "pub const {}: uint32_t = ", name),
-                        ULong      => write!(consts, "pub const {}: c_ulong = ", name),
-                        ULongLong  => write!(consts, "pub const {}: c_ulonglong =", name),
-                        USize      => write!(consts, "pub const {}: size_t = ", name),
-                        Float      => writeln!(consts, "pub const {}: c_float = ", name),
-                        Str        => writeln!(consts, "pub const {}: &'static str = {};", name, sliced_value),
-                        Unknown    => panic!("Unknown const type")
-                    }.unwrap();
-
-                    match typ {
-                        Unsigned   |
-                        ULong      |
-                        ULongLong  |
-                        USize      |
-                        Float     => {
-                            let num_expr = sliced_value.chars().filter(|c| !c.is_alphabetic()).collect::<String>();
-                            if '~' == num_expr.chars().next().unwrap() {
-                                writeln!(consts, "!{};", &num_expr[1..])
-                            } else {writeln!(consts, "{};", num_expr)}.unwrap();
-                        }
-                        _ => ()
-                    }
+                    let (typ, literal) = parse_c_const(value).unwrap_or_else(|| panic!("Unexpected value for {}: {}", name, value));
+                    // Array lengths have to be `usize`, whatever the literal says.
+                    let typ = if hint == USize {USize} else {typ};
+                    let rust_type = match typ {
+                        Unsigned   => "u32",
+                        ULong      => "c_ulong",
+                        ULongLong  => "u64",
+                        USize      => "size_t",
+                        Float      => "f32",
+                        Str        => "&'static str",
+                        Unknown    => unreachable!()
+                    };
+                    write!(consts, "{}", deprecated_attr(processed, "", key, false)).unwrap();
+                    writeln!(consts, "{vis} const {}: {} = {};", name, rust_type, literal, vis = vis).unwrap();
                 }
 
                 // Generate external types
@@ -1034,10 +2403,12 @@ impl<'a> GenTypes<'a> {
                     let externs = &mut gen_types.externs;
                     let (name, requires) = unsafe{ (&*name, &*requires) };
                     if let Some(over) = gen_types.config.extern_type_overrides.iter().find(|o| o.0 == name) {
-                        writeln!(externs, "pub type {} = ::{};", name, over.1).unwrap();
+                        writeln!(externs, "{vis} type {} = ::{};", name, over.1, vis = vis).unwrap();
+                    } else if let (true, Some(def)) = (gen_types.config.define_platform_types, platform_type(name, vis, paths.c_types)) {
+                        writeln!(externs, "{}", def).unwrap();
                     } else if "vk_platform" == requires {
                         if gen_types.config.use_libc_types {
-                            writeln!(&mut gen_types.libc_reexports, "pub use libc::{};", name).unwrap();
+                            writeln!(&mut gen_types.libc_reexports, "{vis} use libc::{};", name, vis = vis).unwrap();
                         } else {
                             let typ =
                                 match name {
@@ -1077,78 +2448,283 @@ impl<'a> GenTypes<'a> {
                                     "c_char" => "u8",
                                     _ => panic!("Unexpected C type")
                                 };
-                            writeln!(externs, "pub type {} = {};", name, typ).unwrap();
+                            writeln!(externs, "{vis} type {} = {};", name, typ, vis = vis).unwrap();
                         }
                     } else {
-                        writeln!(externs, "pub type {} = *const ();", name).unwrap();
+                        writeln!(externs, "{vis} type {} = *const ();", name, vis = vis).unwrap();
                     }
                 }
 
-                // Generate function pointers.
+                // Generate function pointers. Vulkan lets any of them be null, and `Option<fn>` has the same
+                // layout as the bare pointer, so they're all wrapped in one.
                 FuncPointer{name, ref ret, ref params} => {
                     let funcpointers = &mut gen_types.funcpointers;
-                    writeln!(funcpointers, "pub type {} = unsafe extern \"system\" fn(", unsafe{ &*name }).unwrap();
+                    writeln!(funcpointers, "{vis} type {} = Option<unsafe extern \"system\" fn(", unsafe{ &*name }, vis = vis).unwrap();
                     for p in params.iter() {unsafe{
                         write!(funcpointers, "    ").unwrap();
-                        gen_func_param!(funcpointers, p);
+                        gen_func_param!(funcpointers, p).unwrap();
                         funcpointers.push_str(",\n");
                     }}
 
                     if Void == *ret {
-                        writeln!(funcpointers, ");\n").unwrap();
+                        writeln!(funcpointers, ")>;\n").unwrap();
                     } else {
                         write!(funcpointers, ") -> ").unwrap();
-                        match *ret {
-                            ConstPtr(ident, count) => {
-                                for _ in 0..count {
-                                    write!(funcpointers, "*const ").unwrap();
-                                }
-                                writeln!(funcpointers, "{};\n", unsafe{ &*ident })
-                            }
-                            MutPtr(ident, count) => {
-                                for _ in 0..count {
-                                    write!(funcpointers, "*mut ").unwrap();
-                                }
-                                writeln!(funcpointers, "{};\n", unsafe{ &*ident })
-                            }
-                            _ => writeln!(funcpointers, "{};\n", unsafe{ &*ret.type_ptr().unwrap() })
-                        }.unwrap()
+                        unsafe{ gen_func_param!(funcpointers, ret).unwrap() };
+                        writeln!(funcpointers, ">;\n").unwrap();
                     }
                 }
                 _ => ()
             }
+
+            if let Some(cfg) = processed.platform_cfgs.get(key) {
+                for (section, start) in gen_types.gated_sections().iter_mut().zip(section_lens.iter()) {
+                    if section.len() != *start {
+                        let items = section.split_off(*start);
+                        write!(section, "vk_platform_cfg!{{{};\n{}}}\n", cfg, items).unwrap();
+                    }
+                }
+            }
+        }
+
+        if let (false, Some(s_type_enum)) = (structure_types.is_empty(), processed.types.get("VkStructureType")) {
+            let s_type_enum = unsafe{ &*s_type_enum.name().unwrap() };
+            let structs = &mut gen_types.structs;
+            structs.push_str("/// Every struct with an `sType`, paired with its `sType` value.\n");
+            writeln!(structs, "{vis} const STRUCTURE_TYPES: &'static [({}, &'static str)] = &[", s_type_enum, vis = vis).unwrap();
+            for &(ref value, name) in &structure_types {
+                writeln!(structs, "    ({}, \"{}\"),", value, name).unwrap();
+            }
+            structs.push_str("];\n\n");
+            structs.push_str("/// The name of the struct that `s_type` identifies, or `None` if it isn't the `sType` of any of them.\n");
+            writeln!(structs, "{vis} fn structure_type_name(s_type: {}) -> Option<&'static str> {{", s_type_enum, vis = vis).unwrap();
+            structs.push_str("    STRUCTURE_TYPES.iter().find(|&&(t, _)| t == s_type).map(|&(_, name)| name)\n}\n\n");
+        }
+
+        writeln!(gen_types.consts, "/// The names of every extension the bindings were generated with.").unwrap();
+        if processed.config.emit_deprecated {
+            writeln!(gen_types.consts, "#[allow(deprecated)]").unwrap();
+        }
+        writeln!(gen_types.consts, "{vis} const ALL_EXTENSION_NAMES: &'static [&'static str] = &[{}];", processed.extension_names.join(", "), vis = vis).unwrap();
+
+        if gen_types.config.emit_self_tests {
+            gen_types.abi_tests = GenTypes::gen_abi_tests(processed);
         }
 
         gen_types
     }
 
-    pub fn write_types<W: Write>(&self, write: &mut W) {
-        writeln!(write, "{}", include_str!("defines.rs")).unwrap();
+    /// The sections that a platform-specific type can add items to. Constants are left out, since
+    /// they don't refer to any of the platform's types.
+    fn gated_sections(&mut self) -> [&mut String; 8] {
+        [&mut self.structs, &mut self.unions, &mut self.enums, &mut self.bitmasks,
+         &mut self.handles, &mut self.typedefs, &mut self.funcpointers, &mut self.externs]
+    }
+
+    /// Generate the `generated_abi_tests` module, with the expected layouts computed from the raw
+    /// registry by the `layout` module.
+    fn gen_abi_tests(processed: &GenPreproc) -> String {
+        use registry::VkType::*;
+
+        let pointer_size = layout::target_pointer_size();
+        let mut handles = String::new();
+        let mut base_types = String::new();
+        let mut aggregates = String::new();
+
+        for (key, t) in processed.type_ord.iter().map(|k| (*k, processed.types.get(k).unwrap())) {
+            let layout = match layout::type_layout(processed.registry, key, pointer_size) {
+                Some(layout) => layout,
+                None         => continue
+            };
+            let name = match t.name() {
+                Some(name) => unsafe{ &*name },
+                None       => continue
+            };
+
+            let gate = processed.platform_cfgs.get(key).map_or(String::new(), |cfg| format!("#[cfg({})] ", cfg));
+
+            match *t {
+                Handle{..}  => writeln!(handles, "        {}assert_eq!(size_of::<{}>(), {});", gate, name, layout.size).unwrap(),
+                TypeDef{..} => writeln!(base_types, "        {}assert_eq!(size_of::<{}>(), {});", gate, name, layout.size).unwrap(),
+                Struct{..}  |
+                Union{..}   => writeln!(aggregates, "        {}assert_eq!((size_of::<{1}>(), align_of::<{1}>()), ({2}, {3}), \"{1}\");",
+                                        gate, name, layout.size, layout.align).unwrap(),
+                _           => ()
+            }
+        }
+
+        let mut tests = String::new();
+        writeln!(tests, "#[cfg(test)]\nmod generated_abi_tests {{").unwrap();
+        writeln!(tests, "    use super::*;\n    use {}::mem::{{size_of, align_of}};", processed.config.std_paths().core).unwrap();
+        for &(test_name, ref asserts) in &[("handle_sizes", handles), ("base_type_sizes", base_types), ("struct_layouts", aggregates)] {
+            writeln!(tests, "\n    #[test]\n    fn {}() {{\n{}    }}", test_name, asserts).unwrap();
+        }
+        tests.push_str("}\n");
+        tests
+    }
+
+    pub fn write_types<W: Write>(&self, write: &mut W) -> io::Result<()> {
+        let paths = self.config.std_paths();
+        let vis = self.config.visibility;
+        let uint32_path = match self.config.macro_root {
+            Some(root) => format!("$crate::{}::uint32_t", root),
+            None       => "uint32_t".to_owned()
+        };
+        let mut defines = format!(include_str!("defines.rs"), uint32_path, vis = vis);
+        if let Some(prefix) = self.config.symbol_prefix {
+            // Exported macros all live at the crate root, so they need the prefix to stay apart.
+            defines = defines.replace("macro_rules! vk_", &format!("macro_rules! {}vk_", prefix));
+        }
+        writeln!(write, "{}", defines)?;
 
-        writeln!(write, "mod libc_reexports {{").unwrap();
-        writeln!(write, "{}", &self.libc_reexports).unwrap();
-        writeln!(write, "}}").unwrap();
+        writeln!(write, "mod libc_reexports {{")?;
+        writeln!(write, "{}", self.libc_reexports)?;
+        writeln!(write, "}}")?;
 
-        writeln!(write, "pub mod types {{").unwrap();
-        writeln!(write, "#![allow(non_camel_case_types, dead_code)]").unwrap();
+        writeln!(write, "{} mod types {{", vis)?;
+        writeln!(write, "#![allow(non_camel_case_types, dead_code)]")?;
         if !self.config.snake_case_members {
-            writeln!(write, "#![allow(non_snake_case)]").unwrap();
+            writeln!(write, "#![allow(non_snake_case)]")?;
         }
         if self.config.wrap_bitmasks {
-            writeln!(write, "use std::ops::*;").unwrap();
+            writeln!(write, "use {}::ops::*;", paths.core)?;
+        }
+
+        writeln!(write, "use {0}::fmt; use {0}::ffi::CStr; #[allow(unused_imports)]use super::libc_reexports::*;", paths.core)?;
+        if self.uses_prelude || self.config.consts_module {
+            writeln!(write, "use super::*;")?;
+        }
+        let subset = self.config.types_subset;
+        writeln!(write, "{}", self.externs)?;
+        writeln!(write, "{}", self.typedefs)?;
+        if subset.enums_and_consts() && !self.config.consts_module {
+            writeln!(write, "{}", self.consts)?;
+        }
+        if subset.structs() {
+            writeln!(write, "{}", self.structs)?;
+            writeln!(write, "{}", self.unions)?;
+        }
+        if subset.enums_and_consts() {
+            writeln!(write, "{}", self.enums)?;
+        }
+        if subset.structs() {
+            writeln!(write, "{}", self.handles)?;
+        }
+        if subset.enums_and_consts() {
+            writeln!(write, "{}", self.bitmasks)?;
+        }
+        if subset.structs() {
+            writeln!(write, "{}", self.funcpointers)?;
+        }
+        writeln!(write, "}}")?;
+
+        if subset.enums_and_consts() && self.config.consts_module {
+            writeln!(write, "{0} use self::consts::*;\n{0} mod consts {{", vis)?;
+            writeln!(write, "#![allow(dead_code)]")?;
+            writeln!(write, "#[allow(unused_imports)] use super::*;")?;
+            writeln!(write, "{}", self.consts)?;
+            writeln!(write, "}}")?;
+        }
+
+        // The tests cover types from both halves, so they're only emitted when everything is.
+        if self.config.emit_self_tests && subset == TypesSubset::All {
+            writeln!(write, "{}", self.abi_tests)?;
+        }
+        Ok(())
+    }
+}
+
+/// Statistics about a set of generated bindings, returned by the generation functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GenStats {
+    /// The number of types that were generated.
+    pub types: usize,
+    /// The number of commands that were generated.
+    pub commands: usize,
+    /// The number of lines written.
+    pub lines: usize,
+    /// The number of bytes written.
+    pub bytes: usize,
+    /// How long each stage of generation took.
+    pub timings: Timings
+}
+
+/// How long each stage of creating a set of bindings took, for finding out what's slowing down a
+/// build script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+    /// Reading and crawling the xml into the registry.
+    pub crawl: Duration,
+    /// Selecting the types and commands required by the version and extensions.
+    pub select: Duration,
+    /// Turning the selected types and commands into Rust code.
+    pub transform: Duration,
+    /// Writing the code out.
+    pub emit: Duration
+}
+
+impl Timings {
+    /// The time taken by every stage together.
+    pub fn total(&self) -> Duration {
+        self.crawl + self.select + self.transform + self.emit
+    }
+}
+
+/// Passes writes through to `inner`, keeping count of the lines and bytes that went by and of how
+/// long the writes took.
+struct CountingWriter<'w, W: Write + 'w> {
+    inner: &'w mut W,
+    lines: usize,
+    bytes: usize,
+    start: Instant,
+    emit: Duration
+}
+
+impl<'w, W: Write> CountingWriter<'w, W> {
+    fn new(inner: &'w mut W) -> CountingWriter<'w, W> {
+        CountingWriter {
+            inner,
+            lines: 0,
+            bytes: 0,
+            start: Instant::now(),
+            emit: Duration::from_secs(0)
+        }
+    }
+
+    /// The writer is created before the preprocessor, so whatever time since then wasn't spent on
+    /// selection or writing was spent on transforming.
+    fn stats(&self, preproc: &GenPreproc) -> GenStats {
+        let transform = self.start.elapsed()
+            .checked_sub(preproc.select_time + self.emit)
+            .unwrap_or(Duration::from_secs(0));
+        GenStats {
+            types: preproc.types.len(),
+            commands: preproc.commands.len(),
+            lines: self.lines,
+            bytes: self.bytes,
+            timings: Timings {
+                crawl: preproc.registry.crawl_time(),
+                select: preproc.select_time,
+                transform,
+                emit: self.emit
+            }
         }
+    }
+}
+
+impl<'w, W: Write> Write for CountingWriter<'w, W> {
+    fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+        let start = Instant::now();
+        let written = self.inner.write(buf);
+        self.emit += start.elapsed();
+        let written = written?;
+        self.lines += buf[..written].iter().filter(|b| **b == b'\n').count();
+        self.bytes += written;
+        Ok(written)
+    }
 
-        writeln!(write, "use std::fmt; use std::ffi::CStr; use super::*; #[allow(unused_imports)]use super::libc_reexports::*;").unwrap();
-        writeln!(write, "{}", &self.externs).unwrap();
-        writeln!(write, "{}", &self.typedefs).unwrap();
-        writeln!(write, "{}", &self.consts).unwrap();
-        writeln!(write, "{}", &self.structs).unwrap();
-        writeln!(write, "{}", &self.unions).unwrap();
-        writeln!(write, "{}", &self.enums).unwrap();
-        writeln!(write, "{}", &self.handles).unwrap();
-        writeln!(write, "{}", &self.bitmasks).unwrap();
-        writeln!(write, "{}", &self.funcpointers).unwrap();
-        writeln!(write, "}}").unwrap();
+    fn flush(&mut self) -> ::std::io::Result<()> {
+        self.inner.flush()
     }
 }
 
@@ -1167,9 +2743,77 @@ pub enum ConstType {
     Unknown
 }
 
+/// Parse the C literal `value` of an API constant, giving its type and the equivalent Rust literal.
+/// Handles the forms the registry uses: strings, floats with an `f` suffix, integers with `U`, `UL`
+/// or `ULL` suffixes, and complemented integers with an offset, like `(~0U-1)`. Returns `None` for
+/// anything else.
+fn parse_c_const(value: &str) -> Option<(ConstType, String)> {
+    use self::ConstType::*;
+
+    let mut value = value.trim();
+    while value.starts_with('(') && value.ends_with(')') {
+        value = value[1..value.len() - 1].trim();
+    }
+    if let Some(rest) = value.strip_prefix('"') {
+        return match rest.find('"') {
+            Some(end) if end + 1 == rest.len() => Some((Str, value.to_owned())),
+            _                                  => None
+        };
+    }
+
+    let (complement, value) = match value.strip_prefix('~') {
+        Some(value) => (true, value.trim()),
+        None        => (false, value)
+    };
+    let hex = value.starts_with("0x") || value.starts_with("0X");
+    let digits_start = if hex {2} else {0};
+    let digits_end = value[digits_start..]
+        .find(|c: char| !(if hex {c.is_ascii_hexdigit()} else {c.is_ascii_digit() || c == '.'}))
+        .map_or(value.len(), |i| i + digits_start);
+    let (number, value) = value.split_at(digits_end);
+    let suffix_end = value.find(|c: char| !c.is_alphabetic()).unwrap_or(value.len());
+    let (suffix, offset) = value.split_at(suffix_end);
+    if number.len() == digits_start {
+        return None;
+    }
+
+    // Anything after the suffix has to be a plain offset, like the `-1` in `(~0U-1)`.
+    let offset: String = offset.chars().filter(|c| !c.is_whitespace()).collect();
+    let valid_offset = offset.is_empty() || (offset.len() > 1 &&
+        (offset.starts_with('-') || offset.starts_with('+')) &&
+        offset[1..].chars().all(|c| c.is_ascii_digit()));
+    if !valid_offset {
+        return None;
+    }
+
+    let typ = if number.contains('.') {
+        match suffix {
+            "f" | "F" | "" if !complement && offset.is_empty() => Float,
+            _                                                  => return None
+        }
+    } else {
+        match &suffix.to_uppercase()[..] {
+            "" | "U" => Unsigned,
+            "UL"     => ULong,
+            "ULL"    => ULongLong,
+            _        => return None
+        }
+    };
+
+    let mut literal = String::with_capacity(number.len() + offset.len() + 1);
+    if complement {
+        literal.push('!');
+    }
+    literal.push_str(number);
+    literal.push_str(&offset);
+    Some((typ, literal))
+}
+
 impl<'a> VkRegistry<'a> {
     /// Write global bindings for Vulkan API [`version`] \(1.0, 1.1, etc.) to the file [`write`] with
-    /// the specified `extensions` and [`config`]
+    /// the specified `extensions` and [`config`], returning [`GenStats`] on what was written. Any error
+    /// from writing to `write` is returned instead, leaving the output truncated.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -1190,45 +2834,105 @@ impl<'a> VkRegistry<'a> {
     ///     VkVersion(1, 0),
     ///     &[],
     ///     GenConfig::new()
-    /// );
+    /// ).unwrap();
     /// ```
     ///
     /// [`config`]: ./struct.GenConfig.html
+    /// [`GenStats`]: ./struct.GenStats.html
     /// [`version`]: ./struct.VkVersion.html
     /// [`write`]: https://doc.rust-lang.org/stable/std/io/trait.Write.html
-    pub fn gen_global<W: Write>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig) {
-        let preproc = GenPreproc::new(self, version, extensions, config);
+    pub fn gen_global<W: Write>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig) -> io::Result<GenStats> {
+        self.gen_global_filtered(write, version, extensions, config, |_| true)
+    }
 
-        writeln!(write, "{}", include_str!("prelude_common.rs")).unwrap();
-        writeln!(write, "{}", include_str!("prelude_global_gen.rs")).unwrap();
+    /// Write global bindings like [`gen_global()`], but only for the commands, types and constants whose
+    /// raw names (like `"vkCreateInstance"` or `"VkApplicationInfo"`) are accepted by `filter`. Anything
+    /// an accepted item depends on is still generated, even if `filter` rejects it. Enum variants added
+    /// by extensions come along with the enum they extend.
+    ///
+    /// [`gen_global()`]: #method.gen_global
+    pub fn gen_global_filtered<W: Write, F: Fn(&str) -> bool>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig, filter: F) -> io::Result<GenStats> {
+        let write = &mut CountingWriter::new(write);
+        let preproc = GenPreproc::new_filtered(self, version, extensions, config, filter);
+        if preproc.config.strict {
+            preproc.check_references()?;
+        }
+
+        let paths = preproc.config.std_paths();
+        let vis = preproc.config.visibility;
+        writeln!(write, include_str!("prelude_common.rs"), vis = vis, core = paths.core, alloc = paths.alloc)?;
+        writeln!(write, include_str!("prelude_global_gen.rs"), vis = vis, core = paths.core)?;
         let gen_types = GenTypes::new(&preproc);
-        gen_types.write_types(write);
+        gen_types.write_types(write)?;
 
-        writeln!(write, "pub mod cmds {{").unwrap();
-        writeln!(write, "#![allow(dead_code)]").unwrap();
+        writeln!(write, "{} mod cmds {{", vis)?;
+        writeln!(write, "#![allow(dead_code)]")?;
         if !preproc.config.snake_case_commands || !preproc.config.snake_case_members {
-            writeln!(write, "#![allow(non_snake_case)]").unwrap();
+            writeln!(write, "#![allow(non_snake_case)]")?;
         }
-        writeln!(write, "use super::*; #[allow(unused_imports)] use super::libc_reexports::*;").unwrap();
+        writeln!(write, "use super::*; #[allow(unused_imports)] use super::libc_reexports::*;")?;
 
-        writeln!(write, "vk_functions!{{").unwrap();
+        let bool32 = bool32_param_type(&preproc);
+        writeln!(write, "vk_functions!{{{};", preproc.config.load_reporting.macro_arm())?;
         for (c, r) in preproc.commands.iter().zip(preproc.commands_raw.iter()) {unsafe{
-            writeln!(write, "    \"{}\", {}(", r, &*c.name).unwrap();
+            if preproc.config.emit_docs {
+                write!(write, "{}", doc_comment("    ", to_option(c.comment)))?;
+            }
+            if preproc.config.param_docs {
+                write!(write, "{}", param_docs(c))?;
+            }
+            write!(write, "{}", deprecated_attr(&preproc, "    ", r, false))?;
+            if let Some(cfg) = preproc.platform_cfgs.get(r) {
+                writeln!(write, "    if cfg({})", cfg)?;
+            }
+            writeln!(write, "    \"{}\", {}(", r, &*c.name)?;
             for p in c.params.iter() {
-                write!(write, "        {}: ", &*p.name).unwrap();
-                gen_func_param!(write, &p.typ);
-                writeln!(write, ",").unwrap();
+                write!(write, "        {}: ", &*p.name)?;
+                match (bool32, &p.typ) {
+                    (Some(bool32), &VkElType::Var(t)) if *bool32 == *t   => write!(write, "bool as {}", bool32)?,
+                    _                                                    => gen_func_param!(write, &p.typ)?
+                }
+                writeln!(write, ",")?;
             }
-            write!(write, "    ) -> ").unwrap();
-            gen_func_param!(write, &c.ret);
-            writeln!(write, ";\n").unwrap();
+            write!(write, "    ) -> ")?;
+            gen_func_param!(write, &c.ret)?;
+            writeln!(write, ";\n")?;
         }}
-        writeln!(write, "}}}}").unwrap();
-        writeln!(write, "}} // end module").unwrap();
+        writeln!(write, "}}")?;
+        writeln!(write, "{}", gen_load_with_loader(&preproc))?;
+        if preproc.config.per_command_success_checks {
+            writeln!(write, "{}", gen_success_checks(&preproc))?;
+        }
+        if preproc.config.result_wrappers {
+            writeln!(write, "{}", gen_result_wrappers(&preproc, false))?;
+        }
+        writeln!(write, "}}")?;
+        if preproc.config.group_by_extension {
+            write_extension_modules(write, &preproc, true)?;
+        }
+        writeln!(write, "}} // end module")?;
+
+        let stats = write.stats(&preproc);
+        preproc.registry.diagnose(Diagnostic::Generated(stats));
+        Ok(stats)
+    }
+
+    /// Write global bindings for the API version labeled by the registry feature `feature` (e.g.
+    /// `"VK_VERSION_1_0"`). Otherwise identical to [`gen_global()`].
+    ///
+    /// # Panics
+    /// Panics if the registry has no feature named `feature`.
+    ///
+    /// [`gen_global()`]: #method.gen_global
+    pub fn gen_global_by_feature<W: Write>(&self, write: &mut W, feature: &str, extensions: &[&str], config: GenConfig) -> io::Result<GenStats> {
+        let version = self.feature_version(feature).unwrap_or_else(|| panic!("Unknown feature: {}", feature));
+        self.gen_global(write, version, extensions, config)
     }
 
     /// Write struct bindings for Vulkan API [`version`] \(1.0, 1.1, etc.) to the file [`write`] with
-    /// the specified `extensions` and [`config`]
+    /// the specified `extensions` and [`config`], returning [`GenStats`] on what was written. Any error
+    /// from writing to `write` is returned instead, leaving the output truncated.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -1249,42 +2953,913 @@ impl<'a> VkRegistry<'a> {
     ///     VkVersion(1, 0),
     ///     &[],
     ///     GenConfig::new()
-    /// );
+    /// ).unwrap();
     /// ```
     ///
     /// [`config`]: ./struct.GenConfig.html
+    /// [`GenStats`]: ./struct.GenStats.html
     /// [`version`]: ./struct.VkVersion.html
     /// [`write`]: https://doc.rust-lang.org/stable/std/io/trait.Write.html
-    pub fn gen_struct<W: Write>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig) {
+    pub fn gen_struct<W: Write>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig) -> io::Result<GenStats> {
+        self.gen_struct_filtered(write, version, extensions, config, |_| true)
+    }
+
+    /// Write struct bindings like [`gen_struct()`], but only for the commands, types and constants whose
+    /// raw names (like `"vkCreateInstance"` or `"VkApplicationInfo"`) are accepted by `filter`. Anything
+    /// an accepted item depends on is still generated, even if `filter` rejects it. Enum variants added
+    /// by extensions come along with the enum they extend.
+    ///
+    /// [`gen_struct()`]: #method.gen_struct
+    pub fn gen_struct_filtered<W: Write, F: Fn(&str) -> bool>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig, filter: F) -> io::Result<GenStats> {
+        let write = &mut CountingWriter::new(write);
+        let preproc = GenPreproc::new_filtered(self, version, extensions, config, filter);
+        if preproc.config.strict {
+            preproc.check_references()?;
+        }
+
+        let paths = preproc.config.std_paths();
+        let vis = preproc.config.visibility;
+        writeln!(write, include_str!("prelude_common.rs"), vis = vis, core = paths.core, alloc = paths.alloc)?;
+        writeln!(write, include_str!("prelude_struct_gen.rs"), vis = vis, core = paths.core)?;
+        let gen_types = GenTypes::new(&preproc);
+        gen_types.write_types(write)?;
+
+        writeln!(write, "{} mod cmds {{", vis)?;
+        writeln!(write, "#![allow(dead_code)]")?;
+        writeln!(write, "#![allow(non_camel_case_types)]")?;
+        if !preproc.config.snake_case_members || !preproc.config.snake_case_commands {
+            writeln!(write, "#![allow(non_snake_case)]")?;
+        }
+        writeln!(write, "use super::*; #[allow(unused_imports)] use super::libc_reexports::*;")?;
+
+        let commands_trait = if preproc.config.wrap_commands_in_trait {Some("VkCommands")} else {None};
+        write_struct_bindings(write, &preproc, commands_trait, |_| true)?;
+
+        // `Vk` and `FnPtr` are both `#[repr(C)]` and `FnPtr` leads with the function pointer, so the
+        // pointer for the nth command sits at `n * size_of::<FnPtr>()` from the start of the struct.
+        writeln!(write, "impl Vk {{\n    {} const COMMAND_OFFSETS: &'static [(&'static str, usize)] = &[", vis)?;
+        for (i, r) in preproc.commands_raw.iter().enumerate() {
+            writeln!(write, "        (\"{}\", {} * ::{}::mem::size_of::<FnPtr>()),", r, i, paths.core)?;
+        }
+        writeln!(write, "    ];\n}}")?;
+        if preproc.config.per_command_success_checks {
+            writeln!(write, "{}", gen_success_checks(&preproc))?;
+        }
+        if preproc.config.safe_slice_wrappers {
+            writeln!(write, "{}", gen_slice_wrappers(&preproc))?;
+        }
+        if preproc.config.result_wrappers {
+            writeln!(write, "{}", gen_result_wrappers(&preproc, true))?;
+        }
+        writeln!(write, "}}")?;
+        if preproc.config.group_by_extension {
+            write_extension_modules(write, &preproc, false)?;
+        }
+        writeln!(write, "}} // end module")?;
+
+        let stats = write.stats(&preproc);
+        preproc.registry.diagnose(Diagnostic::Generated(stats));
+        Ok(stats)
+    }
+
+    /// Write struct bindings for the API version labeled by the registry feature `feature` (e.g.
+    /// `"VK_VERSION_1_0"`). Otherwise identical to [`gen_struct()`].
+    ///
+    /// # Panics
+    /// Panics if the registry has no feature named `feature`.
+    ///
+    /// [`gen_struct()`]: #method.gen_struct
+    pub fn gen_struct_by_feature<W: Write>(&self, write: &mut W, feature: &str, extensions: &[&str], config: GenConfig) -> io::Result<GenStats> {
+        let version = self.feature_version(feature).unwrap_or_else(|| panic!("Unknown feature: {}", feature));
+        self.gen_struct(write, version, extensions, config)
+    }
+
+    /// Write struct bindings like [`gen_struct()`], but with the commands split across three dispatch
+    /// tables instead of a single `Vk` struct: `VkEntry` for the commands that don't take a dispatchable
+    /// handle, `VkInstanceFns` for the ones taking a `VkInstance` or `VkPhysicalDevice`, and `VkDeviceFns`
+    /// for the ones taking a `VkDevice`, `VkQueue` or `VkCommandBuffer`. Each table has its own
+    /// `load_with()`, so device commands can be loaded through `vkGetDeviceProcAddr` and skip the
+    /// loader's dispatch.
+    ///
+    /// `vkGetInstanceProcAddr` is put in `VkEntry` and `vkGetDeviceProcAddr` in `VkInstanceFns`, since
+    /// those are what load the next table down. `safe_slice_wrappers` isn't supported here.
+    ///
+    /// [`gen_struct()`]: #method.gen_struct
+    pub fn gen_dispatch_tables<W: Write>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig) -> io::Result<GenStats> {
+        let write = &mut CountingWriter::new(write);
         let preproc = GenPreproc::new(self, version, extensions, config);
+        if preproc.config.strict {
+            preproc.check_references()?;
+        }
 
-        writeln!(write, "{}", include_str!("prelude_common.rs")).unwrap();
-        writeln!(write, "{}", include_str!("prelude_struct_gen.rs")).unwrap();
+        let paths = preproc.config.std_paths();
+        let vis = preproc.config.visibility;
+        writeln!(write, include_str!("prelude_common.rs"), vis = vis, core = paths.core, alloc = paths.alloc)?;
+        writeln!(write, include_str!("prelude_struct_gen.rs"), vis = vis, core = paths.core)?;
         let gen_types = GenTypes::new(&preproc);
-        gen_types.write_types(write);
+        gen_types.write_types(write)?;
 
-        writeln!(write, "pub mod cmds {{").unwrap();
-        writeln!(write, "#![allow(dead_code)]").unwrap();
-        writeln!(write, "#![allow(non_camel_case_types)]").unwrap();
+        writeln!(write, "{} mod cmds {{", vis)?;
+        writeln!(write, "#![allow(dead_code)]")?;
+        writeln!(write, "#![allow(non_camel_case_types)]")?;
         if !preproc.config.snake_case_members || !preproc.config.snake_case_commands {
-            writeln!(write, "#![allow(non_snake_case)]").unwrap();
+            writeln!(write, "#![allow(non_snake_case)]")?;
+        }
+        writeln!(write, "use super::*; #[allow(unused_imports)] use super::libc_reexports::*;")?;
+
+        let levels: HashMap<&str, CommandLevel> = preproc.commands_raw.iter()
+            .map(|r| (*r, CommandLevel::of(r, self.commands().get(r).unwrap())))
+            .collect();
+        for &(level, module, table) in &[(CommandLevel::Entry, "entry", "VkEntry"),
+                                         (CommandLevel::Instance, "instance", "VkInstanceFns"),
+                                         (CommandLevel::Device, "device", "VkDeviceFns")] {
+            writeln!(write, "{} mod {} {{", vis, module)?;
+            writeln!(write, "use super::*;")?;
+            write_struct_bindings(write, &preproc, None, |r| levels[r] == level)?;
+            writeln!(write, "}}")?;
+            writeln!(write, "{} use self::{}::Vk as {};", vis, module, table)?;
+        }
+        if preproc.config.per_command_success_checks {
+            writeln!(write, "{}", gen_success_checks(&preproc))?;
+        }
+        writeln!(write, "}}")?;
+        if preproc.config.group_by_extension {
+            write_extension_modules(write, &preproc, false)?;
+        }
+        writeln!(write, "}} // end module")?;
+
+        let stats = write.stats(&preproc);
+        preproc.registry.diagnose(Diagnostic::Generated(stats));
+        Ok(stats)
+    }
+
+    /// Write raw bindings for Vulkan API [`version`] \(1.0, 1.1, etc.) to the file [`write`] with
+    /// the specified `extensions` and [`config`], returning [`GenStats`] on what was written or any
+    /// error from writing. These are the thinnest bindings the generator can produce: the types, a
+    /// `PFN_<command>` function pointer type for each command, the `COMMAND_NAMES` table, and a
+    /// `load()` function that fills an array of function pointers in the same order as the table.
+    /// Dispatch is left up to the user, so there are no wrapper functions.
+    ///
+    /// [`config`]: ./struct.GenConfig.html
+    /// [`GenStats`]: ./struct.GenStats.html
+    /// [`version`]: ./struct.VkVersion.html
+    /// [`write`]: https://doc.rust-lang.org/stable/std/io/trait.Write.html
+    pub fn gen_raw<W: Write>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig) -> io::Result<GenStats> {
+        let write = &mut CountingWriter::new(write);
+        let preproc = GenPreproc::new(self, version, extensions, config);
+        if preproc.config.strict {
+            preproc.check_references()?;
         }
-        writeln!(write, "use super::*; #[allow(unused_imports)] use super::libc_reexports::*;").unwrap();
 
-        writeln!(write, "vk_struct_bindings!{{").unwrap();
+        let paths = preproc.config.std_paths();
+        let vis = preproc.config.visibility;
+        writeln!(write, include_str!("prelude_common.rs"), vis = vis, core = paths.core, alloc = paths.alloc)?;
+        let gen_types = GenTypes::new(&preproc);
+        gen_types.write_types(write)?;
+
+        let mut cmds = String::with_capacity(2usize.pow(15));
         for (c, r) in preproc.commands.iter().zip(preproc.commands_raw.iter()) {unsafe{
-            writeln!(write, "    \"{}\", {}(", r, &*c.name).unwrap();
+            if let Some(cfg) = preproc.platform_cfgs.get(r) {
+                writeln!(cmds, "#[cfg({})]", cfg).unwrap();
+            }
+            writeln!(cmds, "{vis} type PFN_{} = unsafe extern \"system\" fn(", r, vis = vis).unwrap();
             for p in c.params.iter() {
-                write!(write, "        {}: ", &*p.name).unwrap();
-                gen_func_param!(write, &p.typ);
-                writeln!(write, ",").unwrap();
+                write!(cmds, "    {}: ", &*p.name).unwrap();
+                gen_func_param!(cmds, &p.typ).unwrap();
+                writeln!(cmds, ",").unwrap();
             }
-            write!(write, "    ) -> ").unwrap();
-            gen_func_param!(write, &c.ret);
-            writeln!(write, ";\n").unwrap();
+            write!(cmds, ") -> ").unwrap();
+            gen_func_param!(cmds, &c.ret).unwrap();
+            writeln!(cmds, ";\n").unwrap();
         }}
-        writeln!(write, "}}}}").unwrap();
-        writeln!(write, "}} // end module").unwrap();
+
+        let count = preproc.commands_raw.len();
+        writeln!(cmds, "/// The raw names of the commands, in the order `load()` loads them in.").unwrap();
+        writeln!(cmds, "{vis} const COMMAND_NAMES: [&'static str; {}] = [", count, vis = vis).unwrap();
+        for r in &preproc.commands_raw {
+            writeln!(cmds, "    \"{}\",", r).unwrap();
+        }
+        writeln!(cmds, "];\n").unwrap();
+        writeln!(cmds, "/// Load every command in `COMMAND_NAMES` with `load_fn`, returning the function pointers in the same\n\
+                        /// order. Commands that couldn't be loaded are left null.").unwrap();
+        writeln!(cmds, "{vis} fn load<F: FnMut(&str) -> *const ()>(mut load_fn: F) -> [*const (); {}] {{", count, vis = vis).unwrap();
+        writeln!(cmds, "    let mut fns = [::{}::ptr::null(); {}];", preproc.config.std_paths().core, count).unwrap();
+        writeln!(cmds, "    for (f, name) in fns.iter_mut().zip(COMMAND_NAMES.iter()) {{\n        *f = load_fn(name);\n    }}").unwrap();
+        writeln!(cmds, "    fns\n}}").unwrap();
+
+        writeln!(write, "{} mod cmds {{", vis)?;
+        writeln!(write, "#![allow(dead_code, non_camel_case_types)]")?;
+        if !preproc.config.snake_case_members {
+            writeln!(write, "#![allow(non_snake_case)]")?;
+        }
+        writeln!(write, "use super::*; #[allow(unused_imports)] use super::libc_reexports::*;")?;
+        writeln!(write, "{}", cmds)?;
+        writeln!(write, "}}")?;
+        if preproc.config.group_by_extension {
+            write_extension_modules(write, &preproc, false)?;
+        }
+        writeln!(write, "}} // end module")?;
+
+        let stats = write.stats(&preproc);
+        preproc.registry.diagnose(Diagnostic::Generated(stats));
+        Ok(stats)
+    }
+
+    /// Write only the type bindings for Vulkan API [`version`] \(1.0, 1.1, etc.) to the file [`write`]
+    /// with the specified `extensions` and [`config`], returning [`GenStats`] on what was written or any
+    /// error from writing. The types the commands need are still pulled in, but no commands or loaders
+    /// are generated, so the output can live in its own crate. `GenConfig::types_subset` can split that
+    /// crate further.
+    ///
+    /// [`config`]: ./struct.GenConfig.html
+    /// [`GenStats`]: ./struct.GenStats.html
+    /// [`version`]: ./struct.VkVersion.html
+    /// [`write`]: https://doc.rust-lang.org/stable/std/io/trait.Write.html
+    pub fn gen_types_only<W: Write>(&self, write: &mut W, version: VkVersion, extensions: &[&str], config: GenConfig) -> io::Result<GenStats> {
+        let write = &mut CountingWriter::new(write);
+        let preproc = GenPreproc::new(self, version, extensions, config);
+        if preproc.config.strict {
+            preproc.check_references()?;
+        }
+
+        let paths = preproc.config.std_paths();
+        let vis = preproc.config.visibility;
+        writeln!(write, include_str!("prelude_common.rs"), vis = vis, core = paths.core, alloc = paths.alloc)?;
+        let gen_types = GenTypes::new(&preproc);
+        gen_types.write_types(write)?;
+
+        // The prelude re-exports `cmds`, so it has to exist even though it's empty.
+        writeln!(write, "{} mod cmds {{}}", vis)?;
+        if preproc.config.group_by_extension {
+            write_extension_modules(write, &preproc, false)?;
+        }
+        writeln!(write, "}} // end module")?;
+
+        let stats = GenStats {
+            commands: 0,
+            ..write.stats(&preproc)
+        };
+        preproc.registry.diagnose(Diagnostic::Generated(stats));
+        Ok(stats)
+    }
+}
+
+/// Write a module for each of the included extensions, re-exporting the items it introduces from the
+/// root of the bindings, for `group_by_extension`. Commands are only re-exported if `commands` is
+/// true, since the global bindings are the only ones where they're free functions.
+fn write_extension_modules<W: Write>(write: &mut W, preproc: &GenPreproc, commands: bool) -> io::Result<()> {
+    use registry::VkType::*;
+
+    let subset = preproc.config.types_subset;
+    let vis = preproc.config.visibility;
+    let const_variants = !preproc.config.use_native_enums && !preproc.config.newtype_enums;
+    // Whether or not `typ` gets written out with the current types subset, mirroring `GenTypes`.
+    let emitted = |typ: &VkType| match *typ {
+        Struct{..} | Union{..} | Handle{..} | FuncPointer{..} => subset.structs(),
+        Enum{..} | Bitmask{..}                                => subset.enums_and_consts(),
+        ApiConst{name, value}                                 => subset.enums_and_consts() &&
+            (preproc.const_types.contains_key(unsafe{ &*name }) || !unsafe{ &*value }.trim().starts_with("VK_")),
+        TypeDef{..}                                           => true,
+        _                                                     => false
+    };
+
+    let mut module = String::with_capacity(2usize.pow(10));
+    for &(ex, ref introduced) in &preproc.extensions {
+        let mut items: Vec<&str> = Vec::with_capacity(ex.require.len());
+        for key in &preproc.type_ord[introduced.clone()] {
+            let t = preproc.types.get(key).unwrap();
+            // Aliases are written wherever the type they alias is.
+            let concrete = match *t {
+                Alias{..} => preproc.registry.types().get(preproc.registry.resolve_concrete(key)),
+                _         => Some(t)
+            };
+            if concrete.is_some_and(emitted) {
+                items.push(unsafe{ &*t.name().unwrap() });
+            }
+        }
+
+        for req in &ex.require {
+            let item = match *req {
+                VkInterface::Command{name, ..} if commands =>
+                    preproc.commands_raw.iter().position(|r| *r == unsafe{ &*name })
+                        .map(|i| unsafe{ &*preproc.commands[i].name }),
+                // Variants are only items of their own when they're generated as constants.
+                VkInterface::ExtnEnum{extends, ref variant, ..} => {
+                    let variants = match preproc.types.get(unsafe{ &*extends }) {
+                        Some(Bitmask{variants, ..})                => Some(variants),
+                        Some(Enum{variants, ..}) if const_variants => Some(variants),
+                        _                                          => None
+                    };
+                    variants.filter(|_| subset.enums_and_consts()).and_then(|variants| {
+                        let processed = unsafe{ &**preproc.variant_names.get(&*variant.name())? };
+                        // Variants can be removed by `variant_overrides`.
+                        variants.iter().any(|v| unsafe{ &*v.name() } == processed).as_some(processed)
+                    })
+                },
+                _ => None
+            };
+
+            if let Some(item) = item {
+                if !items.contains(&item) {
+                    items.push(item);
+                }
+            }
+        }
+
+        let ex_name = unsafe{ &*ex.name };
+        module.clear();
+        writeln!(module, "/// The items introduced by `{}`.", ex_name).unwrap();
+        write!(module, "{}", deprecated_attr(preproc, "", ex_name, false)).unwrap();
+        if let Some(cfg) = preproc.platform_cfgs.get(ex_name) {
+            writeln!(module, "#[cfg({})]", cfg).unwrap();
+        }
+        writeln!(module, "{vis} mod {} {{", ex_name.to_lowercase(), vis = vis).unwrap();
+        if !items.is_empty() {
+            if preproc.config.emit_deprecated {
+                writeln!(module, "    #[allow(deprecated)]").unwrap();
+            }
+            writeln!(module, "    {vis} use super::{{{}}};", items.join(", "), vis = vis).unwrap();
+        }
+        writeln!(module, "}}").unwrap();
+        writeln!(write, "{}", module)?;
+    }
+    Ok(())
+}
+
+/// The processed name of `VkBool32`, if `bool_params` is enabled and it's been pulled in.
+fn bool32_param_type<'a>(preproc: &'a GenPreproc) -> Option<&'a str> {
+    if preproc.config.bool_params {
+        preproc.types.get("VkBool32").and_then(|t| t.name()).map(|n| unsafe{ &*n })
+    } else {None}
+}
+
+/// The variants of an enum that aren't aliases, along with their values. Only the first variant with
+/// each value is kept, since an arm matching any of the others would never be reached.
+fn unique_variants(variants: &[VkVariant]) -> Vec<(isize, &VkVariant)> {
+    let mut values = HashSet::with_capacity(variants.len());
+    variants.iter()
+        .filter(|v| v.alias_of().is_none())
+        .map(|v| match *v {
+            VkVariant::Value{value, ..} => (value, v),
+            VkVariant::Bitpos{..}       => panic!("Found bitpos in non-biflags enum")
+        })
+        .filter(|&(value, _)| values.insert(value))
+        .collect()
+}
+
+/// The raw name of the variant of the enum `key` with the value `value`, which was processed into
+/// `processed_name`. Variants added by features and extensions are looked up too, and variants that
+/// aren't aliases win out over ones that are.
+fn raw_variant_name<'a>(preproc: &GenPreproc<'a, '_>, key: &str, value: isize, processed_name: &'a str) -> &'a str {
+    let base = match preproc.registry.types().get(key) {
+        Some(VkType::Enum{variants, ..}) => variants.iter().collect(),
+        _                                => Vec::new()
+    };
+    let added = preproc.registry.features().values().flat_map(|f| f.require.iter())
+        .chain(preproc.registry.extns().values().flat_map(|e| e.require.iter()))
+        .filter_map(|req| match *req {
+            VkInterface::ExtnEnum{extends, ref variant, ..} if unsafe{ &*extends } == key => Some(variant),
+            _                                                                              => None
+        });
+    let mut candidates: Vec<_> = base.into_iter().chain(added)
+        .filter(|v| match **v {
+            VkVariant::Value{value: v, ..} => v == value,
+            VkVariant::Bitpos{..}          => false
+        })
+        .map(|v| (unsafe{ &*v.name() }, v.alias_of().is_some()))
+        .collect();
+    candidates.sort_by_key(|&(name, alias)| (alias, name));
+
+    candidates.iter()
+        .find(|&&(name, _)| preproc.variant_names.get(name).is_some_and(|n| unsafe{ &**n } == processed_name))
+        .or(candidates.first())
+        .map_or(processed_name, |&(name, _)| name)
+}
+
+/// Write a `vk_struct_bindings!` invocation for the commands whose raw names are accepted by `filter`,
+/// along with a trait named `commands_trait` that mirrors them if there is one.
+fn write_struct_bindings<W: Write, F: Fn(&str) -> bool>(write: &mut W, preproc: &GenPreproc, commands_trait: Option<&str>, filter: F) -> io::Result<()> {
+    let bool32 = bool32_param_type(preproc);
+    match commands_trait {
+        Some(commands_trait) => writeln!(write, "vk_struct_bindings!{{{}, {};", preproc.config.load_reporting.macro_arm(), commands_trait)?,
+        None                 => writeln!(write, "vk_struct_bindings!{{{};", preproc.config.load_reporting.macro_arm())?
+    }
+    for (c, r) in preproc.commands.iter().zip(preproc.commands_raw.iter()).filter(|&(_, r)| filter(r)) {unsafe{
+        if preproc.config.emit_docs {
+            write!(write, "{}", doc_comment("    ", to_option(c.comment)))?;
+        }
+        if preproc.config.param_docs {
+            write!(write, "{}", param_docs(c))?;
+        }
+        write!(write, "{}", deprecated_attr(preproc, "    ", r, false))?;
+        if let Some(cfg) = preproc.platform_cfgs.get(r) {
+            writeln!(write, "    if cfg({})", cfg)?;
+        }
+        writeln!(write, "    \"{}\", {}(", r, &*c.name)?;
+        for p in c.params.iter() {
+            write!(write, "        {}: ", &*p.name)?;
+            match (bool32, &p.typ) {
+                (Some(bool32), &VkElType::Var(t)) if *bool32 == *t   => write!(write, "bool as {}", bool32)?,
+                _                                                    => gen_func_param!(write, &p.typ)?
+            }
+            writeln!(write, ",")?;
+        }
+        write!(write, "    ) -> ")?;
+        gen_func_param!(write, &c.ret)?;
+        writeln!(write, ";\n")?;
+    }}
+    writeln!(write, "}}")
+}
+
+/// Which of the dispatch tables written by `gen_dispatch_tables()` a command goes in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CommandLevel {
+    Entry,
+    Instance,
+    Device
+}
+
+impl CommandLevel {
+    /// The level of the command named `raw_name`, going off of the type of its first parameter.
+    /// `vkGetInstanceProcAddr` and `vkGetDeviceProcAddr` are exceptions, since they're needed to load
+    /// the instance and device tables respectively.
+    fn of(raw_name: &str, raw: &VkCommand) -> CommandLevel {
+        match raw_name {
+            "vkGetInstanceProcAddr" => return CommandLevel::Entry,
+            "vkGetDeviceProcAddr"   => return CommandLevel::Instance,
+            _                       => ()
+        }
+
+        match raw.params.first().map(|p| &p.typ) {
+            Some(&VkElType::Var(t)) => match unsafe{ &*t } {
+                "VkDevice" | "VkQueue" | "VkCommandBuffer" => CommandLevel::Device,
+                "VkInstance" | "VkPhysicalDevice"          => CommandLevel::Instance,
+                _                                          => CommandLevel::Entry
+            },
+            _ => CommandLevel::Entry
+        }
+    }
+}
+
+/// Generate `load_with_loader()` for the global bindings, which loads the commands through
+/// `vkGetInstanceProcAddr`. Nothing is generated if the bindings don't have `VkInstance` to take.
+fn gen_load_with_loader(preproc: &GenPreproc) -> String {
+    let mut loader = String::new();
+    let vis = preproc.config.visibility;
+    let paths = preproc.config.std_paths();
+    let instance = match preproc.types.get("VkInstance") {
+        Some(instance) => unsafe{ &*instance.name().unwrap() },
+        None           => return loader
+    };
+    let void_function = match preproc.types.get("PFN_vkVoidFunction") {
+        Some(void_function) => unsafe{ &*void_function.name().unwrap() },
+        None                => return loader
+    };
+    let global: Vec<_> = preproc.commands_raw.iter()
+        .filter(|r| CommandLevel::Entry == CommandLevel::of(r, preproc.registry.commands().get(*r).unwrap()))
+        .map(|r| format!("\"{}\"", r))
+        .collect();
+
+    loader.push_str("/// Load the commands through `get_instance_proc_addr`, which should be `vkGetInstanceProcAddr`. The
+/// global commands, like `vkCreateInstance`, are always loaded with a null instance, since that's
+/// the only instance they can be loaded with, and the rest are loaded with `instance`. Calling this
+/// with a null `instance` loads just the global commands, which are enough to create an instance to
+/// call it with again.
+///
+/// Returns the same report as `load_with()`.
+");
+    writeln!(loader, "{vis} fn load_with_loader(get_instance_proc_addr: unsafe extern \"system\" fn({0}, *const ::{3}::c_char) -> {1}, instance: {0}) -> vk_load_report!({2}, type) {{",
+             instance, void_function, preproc.config.load_reporting.macro_arm(), paths.c_types, vis = vis).unwrap();
+    writeln!(loader, "    const GLOBAL_COMMANDS: &'static [&'static str] = &[{}];", global.join(", ")).unwrap();
+    write!(loader, "    // Command names are a lot shorter than this, so they can be nul-terminated without allocating.
+    // One that somehow isn't is left unloaded instead.
+    let mut name_buf = [0u8; 256];
+    load_with(|name| unsafe {{
+        if name.len() >= name_buf.len() {{
+            return ::{core}::ptr::null();
+        }}
+        name_buf[..name.len()].copy_from_slice(name.as_bytes());
+        name_buf[name.len()] = 0;
+        let instance = if GLOBAL_COMMANDS.contains(&name) {{::{core}::mem::zeroed()}} else {{instance}};
+        match get_instance_proc_addr(instance, name_buf.as_ptr() as *const ::{c_types}::c_char) {{
+            Some(f) => f as *const (),
+            None    => ::{core}::ptr::null()
+        }}
+    }})
+}}
+", core = paths.core, c_types = paths.c_types).unwrap();
+    loader
+}
+
+/// Generate the `<command>_is_success()` functions, for `per_command_success_checks`.
+fn gen_success_checks(preproc: &GenPreproc) -> String {
+    let mut checks = String::with_capacity(2usize.pow(14));
+    let vis = preproc.config.visibility;
+    let result = match preproc.types.get("VkResult") {
+        Some(result) => unsafe{ &*result.name().unwrap() },
+        None         => return checks
+    };
+
+    for c in preproc.commands.iter() {
+        let codes = success_patterns(preproc, c, result);
+        if codes.is_empty() {
+            continue;
+        }
+
+        writeln!(checks, "{vis} fn {}_is_success(result: {}) -> bool {{", unsafe{ &*c.name }, result, vis = vis).unwrap();
+        writeln!(checks, "    match result {{\n        {} => true,\n        _ => false\n    }}\n}}\n", codes.join(" | ")).unwrap();
+    }
+    checks
+}
+
+/// The `VkResult` patterns of the success codes documented for `c`, which is empty if it doesn't
+/// document any.
+fn success_patterns(preproc: &GenPreproc, c: &VkCommand, result: &str) -> Vec<String> {
+    c.success_codes.iter()
+        .filter_map(|code| preproc.variant_names.get(unsafe{ &**code }))
+        .map(|code| unsafe{ &**code })
+        .map(|code|
+            if preproc.config.use_native_enums || preproc.config.newtype_enums {
+                format!("{}::{}", result, code)
+            } else {code.to_owned()})
+        .collect()
+}
+
+/// The indices of the parameters of `raw` that are the pointer half of a count and pointer pair, for
+/// `safe_slice_wrappers`. The count has to be a `uint32_t` right before the pointer, and the pointer
+/// has to be named after it, with `pRegions` going with `regionCount`.
+fn slice_params(raw: &VkCommand) -> Vec<usize> {
+    raw.params.windows(2).enumerate()
+        .filter(|&(_, pair)| match (&pair[0].typ, &pair[1].typ) {
+            (&VkElType::Var(count), &VkElType::ConstPtr(elem, 1)) |
+            (&VkElType::Var(count), &VkElType::MutPtr(elem, 1))   => unsafe{ "uint32_t" == &*count && "void" != &*elem },
+            _                                                     => false
+        })
+        .filter(|&(_, pair)| unsafe{ names_count(&*pair[0].name, &*pair[1].name, 1) })
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+/// The indices of the members of a struct that are the pointer half of a count and pointer pair, for
+/// `gen_constructors`. Pairs are found the same way as in `slice_params()`, except that the pointer
+/// can point to pointers, with `ppEnabledLayerNames` going with `enabledLayerCount`.
+fn slice_members(raw_fields: &[VkMember]) -> Vec<usize> {
+    raw_fields.windows(2).enumerate()
+        .filter(|&(_, pair)| match (&pair[0].field_type, &pair[1].field_type) {
+            (&VkElType::Var(count), &VkElType::ConstPtr(elem, depth)) |
+            (&VkElType::Var(count), &VkElType::MutPtr(elem, depth))   => unsafe{
+                "uint32_t" == &*count && (1 < depth || "void" != &*elem) && names_count(&*pair[0].field_name, &*pair[1].field_name, depth)
+            },
+            _                                                         => false
+        })
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+/// Whether or not the pointer `ptr`, which has `depth` levels of indirection, is named after the
+/// count `count`.
+fn names_count(count: &str, ptr: &str, depth: u8) -> bool {
+    let depth = depth as usize;
+    let stem = match count.find("Count") {
+        Some(i) if i > 0 && i + 5 == count.len() && ptr.len() > depth && ptr[..depth].bytes().all(|b| b == b'p') => &count[..i],
+        _                                                                                                         => return false
+    };
+    // So that `pDescriptorCopies` still goes with `descriptorCopyCount`.
+    let stem = stem.strip_suffix('y').unwrap_or(stem);
+    let mut chars = ptr[depth..].chars();
+    chars.next().is_some_and(|first| first.to_lowercase().eq(stem[..1].chars()) && chars.as_str().starts_with(&stem[1..]))
+}
+
+/// Generate the `<command>_slice()` wrappers on `Vk`, for `safe_slice_wrappers`.
+fn gen_slice_wrappers(preproc: &GenPreproc) -> String {
+    let mut wrappers = String::with_capacity(2usize.pow(14));
+    let vis = preproc.config.visibility;
+    let bool32 = bool32_param_type(preproc);
+
+    for (c, r) in preproc.commands.iter().zip(preproc.commands_raw.iter()) { unsafe {
+        let slices = slice_params(preproc.registry.commands().get(r).unwrap());
+        if slices.is_empty() {
+            continue;
+        }
+
+        let name = &*c.name;
+        let mut params = String::new();
+        let mut args = String::new();
+        let mut splits = String::new();
+        for (i, p) in c.params.iter().enumerate() {
+            let param_name = &*p.name;
+            if slices.contains(&(i + 1)) {
+                // The count is filled in from the slice that follows it.
+                continue;
+            }
+
+            if !args.is_empty() {
+                params.push_str(", ");
+                args.push_str(", ");
+            }
+            if slices.contains(&i) {
+                let (split, elem) = match p.typ {
+                    VkElType::ConstPtr(elem, _) => ("slice_ptr_len", format!("&[{}]", &*elem)),
+                    VkElType::MutPtr(elem, _)   => ("slice_ptr_len_mut", format!("&mut [{}]", &*elem)),
+                    _                           => unreachable!()
+                };
+                write!(params, "{}: {}", param_name, elem).unwrap();
+                writeln!(splits, "        let ({0}, {1}) = {2}({0});", param_name, &*c.params[i - 1].name, split).unwrap();
+                write!(args, "{}, {}", &*c.params[i - 1].name, param_name).unwrap();
+            } else {
+                write!(params, "{}: ", param_name).unwrap();
+                match (bool32, &p.typ) {
+                    (Some(bool32), &VkElType::Var(t)) if bool32 == &*t => params.push_str("bool"),
+                    _                                                 => gen_func_param!(params, &p.typ).unwrap()
+                }
+                args.push_str(param_name);
+            }
+        }
+
+        writeln!(wrappers, "    /// `{}()`, taking its arrays as slices.", name).unwrap();
+        write!(wrappers, "{}", deprecated_attr(preproc, "    ", r, true)).unwrap();
+        if let Some(cfg) = preproc.platform_cfgs.get(r) {
+            writeln!(wrappers, "    #[cfg({})]", cfg).unwrap();
+        }
+        write!(wrappers, "    {vis} unsafe fn {}_slice(&self, {}) -> ", name, params, vis = vis).unwrap();
+        gen_func_param!(wrappers, &c.ret).unwrap();
+        writeln!(wrappers, " {{
+{}        self.{}({})
+    }}
+", splits, name, args).unwrap();
+    }}
+
+    if wrappers.is_empty() {
+        wrappers
+    } else {
+        format!("impl Vk {{
+{}}}
+", wrappers)
+    }
+}
+
+/// Generate the `<command>_checked()` wrappers, for `result_wrappers`. They're methods on `Vk` if
+/// `methods` is true, and free functions otherwise.
+fn gen_result_wrappers(preproc: &GenPreproc, methods: bool) -> String {
+    let mut wrappers = String::with_capacity(2usize.pow(14));
+    let vis = preproc.config.visibility;
+    let result = match preproc.types.get("VkResult") {
+        Some(result) => unsafe{ &*result.name().unwrap() },
+        None         => return wrappers
+    };
+    let bool32 = bool32_param_type(preproc);
+    let indent = if methods {"    "} else {""};
+
+    for (c, r) in preproc.commands.iter().zip(preproc.commands_raw.iter()) { unsafe {
+        let raw = preproc.registry.commands().get(r).unwrap();
+        match raw.ret {
+            VkElType::Var(ret) if "VkResult" == &*ret => (),
+            _                                         => continue
+        }
+        let codes = success_patterns(preproc, c, result);
+        if codes.is_empty() {
+            continue;
+        }
+        // A trailing pointer that isn't to an array is something the command writes its output to.
+        let out = match (raw.params.last(), c.params.last().map(|p| &p.typ)) {
+            (Some(last), Some(&VkElType::MutPtr(out, 1))) if last.len().is_none() && "void" != last.typ.type_ptr().map_or("", |t| &*t) =>
+                Some(&*out),
+            _ => None
+        };
+
+        let name = &*c.name;
+        let taken = if out.is_some() {c.params.len() - 1} else {c.params.len()};
+        let mut params = String::new();
+        let mut args = String::new();
+        for p in &c.params[..taken] {
+            if !args.is_empty() {
+                params.push_str(", ");
+                args.push_str(", ");
+            }
+            write!(params, "{}: ", &*p.name).unwrap();
+            match (bool32, &p.typ) {
+                (Some(bool32), &VkElType::Var(t)) if bool32 == &*t => params.push_str("bool"),
+                _                                                 => gen_func_param!(params, &p.typ).unwrap()
+            }
+            args.push_str(&*p.name);
+        }
+        if out.is_some() {
+            if !args.is_empty() {
+                args.push_str(", ");
+            }
+            args.push_str("out.as_mut_ptr()");
+        }
+
+        let receiver = if methods {"self."} else {""};
+        writeln!(wrappers, "{}/// `{}()`, with error codes returned as `Err`.", indent, name).unwrap();
+        write!(wrappers, "{}", deprecated_attr(preproc, indent, r, true)).unwrap();
+        if let Some(cfg) = preproc.platform_cfgs.get(r) {
+            writeln!(wrappers, "{}#[cfg({})]", indent, cfg).unwrap();
+        }
+        writeln!(wrappers, "{}{vis} unsafe fn {}_checked({}{}) -> Result<{}, {}> {{", indent, name,
+                 if methods {"&self, "} else {""}, params, out.unwrap_or("()"), result, vis = vis).unwrap();
+        if out.is_some() {
+            writeln!(wrappers, "{}    let mut out = ::{}::mem::MaybeUninit::uninit();", indent, preproc.config.std_paths().core).unwrap();
+        }
+        writeln!(wrappers, "{}    let result = {}{}({});", indent, receiver, name, args).unwrap();
+        writeln!(wrappers, "{}    match result {{", indent).unwrap();
+        writeln!(wrappers, "{}        {} => Ok({}),", indent, codes.join(" | "), if out.is_some() {"out.assume_init()"} else {"()"}).unwrap();
+        writeln!(wrappers, "{}        _ => vk_result_error(result)", indent).unwrap();
+        writeln!(wrappers, "{}    }}", indent).unwrap();
+        writeln!(wrappers, "{}}}\n", indent).unwrap();
+    }}
+
+    if wrappers.is_empty() {
+        return wrappers;
+    }
+    if methods {
+        wrappers = format!("impl Vk {{\n{}}}\n", wrappers);
+    }
+    // Commands almost always succeed, so the `Err` is built out of line.
+    writeln!(wrappers, "#[cold]\n#[inline(never)]\nfn vk_result_error<T>(result: {0}) -> Result<T, {0}> {{\n    Err(result)\n}}\n", result).unwrap();
+    wrappers
+}
+
+/// Pass `ident` through `rename`, panicking if the new name isn't a legal identifier.
+fn checked_rename(rename: Renamer, ident: &str, kind: IdentKind) -> String {
+    let renamed = (rename.0)(ident, kind);
+    let mut chars = renamed.chars();
+    let legal = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || '_' == c)
+        && chars.all(|c| c.is_ascii_alphanumeric() || '_' == c)
+        && "_" != renamed
+        && (IdentKind::Member == kind || !RUST_KEYWORDS.contains(&&renamed[..]));
+    if !legal {
+        panic!("`rename` turned the {:?} `{}` into `{}`, which isn't a legal identifier", kind, ident, renamed);
+    }
+    renamed
+}
+
+/// `ident` without the `r#` that members that are keywords get escaped with.
+fn unraw(ident: &str) -> &str {
+    ident.trim_start_matches("r#")
+}
+
+/// Every keyword that can't be used as an identifier, in any edition.
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate", "do",
+    "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self", "Self",
+    "static", "struct", "super", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield"
+];
+
+/// What replaced `ex`, as the note of a `#[deprecated]` attribute, or `None` if it's still current.
+fn deprecation_note(ex: &VkExtn) -> Option<String> {
+    match (ex.obsoleted_by(), ex.deprecated_by(), ex.promoted_to()) {
+        (Some(""), _, _)   => Some("obsoleted".to_owned()),
+        (Some(by), _, _)   => Some(format!("obsoleted by {}", by)),
+        (_, Some(""), _)   => Some("deprecated without a replacement".to_owned()),
+        (_, Some(by), _)   => Some(format!("deprecated by {}", by)),
+        (_, _, Some(to))   => Some(format!("promoted to {}", to)),
+        (None, None, None) => None
+    }
+}
+
+/// The `#[deprecated]` attribute of the item with the raw name `raw`, or nothing if `emit_deprecated`
+/// didn't mark it. If `wraps` is true, the item calls the deprecated one and allows the warning
+/// that causes.
+fn deprecated_attr(preproc: &GenPreproc, indent: &str, raw: &str, wraps: bool) -> String {
+    match preproc.deprecations.get(raw) {
+        Some(note) if wraps => format!("{0}#[deprecated(note = \"{1}\")]\n{0}#[allow(deprecated)]\n", indent, note),
+        Some(note)          => format!("{}#[deprecated(note = \"{}\")]\n", indent, note),
+        None                => String::new()
+    }
+}
+
+/// A `#[doc]` attribute holding `comment`, or nothing if there's no comment. The comment is written as
+/// a string literal rather than a `///` comment so that it can't break out of the attribute.
+fn doc_comment(indent: &str, comment: Option<&str>) -> String {
+    match comment {
+        Some(comment) => format!("{}#[doc = {:?}]\n", indent, format!(" {}", comment)),
+        None          => String::new()
+    }
+}
+
+/// The doc comments listing the externally synchronized and not automatically validated parameters
+/// of `command`, for `param_docs`.
+fn param_docs(command: &VkCommand) -> String {
+    let mut docs = String::new();
+    let lists = [
+        ("Externally synchronized", command.params.iter().filter_map(|p| p.externsync()).flat_map(|e| e.split(',')).collect::<Vec<_>>()),
+        ("Not automatically validated", command.params.iter().filter(|p| p.noautovalidity()).map(|p| unsafe{ &*p.name }).collect())
+    ];
+    for &(label, ref params) in lists.iter().filter(|(_, params)| !params.is_empty()) {
+        if !docs.is_empty() {
+            docs.push_str("    ///\n");
+        }
+        let params: Vec<_> = params.iter().map(|p| format!("`{}`", p)).collect();
+        writeln!(docs, "    /// {}: {}", label, params.join(", ")).unwrap();
+    }
+    docs
+}
+
+/// Get the registry name of the variant of the enum with the variant prefix `prefix` that names the
+/// type `typ`, as in `VkDebugReportCallbackEXT` -> `VK_OBJECT_TYPE_DEBUG_REPORT_CALLBACK_EXT`.
+fn type_variant(prefix: &str, typ: &str) -> String {
+    let mut variant = String::with_capacity(prefix.len() + typ.len() + 8);
+    variant.push_str(prefix);
+
+    let name = typ.strip_prefix("Vk").unwrap_or(typ);
+    // Starts off as `true` so that the first word gets separated from the prefix.
+    let mut last_lowercase = true;
+    for c in name.chars() {
+        if c.is_uppercase() && last_lowercase {
+            variant.push('_');
+        }
+        last_lowercase = !c.is_uppercase();
+        variant.extend(c.to_uppercase());
+    }
+    variant
+}
+
+/// The `#[cfg]` predicate for the targets that have the platform named `platform` in the registry, if
+/// there's a target that corresponds to it.
+fn platform_cfg(platform: &str) -> Option<&'static str> {
+    match platform {
+        "win32"                                            => Some("windows"),
+        "xlib" | "xlib_xrandr" | "xcb" | "wayland" | "mir" =>
+            Some("all(unix, not(any(target_os = \"macos\", target_os = \"ios\", target_os = \"android\")))"),
+        "android"                                          => Some("target_os = \"android\""),
+        "macos"                                            => Some("target_os = \"macos\""),
+        "ios"                                              => Some("target_os = \"ios\""),
+        "metal"                                            => Some("any(target_os = \"macos\", target_os = \"ios\")"),
+        "fuchsia"                                          => Some("target_os = \"fuchsia\""),
+        _                                                  => None
+    }
+}
+
+/// The definition of `name` if it's a type from a platform header, for `define_platform_types`.
+fn platform_type(name: &str, vis: Visibility, c_types: &str) -> Option<String> {
+    let def = match name {
+        // X11/Xlib.h and X11/extensions/Xrandr.h
+        "Display"             => format!("#[repr(C)] {} struct Display {{ _private: [u8; 0] }}", vis),
+        "VisualID"            => format!("{} type VisualID = ::{}::c_ulong;", vis, c_types),
+        "Window"              => format!("{} type Window = ::{}::c_ulong;", vis, c_types),
+        "RROutput"            => format!("{} type RROutput = ::{}::c_ulong;", vis, c_types),
+        // xcb/xcb.h
+        "xcb_connection_t"    => format!("#[repr(C)] {} struct xcb_connection_t {{ _private: [u8; 0] }}", vis),
+        "xcb_visualid_t"      => format!("{} type xcb_visualid_t = u32;", vis),
+        "xcb_window_t"        => format!("{} type xcb_window_t = u32;", vis),
+        // wayland-client.h
+        "wl_display"          => format!("#[repr(C)] {} struct wl_display {{ _private: [u8; 0] }}", vis),
+        "wl_surface"          => format!("#[repr(C)] {} struct wl_surface {{ _private: [u8; 0] }}", vis),
+        // mir_toolkit/client_types.h
+        "MirConnection"       => format!("#[repr(C)] {} struct MirConnection {{ _private: [u8; 0] }}", vis),
+        "MirSurface"          => format!("#[repr(C)] {} struct MirSurface {{ _private: [u8; 0] }}", vis),
+        // android/native_window.h
+        "ANativeWindow"       => format!("#[repr(C)] {} struct ANativeWindow {{ _private: [u8; 0] }}", vis),
+        // windows.h
+        "HINSTANCE"           => format!("{} type HINSTANCE = *mut ::{}::c_void;", vis, c_types),
+        "HWND"                => format!("{} type HWND = *mut ::{}::c_void;", vis, c_types),
+        "HANDLE"              => format!("{} type HANDLE = *mut ::{}::c_void;", vis, c_types),
+        "DWORD"               => format!("{} type DWORD = u32;", vis),
+        "LPCWSTR"             => format!("{} type LPCWSTR = *const u16;", vis),
+        "SECURITY_ATTRIBUTES" => format!("#[repr(C)] #[derive(Debug, Clone, Copy)] #[allow(non_snake_case)] {0} struct SECURITY_ATTRIBUTES {{ \
+                                          {0} nLength: u32, {0} lpSecurityDescriptor: *mut ::{1}::c_void, {0} bInheritHandle: i32 }}", vis, c_types),
+        _                     => return None
+    };
+    Some(def)
+}
+
+/// The platform that the external types from the C header `header` belong to.
+fn header_platform(header: &str) -> Option<&'static str> {
+    match header {
+        "windows.h"                                 => Some("win32"),
+        "X11/Xlib.h"                                => Some("xlib"),
+        "X11/extensions/Xrandr.h"                   => Some("xlib_xrandr"),
+        "xcb/xcb.h"                                 => Some("xcb"),
+        "wayland-client.h"                          => Some("wayland"),
+        "mir_toolkit/client_types.h"                => Some("mir"),
+        "android/native_window.h"                   => Some("android"),
+        _ if header.starts_with("zircon/")          => Some("fuchsia"),
+        _                                           => None
+    }
+}
+
+/// Whether or not the registry type `typ` can be created with `mem::zeroed`. Native enums are only
+/// valid as zero if they have a variant with the value `0`.
+fn zeroable(registry: &VkRegistry, typ: &str, native_enums: bool) -> bool {
+    use registry::VkType::*;
+
+    let members_zeroable = |members: &[VkMember]| members.iter().all(|m| match m.field_type {
+        VkElType::Var(t)               => zeroable(registry, unsafe{ &*t }, native_enums),
+        VkElType::ConstArray(t, _)     |
+        VkElType::MutArray(t, _)       |
+        VkElType::ConstArrayEnum(t, _) |
+        VkElType::MutArrayEnum(t, _)   => zeroable(registry, unsafe{ &*t }, native_enums),
+        _                              => true
+    });
+
+    match registry.types().get(typ) {
+        Some(Struct{fields, ..})          => members_zeroable(fields),
+        Some(Union{variants, ..})         => members_zeroable(variants),
+        Some(Enum{variants, ..})          => !native_enums || variants.iter().any(|v| !v.is_bitpos() && v.value() == 0),
+        _                                 => true
     }
 }
 
@@ -1295,6 +3870,7 @@ pub trait GenRegistry {
     fn extns(&self)       -> &HashMap<&str, VkExtn>;
     fn buffer_cap(&self)  -> usize;
     fn core_consts(&self) -> &Vec<&str>;
+    fn diagnose(&self, diagnostic: Diagnostic);
 }
 
 struct PeekNext<I: Iterator> {
@@ -1333,3 +3909,28 @@ impl<I: Iterator> PeekNextCreate for I {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::ConstType::*;
+
+    #[test]
+    fn c_consts() {
+        let parsed = parse_c_const;
+        assert_eq!(parsed("256"), Some((Unsigned, "256".to_owned())));
+        assert_eq!(parsed("1000.0f"), Some((Float, "1000.0".to_owned())));
+        assert_eq!(parsed("(~0U)"), Some((Unsigned, "!0".to_owned())));
+        assert_eq!(parsed("(~0ULL)"), Some((ULongLong, "!0".to_owned())));
+        assert_eq!(parsed("(~0U-1)"), Some((Unsigned, "!0-1".to_owned())));
+        assert_eq!(parsed("(~0U - 2)"), Some((Unsigned, "!0-2".to_owned())));
+        assert_eq!(parsed("(~0UL)"), Some((ULong, "!0".to_owned())));
+        assert_eq!(parsed("0x10U"), Some((Unsigned, "0x10".to_owned())));
+        assert_eq!(parsed("\"VK_KHR_surface\""), Some((Str, "\"VK_KHR_surface\"".to_owned())));
+
+        assert_eq!(parsed("~1.0f"), None);
+        assert_eq!(parsed("(~0U)*2"), None);
+        assert_eq!(parsed("1.0L"), None);
+        assert_eq!(parsed("SOME_MACRO"), None);
+    }
+}
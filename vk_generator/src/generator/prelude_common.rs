@@ -1,176 +1,601 @@
-pub use self::__vk_generator_types::*;
+{vis} use self::__vk_generator_types::*;
 #[allow(non_upper_case_globals)]
-mod __vk_generator_types {
+mod __vk_generator_types {{
 
-pub use std::os::raw::c_ulonglong;
-pub use self::types::*;
-pub use self::cmds::*;
+{vis} use self::types::*;
+{vis} use self::cmds::*;
 
 #[doc(hidden)]
 #[allow(dead_code)]
-pub fn unloaded_function_panic() -> ! {
+{vis} fn unloaded_function_panic() -> ! {{
     panic!("Attempted to run unloaded vulkan function")
-}
+}}
+
+/// Rounds `offset` up to the next multiple of `align`, as `#[repr(C)]` does when laying out fields.
+#[doc(hidden)]
+#[allow(dead_code)]
+{vis} const fn vk_align_up(offset: usize, align: usize) -> usize {{
+    (offset + align - 1) / align * align
+}}
+
+/// Splits `slice` into the pointer and count that commands take for an array parameter. Empty
+/// slices give a null pointer, rather than the dangling one `as_ptr()` would return.
+#[allow(dead_code)]
+{vis} fn slice_ptr_len<T>(slice: &[T]) -> (*const T, u32) {{
+    match slice.len() {{
+        0 => (::{core}::ptr::null(), 0),
+        len => (slice.as_ptr(), vk_count(len))
+    }}
+}}
+
+/// Splits `slice` into the pointer and count that commands take for an array they write into.
+/// Empty slices give a null pointer, rather than the dangling one `as_mut_ptr()` would return.
+#[allow(dead_code)]
+{vis} fn slice_ptr_len_mut<T>(slice: &mut [T]) -> (*mut T, u32) {{
+    match slice.len() {{
+        0 => (::{core}::ptr::null_mut(), 0),
+        len => (slice.as_mut_ptr(), vk_count(len))
+    }}
+}}
+
+/// Converts a slice length into a `uint32_t` count. Lengths that don't fit trip a debug assertion
+/// instead of being silently truncated.
+#[doc(hidden)]
+#[allow(dead_code)]
+#[inline]
+{vis} fn vk_count(len: usize) -> u32 {{
+    debug_assert!(len <= u32::max_value() as usize, "slice of length {{}} is too long for a Vulkan count", len);
+    len as u32
+}}
 
 #[allow(unused_macros)]
-macro_rules! handle_nondispatchable {
-    ($name: ident) => {
+macro_rules! handle_nondispatchable {{
+    ($name: ident) => {{
+        #[repr(transparent)]
+        #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+        {vis} struct $name (uint64_t);
+
+        impl $name {{
+            /// `VK_NULL_HANDLE`.
+            {vis} const NULL: $name = $name(0);
+
+            {vis} fn null() -> $name {{
+                $name::NULL
+            }}
+
+            {vis} fn is_null(self) -> bool {{
+                self == $name::NULL
+            }}
+
+            /// Wrap a handle obtained from somewhere other than these bindings.
+            {vis} fn from_raw(raw: uint64_t) -> $name {{
+                $name(raw)
+            }}
+
+            {vis} fn as_raw(self) -> uint64_t {{
+                self.0
+            }}
+        }}
+
+        vk_handle_fmt!($name);
+    }};
+
+    // Only used if `niche_handles` is true. `VK_NULL_HANDLE` is represented by `None`.
+    ($name: ident, niche) => {{
+        #[repr(transparent)]
+        #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
+        {vis} struct $name (::{core}::num::NonZeroU64);
+
+        impl $name {{
+            {vis} fn from_raw(raw: uint64_t) -> Option<$name> {{
+                ::{core}::num::NonZeroU64::new(raw).map($name)
+            }}
+
+            {vis} fn as_raw(self) -> uint64_t {{
+                self.0.get()
+            }}
+        }}
+
+        vk_handle_fmt!($name);
+    }};
+}}
+
+// Only used if `wrap_dispatchable_handles` is true. `$object` is the opaque type the handle points to.
+#[allow(unused_macros)]
+macro_rules! handle_dispatchable {{
+    ($name: ident, $object: ident) => {{
         #[repr(C)]
+        #[doc(hidden)]
+        {vis} struct $object (u8);
+
+        #[repr(transparent)]
         #[derive(Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash)]
-        pub struct $name (uint64_t);
-
-        impl fmt::Pointer for $name {
-            fn fmt(&self, f: &mut fmt::Formatter) -> ::std::result::Result<(), fmt::Error> {
-                write!(f, "0x{:x}", self.0)
-            }
-        }
-
-        impl fmt::Debug for $name {
-            fn fmt(&self, f: &mut fmt::Formatter) -> ::std::result::Result<(), fmt::Error> {
-                write!(f, "0x{:x}", self.0)
-            }
-        }
-    }
-}
-
-// Only used if `wrap_bitmasks == true`.
-//TODO: use bitflags crate?
-#[allow(unused_macros)]
-macro_rules! vk_bitflags_wrapped {
-    ($name: ident, $all: expr, $flag_type: ty) => {
+        {vis} struct $name (*mut $object);
+
+        impl $name {{
+            /// `VK_NULL_HANDLE`.
+            {vis} const NULL: $name = $name(0 as *mut $object);
+
+            {vis} fn null() -> $name {{
+                $name::NULL
+            }}
+
+            {vis} fn is_null(self) -> bool {{
+                self == $name::NULL
+            }}
+
+            /// Wrap a handle obtained from somewhere other than these bindings, like another Vulkan
+            /// crate or the other side of an FFI boundary.
+            ///
+            /// # Safety
+            ///
+            /// `raw` has to be `0` or the address of a live object of this handle's type, which
+            /// Vulkan gave out and which hasn't been destroyed. The loader dispatches commands through
+            /// the object, so anything else is undefined behavior once the handle reaches a command.
+            {vis} unsafe fn from_raw(raw: usize) -> $name {{
+                $name(raw as *mut $object)
+            }}
+
+            {vis} fn as_raw(self) -> usize {{
+                self.0 as usize
+            }}
+        }}
+
+        vk_handle_fmt!($name);
+    }};
+}}
+
+// Used by `handle_nondispatchable!` and `handle_dispatchable!`.
+#[allow(unused_macros)]
+macro_rules! vk_handle_fmt {{
+    ($name: ident) => {{
+        impl fmt::Pointer for $name {{
+            fn fmt(&self, f: &mut fmt::Formatter) -> ::{core}::result::Result<(), fmt::Error> {{
+                write!(f, "0x{{:x}}", self.as_raw())
+            }}
+        }}
+
+        impl fmt::Debug for $name {{
+            fn fmt(&self, f: &mut fmt::Formatter) -> ::{core}::result::Result<(), fmt::Error> {{
+                write!(f, "{{}}(0x{{:x}})", stringify!($name), self.as_raw())
+            }}
+        }}
+
+        impl fmt::Display for $name {{
+            fn fmt(&self, f: &mut fmt::Formatter) -> ::{core}::result::Result<(), fmt::Error> {{
+                fmt::Debug::fmt(self, f)
+            }}
+        }}
+    }}
+}}
+
+// Used by the command bindings to pass a parameter that's taken as a different type than the
+// command's, like `VkBool32` parameters taken as `bool` if `bool_params == true`.
+#[allow(unused_macros)]
+macro_rules! vk_param_type {{
+    ($param: ty)           => {{$param}};
+    ($param: ty, $raw: ty) => {{$raw}};
+}}
+
+#[allow(unused_macros)]
+macro_rules! vk_param_value {{
+    ($name: ident)           => {{$name}};
+    ($name: ident, $raw: ty) => {{<$raw as From<_>>::from($name)}};
+}}
+
+// Used by `load_with()` to report the functions that failed to load, as chosen by `load_reporting`.
+#[allow(unused_macros)]
+macro_rules! vk_load_report {{
+    (Full, type)                          => {{::{core}::result::Result<(), ::{alloc}::vec::Vec<&'static str>>}};
+    (Full, new)                           => {{::{alloc}::vec::Vec::new()}};
+    (Full, push $unloaded: ident, $raw_name: expr) => {{$unloaded.push($raw_name)}};
+    (Full, finish $unloaded: ident)       => {{if 0 == $unloaded.len() {{Ok(())}} else {{Err($unloaded)}}}};
+
+    (CountOnly, type)                     => {{::{core}::result::Result<(), usize>}};
+    (CountOnly, new)                      => {{0usize}};
+    (CountOnly, push $unloaded: ident, $raw_name: expr) => {{$unloaded += 1}};
+    (CountOnly, finish $unloaded: ident)  => {{if 0 == $unloaded {{Ok(())}} else {{Err($unloaded)}}}};
+
+    (None, type)                          => {{::{core}::result::Result<(), ()>}};
+    (None, new)                           => {{false}};
+    (None, push $unloaded: ident, $raw_name: expr) => {{$unloaded = true}};
+    (None, finish $unloaded: ident)       => {{if !$unloaded {{Ok(())}} else {{Err(())}}}};
+}}
+
+/// The header shared by every struct that can be a part of a `pNext` chain.
+#[doc(hidden)]
+#[repr(C)]
+#[allow(dead_code)]
+{vis} struct VkChainLink {{
+    s_type: u32,
+    p_next: *mut VkChainLink
+}}
+
+/// Walks the `pNext` chain that `link` points into and appends `next` to the end of it.
+#[doc(hidden)]
+#[allow(dead_code)]
+{vis} unsafe fn vk_chain_append(mut link: *mut *mut VkChainLink, next: *mut VkChainLink) {{
+    while !(*link).is_null() {{
+        link = &mut (**link).p_next;
+    }}
+    *link = next;
+}}
+
+// Only used if `chain_builders == true`.
+#[allow(unused_macros)]
+macro_rules! vk_chain_builder {{
+    ($name: ident, $builder: ident, $extends: ident, $p_next: ident) => {{
+        {vis} unsafe trait $extends {{}}
+
+        {vis} struct $builder<'a> {{
+            inner: $name,
+            marker: ::{core}::marker::PhantomData<&'a mut ()>
+        }}
+
+        impl $name {{
+            {vis} fn builder<'a>(self) -> $builder<'a> {{
+                $builder{{ inner: self, marker: ::{core}::marker::PhantomData }}
+            }}
+        }}
+
+        impl<'a> $builder<'a> {{
+            /// Append `next` to the end of this struct's `pNext` chain. Successive calls build up the
+            /// chain in the order they're made.
+            {vis} fn push_next<T: $extends>(mut self, next: &'a mut T) -> Self {{
+                unsafe{{ vk_chain_append(&mut self.inner.$p_next as *mut _ as *mut *mut VkChainLink, next as *mut T as *mut VkChainLink) }};
+                self
+            }}
+
+            /// Unwrap the built struct. This drops the borrows of the structs in the `pNext` chain,
+            /// so they have to be kept alive some other way for as long as the struct is used.
+            {vis} fn build(self) -> $name {{
+                self.inner
+            }}
+        }}
+
+        impl<'a> ::{core}::ops::Deref for $builder<'a> {{
+            type Target = $name;
+            fn deref(&self) -> &$name {{
+                &self.inner
+            }}
+        }}
+    }}
+}}
+
+// Only used if `use_native_enums == false` and `newtype_enums == true`.
+#[allow(unused_macros)]
+macro_rules! vk_newtype_enum {{
+    ($name: ident, [$($variant: ident = $value: literal),*]) => {{
+        vk_newtype_enum!(@emit $name, [$($variant = $value),*], {{}});
+    }};
+    // With `enum_iter == true`, `ALL` goes in the same impl block as the variants.
+    ($name: ident, [$($variant: ident = $value: literal),*], ALL = [$($all: ident),*]) => {{
+        vk_newtype_enum!(@emit $name, [$($variant = $value),*], {{
+            {vis} const ALL: &'static [$name] = &[$($name::$all),*];
+        }});
+    }};
+    (@emit $name: ident, [$($variant: ident = $value: literal),*], {{$($extra: tt)*}}) => {{
+        #[repr(transparent)]
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        {vis} struct $name({vis} i32);
+
+        impl $name {{
+            $({vis} const $variant: $name = $name($value);)*
+            $($extra)*
+        }}
+
+        impl fmt::Debug for $name {{
+            #[allow(unreachable_patterns)]
+            fn fmt(&self, f: &mut fmt::Formatter) -> ::{core}::result::Result<(), fmt::Error> {{
+                match self.0 {{
+                    $($value => write!(f, "{{}}", stringify!($variant)),)*
+                    v => write!(f, "{{}}({{}})", stringify!($name), v)
+                }}
+            }}
+        }}
+    }}
+}}
+
+// Only used if `newtype_enums == true` and `enum_int_eq == true`.
+#[allow(unused_macros)]
+macro_rules! vk_enum_int_eq {{
+    ($name: ident) => {{
+        impl PartialEq<i32> for $name {{
+            fn eq(&self, other: &i32) -> bool {{
+                self.0 == *other
+            }}
+        }}
+
+        impl PartialEq<$name> for i32 {{
+            fn eq(&self, other: &$name) -> bool {{
+                *self == other.0
+            }}
+        }}
+    }}
+}}
+
+// Only used if `impl_display_enums == true`.
+#[allow(unused_macros)]
+macro_rules! vk_enum_display {{
+    (@value native $this: expr) => {{*$this as i32}};
+    (@value newtype $this: expr) => {{$this.0}};
+    ($repr: ident $name: ident, [$($value: literal => $raw: literal),*]) => {{
+        impl fmt::Display for $name {{
+            fn fmt(&self, f: &mut fmt::Formatter) -> ::{core}::result::Result<(), fmt::Error> {{
+                match vk_enum_display!(@value $repr self) {{
+                    $($value => f.write_str($raw),)*
+                    v => write!(f, "{{}}", v)
+                }}
+            }}
+        }}
+    }}
+}}
+
+// Only used if `use_native_enums == true` and `impl_tryfrom_enums == true`.
+#[allow(unused_macros)]
+macro_rules! vk_enum_try_from {{
+    ($name: ident, [$($value: literal => $variant: ident),*]) => {{
+        impl ::{core}::convert::TryFrom<i32> for $name {{
+            type Error = i32;
+
+            fn try_from(value: i32) -> ::{core}::result::Result<$name, i32> {{
+                match value {{
+                    $($value => Ok($name::$variant),)*
+                    v => Err(v)
+                }}
+            }}
+        }}
+    }}
+}}
+
+// Only used if `platform_cfgs == true`.
+#[allow(unused_macros)]
+macro_rules! vk_platform_cfg {{
+    ($cfg: meta; $($item: item)*) => {{$(#[cfg($cfg)] $item)*}}
+}}
+
+// Only used if `geometry_helpers == true`.
+#[allow(unused_macros)]
+macro_rules! vk_geometry_helpers {{
+    ($name: ident, $component: ty, [$($field: ident),+]) => {{
+        impl $name {{
+            {vis} fn new($($field: $component),+) -> $name {{
+                $name{{ $($field: $field),+ }}
+            }}
+
+            /// The component-wise minimum of `self` and `other`.
+            {vis} fn min(self, other: $name) -> $name {{
+                $name{{ $($field: ::{core}::cmp::min(self.$field, other.$field)),+ }}
+            }}
+
+            /// The component-wise maximum of `self` and `other`.
+            {vis} fn max(self, other: $name) -> $name {{
+                $name{{ $($field: ::{core}::cmp::max(self.$field, other.$field)),+ }}
+            }}
+        }}
+    }}
+}}
+
+// Only used if `geometry_helpers == true`.
+#[allow(unused_macros)]
+macro_rules! vk_rect_helpers {{
+    ($name: ident, $offset: ident, $extent: ident) => {{
+        impl $name {{
+            {vis} fn new(offset: $offset, extent: $extent) -> $name {{
+                $name{{ offset: offset, extent: extent }}
+            }}
+
+            /// A rectangle of size `extent` with its offset at the origin.
+            {vis} fn from_extent(extent: $extent) -> $name {{
+                $name{{ offset: $offset{{ x: 0, y: 0 }}, extent: extent }}
+            }}
+
+            /// Whether or not `point` lies inside of this rectangle.
+            {vis} fn contains(&self, point: $offset) -> bool {{
+                // Widened so that rectangles reaching the edges of the coordinate space don't overflow.
+                let (x, y) = (point.x as i64, point.y as i64);
+                let (left, top) = (self.offset.x as i64, self.offset.y as i64);
+                left <= x && x < left + self.extent.width as i64 &&
+                top <= y && y < top + self.extent.height as i64
+            }}
+
+            /// Whether or not `other` lies entirely inside of this rectangle.
+            {vis} fn contains_rect(&self, other: &$name) -> bool {{
+                let (left, top) = (self.offset.x as i64, self.offset.y as i64);
+                let (other_left, other_top) = (other.offset.x as i64, other.offset.y as i64);
+                left <= other_left && other_left + other.extent.width as i64 <= left + self.extent.width as i64 &&
+                top <= other_top && other_top + other.extent.height as i64 <= top + self.extent.height as i64
+            }}
+        }}
+
+        impl From<$extent> for $name {{
+            fn from(extent: $extent) -> $name {{
+                $name::from_extent(extent)
+            }}
+        }}
+    }}
+}}
+
+// Only used if `geometry_helpers == true`.
+#[allow(unused_macros)]
+macro_rules! vk_geometry_widen {{
+    ($narrow: ident, $wide: ident, [$($field: ident),+], $extra: ident = $value: expr) => {{
+        impl From<$narrow> for $wide {{
+            fn from(narrow: $narrow) -> $wide {{
+                $wide{{ $($field: narrow.$field,)+ $extra: $value }}
+            }}
+        }}
+    }}
+}}
+
+// Only used if `wrap_bitmasks == true` and `bitflags_crate == false`.
+#[allow(unused_macros)]
+macro_rules! vk_bitflags_wrapped {{
+    ($name: ident, $all: expr, $flag_type: ty, [$($flag: ident),*]) => {{
         #[repr(C)]
         #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-        pub struct $name {flags: $flag_type}
+        {vis} struct $name {{flags: $flag_type}}
+
+        impl fmt::Debug for $name {{
+            fn fmt(&self, f: &mut fmt::Formatter) -> ::{core}::result::Result<(), fmt::Error> {{
+                let named: &[(&str, $flag_type)] = &[$((stringify!($flag), $flag.flags)),*];
+                let mut remaining = self.flags;
+                let mut first = true;
+
+                write!(f, "{{}}(", stringify!($name))?;
+                for &(flag_name, flag) in named {{
+                    // Skip zero-valued flags and flags whose bits have already been printed
+                    if flag != 0 && self.flags & flag == flag && remaining & flag != 0 {{
+                        if !first {{
+                            write!(f, " | ")?;
+                        }}
+                        write!(f, "{{}}", flag_name)?;
+                        remaining &= !flag;
+                        first = false;
+                    }}
+                }}
 
-        impl fmt::Debug for $name {
-            fn fmt(&self, f: &mut fmt::Formatter) -> ::std::result::Result<(), fmt::Error> {
-                write!(f, "{}({:b})", stringify!($name), self.flags)
-            }
-        }
+                // Any bits that don't correspond to a known flag get printed in binary
+                if remaining != 0 || first {{
+                    if !first {{
+                        write!(f, " | ")?;
+                    }}
+                    write!(f, "{{:#b}}", remaining)?;
+                }}
+                write!(f, ")")
+            }}
+        }}
+
+        impl Default for $name {{
+            #[inline]
+            fn default() -> Self {{
+                $name::empty()
+            }}
+        }}
 
-        impl $name {
+        impl $name {{
             #[inline]
-            pub fn empty() -> Self {
-                $name {flags: 0}
-            }
+            {vis} fn empty() -> Self {{
+                $name {{flags: 0}}
+            }}
 
             #[inline]
-            pub fn all() -> Self {
-                $name {flags: $all}
-            }
+            {vis} fn all() -> Self {{
+                $name {{flags: $all}}
+            }}
 
             #[inline]
-            pub fn flags(self) -> $flag_type {
+            {vis} fn flags(self) -> $flag_type {{
                 self.flags
-            }
+            }}
 
             #[inline]
-            pub fn from_flags(flags: $flag_type) -> Option<Self> {
-                if flags & !$all == 0 {
-                    Some($name {flags: flags})
-                } else {
+            {vis} fn from_flags(flags: $flag_type) -> Option<Self> {{
+                if flags & !$all == 0 {{
+                    Some($name {{flags: flags}})
+                }} else {{
                     None
-                }
-            }
+                }}
+            }}
 
             #[inline]
-            pub fn from_flags_truncate(flags: $flag_type) -> Self {
-                $name {flags: flags & $all}
-            }
+            {vis} fn from_flags_truncate(flags: $flag_type) -> Self {{
+                $name {{flags: flags & $all}}
+            }}
 
             #[inline]
-            pub fn is_empty(self) -> bool {
+            {vis} fn is_empty(self) -> bool {{
                 self == Self::empty()
-            }
+            }}
 
             #[inline]
-            pub fn is_all(self) -> bool {
+            {vis} fn is_all(self) -> bool {{
                 self & Self::all() == Self::all()
-            }
+            }}
 
             #[inline]
-            pub fn intersects(self, other: $name) -> bool {
+            {vis} fn intersects(self, other: $name) -> bool {{
                 self & other != Self::empty()
-            }
+            }}
 
             /// Returns true of `other` is a subset of `self`
             #[inline]
-            pub fn subset(self, other: $name) -> bool {
+            {vis} fn subset(self, other: $name) -> bool {{
                 self & other == other
-            }
-        }
+            }}
+        }}
 
-        impl BitOr for $name {
+        impl BitOr for $name {{
             type Output = $name;
 
             #[inline]
-            fn bitor(self, rhs: $name) -> Self {
-                $name {flags: self.flags | rhs.flags }
-            }
-        }
+            fn bitor(self, rhs: $name) -> Self {{
+                $name {{flags: self.flags | rhs.flags }}
+            }}
+        }}
 
-        impl BitOrAssign for $name {
+        impl BitOrAssign for $name {{
             #[inline]
-            fn bitor_assign(&mut self, rhs: Self) {
+            fn bitor_assign(&mut self, rhs: Self) {{
                 *self = *self | rhs
-            }
-        }
+            }}
+        }}
 
-        impl BitAnd for $name {
+        impl BitAnd for $name {{
             type Output = Self;
 
             #[inline]
-            fn bitand(self, rhs: Self) -> Self {
-                $name {flags: self.flags & rhs.flags}
-            }
-        }
+            fn bitand(self, rhs: Self) -> Self {{
+                $name {{flags: self.flags & rhs.flags}}
+            }}
+        }}
 
-        impl BitAndAssign for $name {
+        impl BitAndAssign for $name {{
             #[inline]
-            fn bitand_assign(&mut self, rhs: Self) {
+            fn bitand_assign(&mut self, rhs: Self) {{
                 *self = *self & rhs
-            }
-        }
+            }}
+        }}
 
-        impl BitXor for $name {
+        impl BitXor for $name {{
             type Output = $name;
 
             #[inline]
-            fn bitxor(self, rhs: Self) -> Self {
-                $name {flags: self.flags ^ rhs.flags}
-            }
-        }
+            fn bitxor(self, rhs: Self) -> Self {{
+                $name {{flags: self.flags ^ rhs.flags}}
+            }}
+        }}
 
-        impl BitXorAssign for $name {
+        impl BitXorAssign for $name {{
             #[inline]
-            fn bitxor_assign(&mut self, rhs: Self) {
+            fn bitxor_assign(&mut self, rhs: Self) {{
                 *self = *self ^ rhs
-            }
-        }
+            }}
+        }}
 
-        impl Sub for $name {
+        impl Sub for $name {{
             type Output = Self;
 
             #[inline]
-            fn sub(self, rhs: Self) -> Self {
+            fn sub(self, rhs: Self) -> Self {{
                 self & !rhs
-            }
-        }
+            }}
+        }}
 
-        impl SubAssign for $name {
+        impl SubAssign for $name {{
             #[inline]
-            fn sub_assign(&mut self, rhs: Self) {
+            fn sub_assign(&mut self, rhs: Self) {{
                 *self = *self - rhs
-            }
-        }
+            }}
+        }}
 
-        impl Not for $name {
+        impl Not for $name {{
             type Output = Self;
 
             #[inline]
-            fn not(self) -> Self {
+            fn not(self) -> Self {{
                 self ^ Self::all()
-            }
-        }
-    }
-}
+            }}
+        }}
+    }}
+}}
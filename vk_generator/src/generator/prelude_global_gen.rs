@@ -1,49 +1,58 @@
-macro_rules! vk_functions {
-    ($($raw_name: expr, $name: ident ($($param_name: ident: $param: ty),*,) -> $ret: ty;)+) => {
+macro_rules! vk_functions {{
+    ($report: ident; $($(#[$attr: meta])* $(if cfg($cfg: meta))? $raw_name: literal, $name: ident ($($param_name: ident: $param: ty $(as $raw: ty)?),*,) -> $ret: ty;)*) => {{
         $(
-            pub unsafe extern "system" fn $name (
+            $(#[$attr])*
+            $(#[cfg($cfg)])?
+            {vis} unsafe extern "system" fn $name (
                 $($param_name: $param),*
-                ) -> $ret {
-                use std::mem;
+                ) -> $ret {{
+                use {core}::mem;
 
-                mem::transmute::<_, $name::FnType>($name::FN_PTR)($($param_name),*)
-            }
+                mem::transmute::<_, $name::FnType>($name::FN_PTR)($(vk_param_value!($param_name $(, $raw)?)),*)
+            }}
 
-            pub mod $name {
+            $(#[cfg($cfg)])?
+            {vis} mod $name {{
 
                 use super::super::*;
                 #[allow(unused_imports)]
                 use super::super::libc_reexports::*;
-                pub const RAW_NAME: &'static str = $raw_name;
-                pub static mut FN_PTR: *const () = unloaded_function_panic as *const ();
+                {vis} const RAW_NAME: &'static str = $raw_name;
+                {vis} static mut FN_PTR: *const () = unloaded_function_panic as *const ();
                 #[doc(hidden)]
-                pub type FnType = unsafe extern "system" fn($($param),*) -> $ret;
+                {vis} type FnType = unsafe extern "system" fn($(vk_param_type!($param $(, $raw)?)),*) -> $ret;
 
-                pub fn is_loaded() -> bool {
-                    unsafe{ FN_PTR == unloaded_function_panic as *const () }
-                }
-            }
-        )+
+                {vis} fn is_loaded() -> bool {{
+                    unsafe{{ FN_PTR == unloaded_function_panic as *const () }}
+                }}
+            }}
+        )*
 
-        pub fn load_with<F: FnMut(&str) -> *const ()>(mut load_fn: F) -> ::std::result::Result<(), Vec<&'static str>> {unsafe{
-            use std::ptr; 
+        // Everything in here goes unused if no commands were generated.
+        #[allow(unused)]
+        {vis} fn load_with<F: FnMut(&str) -> *const ()>(mut load_fn: F) -> vk_load_report!($report, type) {{unsafe{{
+            use {core}::ptr; 
             let mut fn_buf: *const (); 
-            let mut unloaded_fns = Vec::new();
+            let mut unloaded_fns = vk_load_report!($report, new);
 
             $(
-                fn_buf = load_fn($raw_name);
-                if ptr::null() != fn_buf {
-                    $name::FN_PTR = fn_buf;
-                } else if $name::FN_PTR == unloaded_function_panic as *const () {
-                    unloaded_fns.push($raw_name)
-                }
-            )+
-
-            if 0 == unloaded_fns.len() {
-                Ok(())
-            } else {
-                Err(unloaded_fns)
-            }
+                $(#[cfg($cfg)])?
+                {{
+                    fn_buf = load_fn($raw_name);
+                    if ptr::null() != fn_buf {{
+                        $name::FN_PTR = fn_buf;
+                    }} else if $name::FN_PTR == unloaded_function_panic as *const () {{
+                        vk_load_report!($report, push unloaded_fns, $raw_name);
+                    }}
+                }}
+            )*
+
+            vk_load_report!($report, finish unloaded_fns)
+        }}}}
+
+        /// Identical to `load_with()`, but takes the loader as a trait object.
+        {vis} fn load_with_dyn(load_fn: &dyn Fn(&str) -> *const ()) -> vk_load_report!($report, type) {{
+            load_with(|name| load_fn(name))
         }}
-    }
-}
+    }}
+}}
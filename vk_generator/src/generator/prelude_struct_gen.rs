@@ -1,62 +1,94 @@
-macro_rules! vk_struct_bindings {
-    ($($raw_name: expr, $name: ident ($($param_name: ident: $param: ty),*,) -> $ret: ty);+;) => {
-        $(type $name = unsafe extern "system" fn($($param),*) -> $ret);+;
+macro_rules! vk_struct_bindings {{
+    ($report: ident, $commands_trait: ident; $($commands: tt)*) => {{
+        vk_struct_bindings!{{$report; $($commands)*}}
+        vk_commands_trait!{{$commands_trait; $($commands)*}}
+    }};
+    ($report: ident; $($(#[$attr: meta])* $(if cfg($cfg: meta))? $raw_name: literal, $name: ident ($($param_name: ident: $param: ty $(as $raw: ty)?),*,) -> $ret: ty;)*) => {{
+        $($(#[cfg($cfg)])? type $name = unsafe extern "system" fn($(vk_param_type!($param $(, $raw)?)),*) -> $ret;)*
 
-        pub struct FnPtr {
-            pub raw_name: &'static str,
-            fn_ptr: *const ()
-        }
+        #[repr(C)]
+        {vis} struct FnPtr {{
+            fn_ptr: *const (),
+            {vis} raw_name: &'static str
+        }}
 
-        impl FnPtr {
-            pub fn is_loaded(&self) -> bool {
+        impl FnPtr {{
+            {vis} fn is_loaded(&self) -> bool {{
                 self.fn_ptr != unloaded_function_panic as *const ()
-            }
-        }
-
-        pub struct Vk {
-            $($name: FnPtr),+
-        }
-
-        impl Vk {
-            pub fn new() -> Vk {unsafe{
-                use std::mem;
-                let mut vk: Vk = mem::uninitialized();
+            }}
+        }}
 
-                $(
-                    vk.$name = FnPtr{ raw_name: $raw_name, fn_ptr: unloaded_function_panic as *const ()};
-                )+
+        #[repr(C)]
+        {vis} struct Vk {{
+            $($name: FnPtr),*
+        }}
 
-                vk
+        impl Vk {{
+            {vis} fn new() -> Vk {{
+                Vk {{
+                    $($name: FnPtr{{ raw_name: $raw_name, fn_ptr: unloaded_function_panic as *const ()}}),*
+                }}
             }}
 
-            pub fn load_with<F: FnMut(&str) -> *const ()>(&mut self, mut load_fn: F) -> ::std::result::Result<(), Vec<&'static str>> {
-                use std::ptr;
+            // Everything in here goes unused if no commands were generated.
+            #[allow(unused)]
+            {vis} fn load_with<F: FnMut(&str) -> *const ()>(&mut self, mut load_fn: F) -> vk_load_report!($report, type) {{
+                use {core}::ptr;
                 let mut fn_buf: *const ();
-                let mut unloaded_fns = Vec::new();
+                let mut unloaded_fns = vk_load_report!($report, new);
 
                 $(
                     fn_buf = load_fn($raw_name);
-                    if ptr::null() != fn_buf {
-                        self.$name = FnPtr{ raw_name: $raw_name, fn_ptr: fn_buf };
-                    } else if self.$name.fn_ptr == unloaded_function_panic as *const () {
-                        unloaded_fns.push($raw_name)
-                    }
-                )+
-
-                if 0 == unloaded_fns.len() {
-                    Ok(())
-                } else {
-                    Err(unloaded_fns)
-                }
-            }
+                    if ptr::null() != fn_buf {{
+                        self.$name = FnPtr{{ raw_name: $raw_name, fn_ptr: fn_buf }};
+                    }} else if self.$name.fn_ptr == unloaded_function_panic as *const () {{
+                        vk_load_report!($report, push unloaded_fns, $raw_name);
+                    }}
+                )*
+
+                vk_load_report!($report, finish unloaded_fns)
+            }}
+
+            /// Identical to `load_with()`, but takes the loader as a trait object.
+            {vis} fn load_with_dyn(&mut self, load_fn: &dyn Fn(&str) -> *const ()) -> vk_load_report!($report, type) {{
+                self.load_with(|name| load_fn(name))
+            }}
+
+            $(
+                $(#[$attr])*
+                $(#[cfg($cfg)])?
+                {vis} unsafe extern "system" fn $name(&self, $($param_name: $param),*) -> $ret {{
+                    use {core}::mem;
+
+                    mem::transmute::<_, $name>(self.$name.fn_ptr)($(vk_param_value!($param_name $(, $raw)?)),*)
+                }}
+            )*
+        }}
+    }}
+}}
+
+// Only used if `wrap_commands_in_trait == true`.
+#[allow(unused_macros)]
+macro_rules! vk_commands_trait {{
+    ($commands_trait: ident; $($(#[$attr: meta])* $(if cfg($cfg: meta))? $raw_name: literal, $name: ident ($($param_name: ident: $param: ty $(as $raw: ty)?),*,) -> $ret: ty;)*) => {{
+        /// Every command that `Vk` can call, so that code using them can be run against something else.
+        {vis} trait $commands_trait {{
+            $(
+                $(#[$attr])*
+                $(#[cfg($cfg)])?
+                unsafe fn $name(&self, $($param_name: $param),*) -> $ret;
+            )*
+        }}
 
+        // The commands can be `#[deprecated]`, which forwarding to them shouldn't warn about.
+        #[allow(deprecated)]
+        impl $commands_trait for Vk {{
             $(
-                pub unsafe extern "system" fn $name(&self, $($param_name: $param),*) -> $ret {
-                    use std::mem;
-
-                    mem::transmute::<_, $name>(self.$name.fn_ptr)($($param_name),*)
-                }
-            )+
-        }
-    }
-}
+                $(#[cfg($cfg)])?
+                unsafe fn $name(&self, $($param_name: $param),*) -> $ret {{
+                    Vk::$name(self, $($param_name),*)
+                }}
+            )*
+        }}
+    }}
+}}
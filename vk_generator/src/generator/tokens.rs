@@ -0,0 +1,39 @@
+//! Generating bindings as a `proc_macro2::TokenStream`, for procedural macros that splice the
+//! bindings into their own output. Only built with the `proc-macro2` feature.
+//!
+//! The generators assemble their output as source text, including the `include_str!` preludes, so
+//! the token stream is lexed from that text in one pass here rather than by every caller.
+use registry::{VkRegistry, VkVersion};
+use generator::GenConfig;
+
+use std::io;
+use std::str::FromStr;
+
+use proc_macro2::TokenStream;
+
+impl<'a> VkRegistry<'a> {
+    /// Generate the same bindings as [`gen_global()`], as a token stream. Errors are the same as
+    /// [`gen_global()`]'s.
+    ///
+    /// [`gen_global()`]: #method.gen_global
+    pub fn gen_global_tokens(&self, version: VkVersion, extensions: &[&str], config: GenConfig) -> io::Result<TokenStream> {
+        let mut out = Vec::new();
+        self.gen_global(&mut out, version, extensions, config)?;
+        lex(out)
+    }
+
+    /// Generate the same bindings as [`gen_struct()`], as a token stream. Errors are the same as
+    /// [`gen_struct()`]'s.
+    ///
+    /// [`gen_struct()`]: #method.gen_struct
+    pub fn gen_struct_tokens(&self, version: VkVersion, extensions: &[&str], config: GenConfig) -> io::Result<TokenStream> {
+        let mut out = Vec::new();
+        self.gen_struct(&mut out, version, extensions, config)?;
+        lex(out)
+    }
+}
+
+fn lex(out: Vec<u8>) -> io::Result<TokenStream> {
+    let out = String::from_utf8(out).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    TokenStream::from_str(&out).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Generated bindings don't lex: {}", e)))
+}
@@ -4,6 +4,10 @@
 //! documentation can be found in [`VkRegistry`] and [`GenConfig`], and looking over the provided
 //! [examples](https://github.com/Osspial/vk-rs/tree/master/vk_generator/examples) is encouraged.
 //!
+//! The generated file never declares any crates or crate-level attributes, so it can be
+//! `include!`d into any module. If `use_libc_types` is enabled, the including crate has to declare
+//! `extern crate libc` itself.
+//!
 //! [`VkRegistry`]: ./struct.VkRegistry.html
 //! [`GenConfig`]: ./struct.GenConfig.html
 //! [`gen_global()`]: ./struct.VkRegistry.html#method.gen_global
@@ -11,6 +15,11 @@
 
 extern crate xml;
 extern crate boolinator;
+#[cfg(feature = "proc-macro2")]
+extern crate proc_macro2;
+#[cfg(feature = "serde_json")]
+#[macro_use]
+extern crate serde_json;
 
 use std::mem;
 
@@ -24,8 +33,8 @@ mod registry;
 #[cfg(not(feature = "unstable_generator_api"))]
 mod generator;
 
-pub use registry::{VkRegistry, VkVersion};
-pub use generator::{GenConfig, VariantPaddingConfig};
+pub use registry::{Diagnostic, TypesByCategory, VkFieldView, VkRegistry, VkTypeKind, VkTypeView, VkVersion};
+pub use generator::{GenConfig, GenConfigBuilder, GenStats, IdentKind, LoadReporting, Renamer, Timings, TypesSubset, VariantAction, VariantPaddingConfig, Visibility};
 
 #[inline]
 fn to_option<'u>(s: *const str) -> Option<&'u str> {
@@ -0,0 +1,83 @@
+//! The string storage behind the `*const str` pointers handed out by the registry and generator.
+
+use std::{slice, str};
+
+/// The smallest chunk the arena will allocate, so that small buffers don't allocate a new chunk for
+/// every string.
+const MIN_CHUNK: usize = 4096;
+
+/// An append-only store of strings. Strings are stored in a chain of chunks which are never
+/// reallocated, so a pointer returned by the arena stays valid for as long as the arena lives, no
+/// matter how much gets appended to it afterwards.
+pub struct StringArena {
+    chunks: Vec<String>,
+    /// Scratch space for `append_with`, which can't know how long a string is before it's written.
+    scratch: String
+}
+
+impl StringArena {
+    /// Create an arena that can hold `capacity` bytes before it has to allocate another chunk.
+    pub fn with_capacity(capacity: usize) -> StringArena {
+        StringArena {
+            chunks: vec![String::with_capacity(capacity.max(MIN_CHUNK))],
+            scratch: String::new()
+        }
+    }
+
+    /// The total number of bytes the arena can hold without allocating another chunk.
+    pub fn capacity(&self) -> usize {
+        self.chunks.iter().map(|c| c.capacity()).sum()
+    }
+
+    /// Append `string` to the arena, returning a pointer to the stored copy.
+    pub fn append(&mut self, string: &str) -> *const str {
+        let needs_chunk = {
+            let chunk = self.chunks.last().unwrap();
+            chunk.capacity() - chunk.len() < string.len()
+        };
+        if needs_chunk {
+            // New chunks are as big as the last one, so a bad first guess doesn't mean lots of tiny chunks.
+            let capacity = string.len().max(self.chunks.last().unwrap().capacity());
+            self.chunks.push(String::with_capacity(capacity));
+        }
+
+        let chunk = self.chunks.last_mut().unwrap();
+        let start = chunk.len();
+        chunk.push_str(string);
+
+        unsafe {
+            let ptr = chunk.as_ptr().add(start);
+            str::from_utf8_unchecked(slice::from_raw_parts(ptr, string.len())) as *const str
+        }
+    }
+
+    /// Append whatever `write` writes to the string it's given, returning a pointer to the stored
+    /// copy.
+    pub fn append_with<F: FnOnce(&mut String)>(&mut self, write: F) -> *const str {
+        let mut scratch = ::std::mem::take(&mut self.scratch);
+        scratch.clear();
+        write(&mut scratch);
+        let ptr = self.append(&scratch);
+        self.scratch = scratch;
+        ptr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pointers_survive_growth() {
+        let mut arena = StringArena::with_capacity(0);
+        let strings: Vec<String> = (0..10_000).map(|i| format!("VK_STRING_{}", i)).collect();
+        let ptrs: Vec<_> = strings.iter().map(|s| arena.append(s)).collect();
+        let built = arena.append_with(|s| s.push_str(&"x".repeat(3 * MIN_CHUNK)));
+
+        assert!(arena.chunks.len() > 1);
+        for (s, p) in strings.iter().zip(ptrs.iter()) {
+            assert_eq!(s, unsafe{ &**p });
+        }
+        assert_eq!(unsafe{ &*built }.len(), 3 * MIN_CHUNK);
+    }
+}
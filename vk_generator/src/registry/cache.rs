@@ -0,0 +1,210 @@
+//! A binary encoding of the xml events that a registry is crawled from, so that a registry can be
+//! rebuilt without running the xml parser, which takes up nearly all of the time spent in
+//! `VkRegistry::new`.
+//!
+//! The registry itself is all pointers into its string buffer, which can't be written out as they
+//! are. Instead, every distinct string in the document is written once to a string table, the events
+//! refer to strings by their index in that table, and the crawler builds a fresh registry (and fresh
+//! pointers) out of the decoded events.
+//!
+//! The layout, with every integer a little-endian `u32`:
+//!
+//! ```text
+//! magic ("VKRC") | version | xml length | string count | (length, bytes)* | event count | event*
+//! ```
+//!
+//! where each event is a tag byte followed by string indices: `0` for the start of an element (its
+//! name, attribute count and each attribute's name and value), `1` for the end of one (its name) and
+//! `2` for text.
+
+use xml::attribute::OwnedAttribute;
+use xml::name::OwnedName;
+use xml::namespace::Namespace;
+use xml::reader::XmlEvent;
+
+use std::collections::HashMap;
+use std::str;
+
+const MAGIC: &[u8] = b"VKRC";
+/// Bumped whenever the layout changes, so that stale caches are rejected instead of misread.
+const VERSION: u32 = 1;
+
+const START_ELEMENT: u8 = 0;
+const END_ELEMENT: u8 = 1;
+const CHARACTERS: u8 = 2;
+
+/// Encode `events`, which were parsed from an xml document `xml_len` bytes long. Only the events the
+/// crawler looks at are kept.
+pub fn encode<I: Iterator<Item = XmlEvent>>(events: I, xml_len: usize) -> Vec<u8> {
+    let mut strings: HashMap<String, u32> = HashMap::with_capacity(2usize.pow(14));
+    let mut table: Vec<u8> = Vec::with_capacity(xml_len / 2);
+    let mut body: Vec<u8> = Vec::with_capacity(xml_len / 4);
+    let mut event_count = 0;
+
+    {
+        let mut index = |string: String, table: &mut Vec<u8>| -> u32 {
+            let next = strings.len() as u32;
+            *strings.entry(string).or_insert_with_key(|s| {
+                push_u32(table, s.len() as u32);
+                table.extend_from_slice(s.as_bytes());
+                next
+            })
+        };
+
+        for event in events {
+            match event {
+                XmlEvent::StartElement{name, attributes, ..} => {
+                    body.push(START_ELEMENT);
+                    let name = index(name.local_name, &mut table);
+                    push_u32(&mut body, name);
+                    push_u32(&mut body, attributes.len() as u32);
+                    for attr in attributes {
+                        let attr_name = index(attr.name.local_name, &mut table);
+                        let value = index(attr.value, &mut table);
+                        push_u32(&mut body, attr_name);
+                        push_u32(&mut body, value);
+                    }
+                }
+                XmlEvent::EndElement{name} => {
+                    body.push(END_ELEMENT);
+                    let name = index(name.local_name, &mut table);
+                    push_u32(&mut body, name);
+                }
+                XmlEvent::Characters(chars) => {
+                    body.push(CHARACTERS);
+                    let chars = index(chars, &mut table);
+                    push_u32(&mut body, chars);
+                }
+                _ => continue
+            }
+            event_count += 1;
+        }
+    }
+
+    let mut cache = Vec::with_capacity(MAGIC.len() + 16 + table.len() + body.len());
+    cache.extend_from_slice(MAGIC);
+    push_u32(&mut cache, VERSION);
+    push_u32(&mut cache, xml_len as u32);
+    push_u32(&mut cache, strings.len() as u32);
+    cache.extend_from_slice(&table);
+    push_u32(&mut cache, event_count);
+    cache.extend_from_slice(&body);
+    cache
+}
+
+/// Decode a cache written by `encode()`, returning the events and the length of the original
+/// document. Returns an error describing the problem if the cache is truncated, corrupt or from a
+/// different version of the format.
+pub fn decode(cache: &[u8]) -> Result<(Vec<XmlEvent>, usize), &'static str> {
+    if !cache.starts_with(MAGIC) {
+        return Err("not a registry cache");
+    }
+    let mut reader = Reader{ bytes: cache, pos: MAGIC.len() };
+    if reader.u32()? != VERSION {
+        return Err("the cache was written by a different version of the generator");
+    }
+    let xml_len = reader.u32()? as usize;
+
+    let string_count = reader.u32()? as usize;
+    let mut strings = Vec::with_capacity(string_count.min(cache.len()));
+    for _ in 0..string_count {
+        let len = reader.u32()? as usize;
+        let bytes = reader.bytes(len)?;
+        strings.push(str::from_utf8(bytes).map_err(|_| "the cache contains invalid UTF-8")?);
+    }
+    let string = |reader: &mut Reader| -> Result<String, &'static str> {
+        strings.get(reader.u32()? as usize).map(|s| (*s).to_owned()).ok_or("the cache refers to a missing string")
+    };
+
+    let event_count = reader.u32()? as usize;
+    let mut events = Vec::with_capacity(event_count.min(cache.len()));
+    for _ in 0..event_count {
+        let event = match reader.bytes(1)?[0] {
+            START_ELEMENT => {
+                let name = OwnedName::local(string(&mut reader)?);
+                let attr_count = reader.u32()? as usize;
+                let mut attributes = Vec::with_capacity(attr_count.min(cache.len()));
+                for _ in 0..attr_count {
+                    let attr_name = OwnedName::local(string(&mut reader)?);
+                    attributes.push(OwnedAttribute{ name: attr_name, value: string(&mut reader)? });
+                }
+                XmlEvent::StartElement{ name, attributes, namespace: Namespace::empty() }
+            }
+            END_ELEMENT => XmlEvent::EndElement{ name: OwnedName::local(string(&mut reader)?) },
+            CHARACTERS  => XmlEvent::Characters(string(&mut reader)?),
+            _           => return Err("the cache contains an unknown event")
+        };
+        events.push(event);
+    }
+
+    if reader.pos != cache.len() {
+        return Err("the cache has trailing data");
+    }
+    Ok((events, xml_len))
+}
+
+fn push_u32(bytes: &mut Vec<u8>, value: u32) {
+    bytes.extend_from_slice(&value.to_le_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], &'static str> {
+        let bytes = self.bytes.get(self.pos..self.pos.saturating_add(len)).ok_or("the cache is truncated")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, &'static str> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from(bytes[0]) | u32::from(bytes[1]) << 8 | u32::from(bytes[2]) << 16 | u32::from(bytes[3]) << 24)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use xml::{EventReader, ParserConfig};
+
+    const DOCUMENT: &[u8] = br#"<registry><types><type category="struct" name="VkA">text</type></types></registry>"#;
+
+    fn parse(xml: &[u8]) -> Vec<XmlEvent> {
+        EventReader::new_with_config(xml, ParserConfig::new().trim_whitespace(true))
+            .into_iter().map(|e| e.unwrap()).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let events = parse(DOCUMENT);
+        let (decoded, len) = decode(&encode(events.clone().into_iter(), DOCUMENT.len())).unwrap();
+        // Namespaces aren't kept, since the crawler never looks at them.
+        let kept: Vec<_> = events.into_iter().filter_map(|e| match e {
+            XmlEvent::StartElement{name, attributes, ..} =>
+                Some(XmlEvent::StartElement{ name, attributes, namespace: Namespace::empty() }),
+            XmlEvent::EndElement{..} | XmlEvent::Characters(_) => Some(e),
+            _                                                  => None
+        }).collect();
+
+        assert_eq!(len, DOCUMENT.len());
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", kept));
+    }
+
+    #[test]
+    fn bad_caches() {
+        let cache = encode(parse(DOCUMENT).into_iter(), DOCUMENT.len());
+        assert_eq!(decode(b"<registry>").unwrap_err(), "not a registry cache");
+        assert_eq!(decode(&cache[..cache.len() - 1]).unwrap_err(), "the cache is truncated");
+
+        let mut trailing = cache.clone();
+        trailing.push(0);
+        assert_eq!(decode(&trailing).unwrap_err(), "the cache has trailing data");
+
+        let mut other_version = cache.clone();
+        other_version[MAGIC.len()] += 1;
+        assert!(decode(&other_version).is_err());
+    }
+}
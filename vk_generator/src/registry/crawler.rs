@@ -1,14 +1,15 @@
 //! Module that contains a crawler function that crawls through the vulkan xml and returns a
 //! Registry struct
-use xml::reader::{Events, XmlEvent};
+use xml::reader::{self, XmlEvent};
 use xml::name::OwnedName;
 use xml::attribute::OwnedAttribute;
-use std::io::Read;
 use std::slice::Iter;
 use std::num::ParseIntError;
-use super::{VkRegistry, VkType, VkMember, VkVariant, VkCommand, VkParam, VkFeature, VkVersion, VkReqRem, VkExtn, VkElType};
+use super::{Diagnostic, VkRegistry, VkType, VkMember, VkVariant, VkCommand, VkParam, VkFeature, VkVersion, VkReqRem, VkExtn, VkElType};
 
-pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
+/// Crawl `xml_events` into `registry`, returning an error describing what was wrong with the xml if
+/// it isn't a valid registry.
+pub fn crawl<I: Iterator<Item = reader::Result<XmlEvent>>>(xml_events: I, registry: &mut VkRegistry) -> Result<(), String> {
     use self::XmlElement::*;
 
     // Everything that we're going to put in the registry get stored in temporary buffers before it's   |
@@ -19,6 +20,9 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
     let mut interface_reqrem = VkReqRem::None;
     let mut extn_buffer: Option<VkExtn> = None;
     let mut cur_block = VkBlock::None;
+    // Why the type in `type_buffer` is unhandled, if it is and its name is in a `<name>` tag that
+    // hasn't been reached yet.
+    let mut unhandled_reason: Option<&'static str> = None;
     // A variable that contains what the index of the element in vk_elements that vk_elements was
     // popped to. Used to prevent the element iterator from going over elements that have already
     // been checked.
@@ -26,10 +30,21 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
 
     // A stack of the relevant elements in the vulkan xml
     let mut vk_elements = Vec::with_capacity(10);
+    // How many levels deep the crawler is inside of an element that's only for other APIs or is in
+    // `SKIPPED_BLOCKS`, which are skipped entirely.
+    let mut skip_depth = 0;
 
     for event in xml_events {
-        let event = event.unwrap();
+        let event = event.map_err(|e| e.to_string())?;
         match event {
+            XmlEvent::StartElement{..} if 0 < skip_depth => skip_depth += 1,
+            XmlEvent::EndElement{..} if 0 < skip_depth   => skip_depth -= 1,
+            XmlEvent::Characters(..) if 0 < skip_depth   => (),
+            XmlEvent::StartElement{ref name, ref attributes, ..} if for_other_api(&name.local_name, attributes, registry.api()) =>
+                skip_depth = 1,
+            XmlEvent::StartElement{ref name, ..} if SKIPPED_BLOCKS.contains(&&*name.local_name) =>
+                skip_depth = 1,
+
             XmlEvent::StartElement{ name, attributes, .. } => {
                 vk_elements.push(XmlElement::new_tag(name, attributes));
             }
@@ -54,62 +69,103 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                         } else {
                                             match find_attribute(tag_attrs, "type") {
                                                 Some("enum")    => type_buffer = VkType::new_enum(registry.append_str(name)),
-                                                Some("bitmask") => type_buffer = VkType::new_bitmask(registry.append_str(name)),
-                                                t               => panic!(format!("Unexpected enum type {:?} {}", t, name))
+                                                Some("bitmask") => {
+                                                    let bitwidth = match find_attribute(tag_attrs, "bitwidth") {
+                                                        Some(w) => w.parse().map_err(|_| format!("Invalid bitwidth {} on {}", w, name))?,
+                                                        None    => 32
+                                                    };
+                                                    type_buffer = VkType::new_bitmask(registry.append_str(name), bitwidth);
+                                                }
+                                                t               => return Err(format!("Unexpected enum type {:?} {}", t, name))
+                                            }
+                                            if let Some(comment) = find_attribute(tag_attrs, "comment") {
+                                                type_buffer.set_comment(registry.append_str(comment)).unwrap();
                                             }
                                         }
-                                    } else {panic!("Could not find enum name")},
+                                    } else {return Err("Could not find enum name".to_owned())},
                                 "enum"
                                     if VkBlock::Enums == cur_block =>
                                     if let Some(name) = find_attribute(tag_attrs, "name") {
                                         match type_buffer {
-                                            VkType::Enum{name: enum_name, ref mut variants} |
-                                            VkType::Bitmask{name: enum_name, ref mut variants} => {
+                                            VkType::Enum{name: enum_name, ref mut variants, ..} |
+                                            VkType::Bitmask{name: enum_name, ref mut variants, ..} => {
                                                 let name = registry.append_str(name);
 
                                                 if "API Constants" == unsafe{ &*enum_name } {
-                                                    if let Some(value) = find_attribute(tag_attrs, "value") {
+                                                    // Aliased constants get the name of the constant they alias as
+                                                    // their value, which the generator skips over.
+                                                    if let Some(value) = find_attribute(tag_attrs, "value").or(find_attribute(tag_attrs, "alias")) {
                                                         let value = registry.append_str(value);
                                                         registry.push_type(VkType::new_const(name, value)).ok();
-                                                    } else {panic!("Could not find value in API Constant")}
+                                                    } else {return Err("Could not find value in API Constant".to_owned())}
                                                 } else {
                                                     variants.push(
                                                         if let Some(value) = find_attribute(tag_attrs, "value") {
-                                                            VkVariant::new_value(name, to_number(value).unwrap())
+                                                            VkVariant::new_value(name, to_number(value).map_err(|_| format!("Invalid enum value {}", value))?)
                                                         } else if let Some(bitpos) = find_attribute(tag_attrs, "bitpos") {
-                                                            VkVariant::new_bitpos(name, to_number(bitpos).unwrap() as u32)
-                                                        } else {panic!("Could not find value or bitpos in enum")}
+                                                            VkVariant::new_bitpos(name, parse_bitpos(bitpos)?)
+                                                        } else if let Some(alias) = find_attribute(tag_attrs, "alias") {
+                                                            VkVariant::new_alias(name, registry.append_str(alias))
+                                                        } else {return Err("Could not find value, bitpos or alias in enum".to_owned())}
+                                                        .with_comment(find_attribute(tag_attrs, "comment").map(|c| registry.append_str(c)))
                                                     );
                                                 }
                                             }
                                             _ => ()
                                         }
-                                    } else {panic!("Could not find enum variant name")},
+                                    } else {return Err("Could not find enum variant name".to_owned())},
 
 
                                 // Handle type (struct, union, typedef, etc.) registration
                                 "types"      => cur_block = VkBlock::Types,
                                 "type"
                                     if VkBlock::Types == cur_block =>
-                                    if let Some(category) = find_attribute(tag_attrs, "category") {
+                                    if let (Some(name), Some(alias)) = (find_attribute(tag_attrs, "name"), find_attribute(tag_attrs, "alias")) {
+                                        registry.push_type(type_buffer).ok();
+                                        let deprecated = find_attribute(tag_attrs, "deprecated").map(|d| registry.append_str(d));
+                                        type_buffer = VkType::new_alias(registry.append_str(name), registry.append_str(alias))
+                                            .with_deprecated(deprecated);
+                                    } else if let Some(category) = find_attribute(tag_attrs, "category") {
                                         registry.push_type(type_buffer).ok();
                                         match category {
                                             "basetype"       |
-                                            "bitmask"       => type_buffer = VkType::new_typedef(find_attribute(tag_attrs, "requires").map(|r| registry.append_str(r))),
+                                            // Newer registries name the flag bits a bitmask takes with `bitvalues`.
+                                            "bitmask"       => {
+                                                let requires = find_attribute(tag_attrs, "requires").or(find_attribute(tag_attrs, "bitvalues"));
+                                                type_buffer = VkType::new_typedef(requires.map(|r| registry.append_str(r)))
+                                            }
                                             "define"        =>
                                                 if let Some(name) = find_attribute(tag_attrs, "name") {
                                                     type_buffer = VkType::new_define(registry.append_str(name));
                                                 } else {type_buffer = VkType::empty_define()},
-                                            "enum"          => type_buffer = VkType::Unhandled,
+                                            "enum"          => {
+                                                type_buffer = VkType::Unhandled;
+                                                unhandled_reason = diagnose_unhandled(registry, tag_attrs, "enums are defined by their <enums> block");
+                                            }
                                             "funcpointer"   => type_buffer = VkType::empty_funcpointer(),
-                                            "group"         => type_buffer = VkType::Unhandled,
-                                            "handle"        => type_buffer = VkType::empty_handle(),
-                                            "include"       => type_buffer = VkType::Unhandled,
-                                            "struct"        =>
-                                                type_buffer = VkType::new_struct(registry.append_str(find_attribute(tag_attrs, "name").unwrap())),
+                                            "group"         => {
+                                                type_buffer = VkType::Unhandled;
+                                                unhandled_reason = diagnose_unhandled(registry, tag_attrs, "groups aren't used by the generator");
+                                            }
+                                            "handle"        => type_buffer = VkType::empty_handle(find_attribute(tag_attrs, "objtypeenum").map(|o| registry.append_str(o))),
+                                            "include"       => {
+                                                type_buffer = VkType::Unhandled;
+                                                unhandled_reason = diagnose_unhandled(registry, tag_attrs, "headers are never included by the generated code");
+                                            }
+                                            "struct"        => {
+                                                type_buffer = VkType::new_struct(registry.append_str(find_attribute(tag_attrs, "name").unwrap()));
+                                                if let VkType::Struct{ref mut extends, ..} = type_buffer {
+                                                    for e in find_attribute(tag_attrs, "structextends").into_iter().flat_map(|e| e.split(',')) {
+                                                        extends.push(registry.append_str(e));
+                                                    }
+                                                }
+                                            }
                                             "union"         =>
                                                 type_buffer = VkType::new_union(registry.append_str(find_attribute(tag_attrs, "name").unwrap())),
-                                            _               => panic!("Unexpected category")
+                                            _               => return Err("Unexpected category".to_owned())
+                                        }
+                                        if let Some(comment) = find_attribute(tag_attrs, "comment") {
+                                            type_buffer.set_comment(registry.append_str(comment)).ok();
                                         }
                                     } else if let Some(requires) = find_attribute(tag_attrs, "requires") {
                                         registry.push_type(type_buffer).ok();
@@ -117,20 +173,26 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                         if let Some(name) = find_attribute(tag_attrs, "name") {
                                             let name = registry.append_str(name);
                                             type_buffer = VkType::new_extern(name, requires);
-                                        } else {panic!("Expected external type name; found nothing")}
+                                        } else {return Err("Expected external type name; found nothing".to_owned())}
                                     },
                                 // Members of structs and unions
                                 "member"
                                     if VkBlock::Types == cur_block =>
                                     match type_buffer {
                                         VkType::Struct{fields: ref mut members, ..}   |
-                                        VkType::Union{variants: ref mut members, ..} =>
-                                            if let Some("true") = find_attribute(tag_attrs, "optional") {
-                                                members.push(VkMember::empty(true))
-                                            } else {members.push(VkMember::empty(false))},
-                                        _                                            => panic!("Unexpected \"member\" tag found")
+                                        VkType::Union{variants: ref mut members, ..} => {
+                                            let mut member = VkMember::empty(Some("true") == find_attribute(tag_attrs, "optional"));
+                                            if let Some(values) = find_attribute(tag_attrs, "values") {
+                                                member.values = registry.append_str(values);
+                                            }
+                                            if let Some(len) = find_attribute(tag_attrs, "len") {
+                                                member.len = registry.append_str(len);
+                                            }
+                                            members.push(member)
+                                        }
+                                        _                                            => return Err("Unexpected \"member\" tag found".to_owned())
                                     },
-                                "member"     => panic!("\"member\" tag found outside of \"types\" block"),
+                                "member"     => return Err("\"member\" tag found outside of \"types\" block".to_owned()),
 
 
                                 // Register commands. Most of the relevant code is in the `Characters` blocks
@@ -138,10 +200,33 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                 "command"
                                     if VkBlock::Commands == cur_block => {
                                         registry.push_command(command_buffer).ok();
-                                        command_buffer = Some(VkCommand::empty());
+                                        let mut command = VkCommand::empty();
+                                        for c in find_attribute(tag_attrs, "successcodes").into_iter().flat_map(|c| c.split(',')) {
+                                            command.success_codes.push(registry.append_str(c));
+                                        }
+                                        if let Some(comment) = find_attribute(tag_attrs, "comment") {
+                                            command.comment = registry.append_str(comment);
+                                        }
+                                        // Aliases are empty `<command>` tags, so their names come from an attribute
+                                        // rather than a `<proto>`.
+                                        if let (Some(name), Some(alias)) = (find_attribute(tag_attrs, "name"), find_attribute(tag_attrs, "alias")) {
+                                            command.name = registry.append_str(name);
+                                            command.alias_of = registry.append_str(alias);
+                                        }
+                                        command_buffer = Some(command);
                                     }
                                 "param"
-                                    if VkBlock::Commands == cur_block => command_buffer.as_mut().unwrap().params.push(VkParam::empty()),
+                                    if VkBlock::Commands == cur_block => {
+                                        let mut param = VkParam::empty();
+                                        if let Some(externsync) = find_attribute(tag_attrs, "externsync") {
+                                            param.externsync = registry.append_str(externsync);
+                                        }
+                                        param.noautovalidity = Some("true") == find_attribute(tag_attrs, "noautovalidity");
+                                        if let Some(len) = find_attribute(tag_attrs, "len") {
+                                            param.len = registry.append_str(len);
+                                        }
+                                        command_buffer.as_mut().unwrap().params.push(param)
+                                    }
 
 
                                 // Register features. Features are the function/type declarations for the specific API version.
@@ -150,30 +235,30 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                         if let Some(version) = find_attribute(tag_attrs, "number") {
                                             cur_block = VkBlock::Feature;
                                             registry.push_feature(feature_buffer).ok();
-                                            feature_buffer = Some(VkFeature::new(registry.append_str(name), VkVersion::from_str(version)));
-                                        } else {panic!("Could not find feature number")}
-                                    } else {panic!("Could not find feature name")},
+                                            feature_buffer = Some(VkFeature::new(registry.append_str(name), VkVersion::from_str(version)?));
+                                        } else {return Err("Could not find feature number".to_owned())}
+                                    } else {return Err("Could not find feature name".to_owned())},
                                 "require"
-                                    if VkBlock::Feature == cur_block =>
+                                    if VkBlock::Feature == cur_block || VkBlock::Extensions == cur_block =>
                                     interface_reqrem = VkReqRem::Require(find_attribute(tag_attrs, "profile").map(|s| registry.append_str(s))),
                                 "remove"
-                                    if VkBlock::Feature == cur_block =>
+                                    if VkBlock::Feature == cur_block || VkBlock::Extensions == cur_block =>
                                     interface_reqrem = VkReqRem::Remove(find_attribute(tag_attrs, "profile").map(|s| registry.append_str(s))),
                                 "command"
                                     if VkBlock::Feature == cur_block =>
                                     if let Some(name) = find_attribute(tag_attrs, "name") {
-                                        feature_buffer.as_mut().unwrap().push_command(registry.append_str(name), &interface_reqrem);
-                                    } else {panic!("Could not find feature name")},
+                                        feature_buffer.as_mut().unwrap().push_command(registry.append_str(name), &interface_reqrem)?;
+                                    } else {return Err("Could not find feature name".to_owned())},
                                 "enum"
                                     if VkBlock::Feature == cur_block =>
                                     if let Some(name) = find_attribute(tag_attrs, "name") {
-                                        feature_buffer.as_mut().unwrap().push_const(registry.append_str(name), &interface_reqrem);
-                                    } else {panic!("Could not find feature name")},
+                                        feature_buffer.as_mut().unwrap().push_const(registry.append_str(name), &interface_reqrem)?;
+                                    } else {return Err("Could not find feature name".to_owned())},
                                 "type"
                                     if VkBlock::Feature == cur_block =>
                                     if let Some(name) = find_attribute(tag_attrs, "name") {
-                                        feature_buffer.as_mut().unwrap().push_type(registry.append_str(name), &interface_reqrem);
-                                    } else {panic!("Could not find feature name")},
+                                        feature_buffer.as_mut().unwrap().push_type(registry.append_str(name), &interface_reqrem)?;
+                                    } else {return Err("Could not find feature name".to_owned())},
 
 
                                 // Register extensions.
@@ -183,19 +268,38 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                     if let Some(name) = find_attribute(tag_attrs, "name") {
                                         if let Some(num) = find_attribute(tag_attrs, "number") {
                                             registry.push_extn(extn_buffer).ok();
-                                            extn_buffer = Some(VkExtn::new(registry.append_str(name), isize::from_str_radix(num, 10).unwrap()))
-                                        } else {panic!("Could not find extension number")}
-                                    } else {panic!("Could not find extension name")},
+                                            // Older registries list everything that's needed in `requires`, and the core
+                                            // version separately in `requiresCore`.
+                                            let depends = find_attribute(tag_attrs, "depends").map(|d| d.to_owned()).or_else(|| {
+                                                let requires = find_attribute(tag_attrs, "requires").map(|r| r.replace(',', "+"));
+                                                let core = find_attribute(tag_attrs, "requiresCore").map(|v| format!("VK_VERSION_{}", v.replace('.', "_")));
+                                                match (requires, core) {
+                                                    (Some(r), Some(c)) => Some(format!("{}+{}", r, c)),
+                                                    (r, c)             => r.or(c)
+                                                }
+                                            });
+                                            // Older registries only have the `VK_USE_PLATFORM_<PLATFORM>_<VENDOR>` macro
+                                            // that guards the extension, which the platform's name can be taken from.
+                                            let platform = find_attribute(tag_attrs, "platform").map(|p| p.to_owned())
+                                                .or_else(|| find_attribute(tag_attrs, "protect").and_then(platform_from_protect));
+                                            extn_buffer = Some(VkExtn::new(registry.append_str(name), num.parse().map_err(|_| format!("Invalid extension number {}", num))?)
+                                                .with_depends(depends.map(|d| registry.append_str(&d)))
+                                                .with_platform(platform.map(|p| registry.append_str(&p)))
+                                                .with_replacements(find_attribute(tag_attrs, "promotedto").map(|p| registry.append_str(p)),
+                                                                   find_attribute(tag_attrs, "deprecatedby").map(|d| registry.append_str(d)),
+                                                                   find_attribute(tag_attrs, "obsoletedby").map(|o| registry.append_str(o))));
+                                        } else {return Err("Could not find extension number".to_owned())}
+                                    } else {return Err("Could not find extension name".to_owned())},
                                 "command"
                                     if VkBlock::Extensions == cur_block =>
                                     if let Some(name) = find_attribute(tag_attrs, "name") {
-                                        extn_buffer.as_mut().unwrap().push_command(registry.append_str(name), &interface_reqrem);
-                                    } else {panic!("Could not find extension command name")},
+                                        extn_buffer.as_mut().unwrap().push_command(registry.append_str(name), &interface_reqrem)?;
+                                    } else {return Err("Could not find extension command name".to_owned())},
                                 "type"
                                     if VkBlock::Extensions == cur_block =>
                                     if let Some(name) = find_attribute(tag_attrs, "name") {
-                                        extn_buffer.as_mut().unwrap().push_type(registry.append_str(name), &interface_reqrem);
-                                    } else {panic!("Could not find extension type name")},
+                                        extn_buffer.as_mut().unwrap().push_type(registry.append_str(name), &interface_reqrem)?;
+                                    } else {return Err("Could not find extension type name".to_owned())},
                                 "enum"
                                     if VkBlock::Extensions == cur_block =>
                                     if let Some(name) = find_attribute(tag_attrs, "name") {
@@ -205,8 +309,13 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                             let variant =
                                                 if let Some(offset) = find_attribute(tag_attrs, "offset") {
                                                     // Determine enumerant value, as defined in the "Layers & Extensions" appendix of the spec
-                                                    let offset = isize::from_str_radix(offset, 10).unwrap();
-                                                    let extn_num = extn_buffer.as_ref().unwrap().num;
+                                                    let offset: isize = offset.parse().map_err(|_| format!("Invalid enum offset {}", offset))?;
+                                                    // `extnumber` is there when the enum belongs to a different extension
+                                                    // than the one that's requiring it.
+                                                    let extn_num = match find_attribute(tag_attrs, "extnumber") {
+                                                        Some(num) => num.parse().map_err(|_| format!("Invalid extension number {}", num))?,
+                                                        None      => extn_buffer.as_ref().unwrap().num
+                                                    };
                                                     let mut value = BASE_VALUE + (extn_num - 1) * RANGE_SIZE + offset;
 
                                                     if let Some("-") = find_attribute(tag_attrs, "dir") {
@@ -215,23 +324,24 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
 
                                                     VkVariant::new_value(name, value)
                                                 } else if let Some(value) = find_attribute(tag_attrs, "value") {
-                                                    let value = isize::from_str_radix(value, 10).unwrap();
-                                                    VkVariant::new_value(name, value)
+                                                    VkVariant::new_value(name, to_number(value).map_err(|_| format!("Invalid enum value {}", value))?)
                                                 } else if let Some(bitpos) = find_attribute(tag_attrs, "bitpos") {
-                                                    let bitpos = u32::from_str_radix(bitpos, 10).unwrap();
-                                                    VkVariant::new_bitpos(name, bitpos)
-                                                } else {panic!("Invalid enum extension; missing \"offset\" or \"bitpos\"")};
+                                                    VkVariant::new_bitpos(name, parse_bitpos(bitpos)?)
+                                                } else if let Some(alias) = find_attribute(tag_attrs, "alias") {
+                                                    VkVariant::new_alias(name, registry.append_str(alias))
+                                                } else {return Err("Invalid enum extension; missing \"offset\", \"bitpos\" or \"alias\"".to_owned())}
+                                                .with_comment(find_attribute(tag_attrs, "comment").map(|c| registry.append_str(c)));
 
                                             extn_buffer.as_mut().unwrap().push_enum(
                                                 variant,
                                                 Some(registry.append_str(extends)),
-                                                &interface_reqrem);
+                                                &interface_reqrem)?;
                                         } else if let Some(value) = find_attribute(tag_attrs, "value") {
                                             extn_buffer.as_mut().unwrap().push_const(
                                                 name, registry.append_str(value),
-                                                &interface_reqrem);
+                                                &interface_reqrem)?;
                                         }
-                                    } else {panic!("Could not find enum name")},
+                                    } else {return Err("Could not find enum name".to_owned())},
                                 _ => ()
                             },
 
@@ -249,7 +359,7 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                 VkType::Union{variants: ref mut members, ..} => {
                                     if let Some(member) = members.last_mut() {
                                         match tag {
-                                            "member" => process_type(chars, &mut member.field_type),
+                                            "member" => process_type(chars, &mut member.field_type)?,
                                             "type"   => member.field_type.set_type(registry.append_str(chars)),
                                             "name"   =>
                                                 // This exists as an `if let` block and isn't just `member.set_name(registry.append_str(chars))`    |
@@ -260,10 +370,10 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                                 // `size` is just the size of the the array. `name_len` is how long the name (without [\d+]) is.    |
                                                 // For example, if the contents of <name> are "srcOffsets[2]", `name_len` is equal to 10 because    |
                                                 // "srcOffsets" is 10 characters long.                                                              |
-                                                if let Some((size, name_len)) = parse_array_index(chars) {
+                                                if let Some((size, name_len)) = parse_array_index(chars)? {
                                                     member.field_type.make_array(size);
-                                                    member.set_name(registry.append_str(&chars[..name_len]));
-                                                } else {member.set_name(registry.append_str(chars))},
+                                                    member.set_name(registry.append_str(&chars[..name_len]))?;
+                                                } else {member.set_name(registry.append_str(chars))?},
                                             // Some arrays have their length defined as a constant value. This adds the name of that constant   |
                                             // to the type buffer.
                                             "enum"   => member.field_type.set_array_const(registry.append_str(chars)),
@@ -281,10 +391,10 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                             },
                                         "name" => *name = registry.append_str(chars),
                                         "comment" => (),
-                                        _ => panic!("Unexpected tag")
+                                        _ => return Err("Unexpected tag".to_owned())
                                     },
                                 VkType::Handle{ref mut name,
-                                               ref mut dispatchable} =>
+                                               ref mut dispatchable, ..} =>
                                     match tag {
                                         "type" =>
                                             match chars {
@@ -293,7 +403,7 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                                 "VK_DEFINE_NON_DISPATCHABLE_HANDLE" => *dispatchable = false,
                                                 "("                                  |
                                                 ")"                                 => (),
-                                                _                                   => panic!("Unexpected handle")
+                                                _                                   => return Err("Unexpected handle".to_owned())
                                             },
                                         "name" => *name = registry.append_str(chars),
                                         _ => ()
@@ -303,7 +413,7 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                         "name" => *name = registry.append_str(chars),
                                         "comment" |
                                         "type" => (),
-                                        _      => panic!("Unexpected define tag")
+                                        _      => return Err("Unexpected define tag".to_owned())
                                     },
                                 VkType::FuncPointer{ref mut name, ref mut ret, ref mut params} =>
                                     match tag {
@@ -333,7 +443,8 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                                         if 0 < ptr_count {
                                                             p.make_ptr(ptr_count)
                                                         } else if (0, 0) != indices {
-                                                            p.make_array(parse_array_index(&chars[indices.0..indices.1]).unwrap().0)
+                                                            p.make_array(parse_array_index(&chars[indices.0..indices.1])?
+                                                                .ok_or_else(|| format!("Invalid array size {}", &chars[indices.0..indices.1]))?.0)
                                                         }
                                                     }
 
@@ -367,7 +478,11 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                                 }
                                             },
                                         "comment" => (),
-                                        _      => panic!("Unexpected define tag")
+                                        _      => return Err("Unexpected define tag".to_owned())
+                                    },
+                                VkType::Unhandled if "name" == tag =>
+                                    if let Some(reason) = unhandled_reason.take() {
+                                        registry.diagnose_with(|| Diagnostic::UnhandledType{ name: chars.to_owned(), reason });
                                     },
                                 _ => ()
                             }
@@ -386,14 +501,14 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                 // is going to be a <proto>. In the case that it isn't some shit has gone down and it should        |
                                 // probably be reported.                                                                            |
                                 if let Some(last_param) = command_buffer.params.last_mut() {
-                                    process_type(chars, &mut last_param.typ);
+                                    process_type(chars, &mut last_param.typ)?;
                                     match tag1 {
-                                        "proto" => panic!("Unexpected proto tag"),
+                                        "proto" => return Err("Unexpected proto tag".to_owned()),
                                         "param" =>
                                             match tag {
                                                 "type" => last_param.typ.set_type(registry.append_str(chars)),
                                                 "name" => last_param.name = registry.append_str(chars),
-                                                _      => panic!("Unexpected tag")
+                                                _      => return Err("Unexpected tag".to_owned())
                                             },
                                         _ => ()
                                     }
@@ -404,15 +519,15 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
                                                 command_buffer.ret.make_void();
                                             } else {command_buffer.ret.set_type(registry.append_str(chars))},
                                         "name" => command_buffer.name = registry.append_str(chars),
-                                        _      => panic!("Unexpected tag")
+                                        _      => return Err("Unexpected tag".to_owned())
                                     }
-                                } else {panic!("Some shit went down in a function tag, and you should probably go and report it.")}
+                                } else {return Err("Some shit went down in a function tag, and you should probably go and report it.".to_owned())}
                             }
                         Characters{..} => ()
                     }
                 }
 
-                pop_element_stack(&mut vk_elements);
+                pop_element_stack(&mut vk_elements)?;
                 popped_to = vk_elements.len();
             }
 
@@ -431,6 +546,10 @@ pub fn crawl<R: Read>(xml_events: Events<R>, registry: &mut VkRegistry) {
     registry.push_command(command_buffer).ok();
     registry.push_feature(feature_buffer).ok();
     registry.push_extn(extn_buffer).ok();
+    registry.resolve_variant_aliases();
+    registry.resolve_bitmask_widths();
+    registry.resolve_command_aliases();
+    Ok(())
 }
 
 fn to_number(source: &str) -> Result<isize, ParseIntError> {
@@ -441,18 +560,54 @@ fn to_number(source: &str) -> Result<isize, ParseIntError> {
     }
 }
 
+/// Parse the `bitpos` attribute of a flag, which has to be one of the bits of a 64-bit bitmask.
+fn parse_bitpos(bitpos: &str) -> Result<u32, String> {
+    match to_number(bitpos) {
+        Ok(b) if (0..64).contains(&b) => Ok(b as u32),
+        _                             => Err(format!("Invalid enum bitpos {}", bitpos))
+    }
+}
+
+/// Report the unhandled type described by `attrs` as skipped for `reason`. If the name of the type
+/// isn't an attribute, `reason` is returned so that it can be reported once the `<name>` tag is.
+fn diagnose_unhandled(registry: &VkRegistry, attrs: &Vec<OwnedAttribute>, reason: &'static str) -> Option<&'static str> {
+    match find_attribute(attrs, "name") {
+        Some(name) => {
+            registry.diagnose_with(|| Diagnostic::UnhandledType{ name: name.to_owned(), reason });
+            None
+        }
+        None => Some(reason)
+    }
+}
+
+/// Whether or not the element `tag` with the attributes `attrs` is only for APIs other than `api`.
+/// Extensions list the APIs they're for in `supported`, and everything else lists them in `api`.
+/// Disabled extensions aren't for any API, but are crawled anyways.
+fn for_other_api(tag: &str, attrs: &Vec<OwnedAttribute>, api: &str) -> bool {
+    let apis = match (tag, find_attribute(attrs, "supported")) {
+        ("extension", Some("disabled")) => return false,
+        ("extension", supported)        => supported,
+        _                               => find_attribute(attrs, "api")
+    };
+    match apis {
+        Some(apis) => !apis.split(',').any(|a| api == a),
+        None       => false
+    }
+}
+
 fn find_attribute<'v>(source: &'v Vec<OwnedAttribute>, query: &str) -> Option<&'v str> {
     source.into_iter().skip_while(|attr| &attr.name.local_name != query).next().map(|res| &*res.value)
 }
 
 /// Takes a mutable reference to a XmlElement stack, popping the stack of Character elements until
 /// it pops a Tag
-fn pop_element_stack(vk_elements: &mut Vec<XmlElement>) {
+fn pop_element_stack(vk_elements: &mut Vec<XmlElement>) -> Result<(), String> {
     if let Some(el) = vk_elements.pop() {
         if let XmlElement::Characters{..} = el {
-            pop_element_stack(vk_elements)
+            pop_element_stack(vk_elements)?
         }
-    } else {panic!("Invalid xml; Unexpected closing tag")}
+        Ok(())
+    } else {Err("Invalid xml; Unexpected closing tag".to_owned())}
 }
 
 /// Takes a stack of XmlElements and returns a tuple containing the tag names of the last two tags.
@@ -477,8 +632,9 @@ fn get_tags(vk_elements: &Vec<XmlElement>) -> (*const str, Option<*const str>) {
 /// Takes a string slice and extracts x from [x], as long as [x] is at the end.
 ///
 /// Returns (x, length of `chars` without [x]) if "[x]" is detected.
-/// Returns (0, 0) if "[" is detected at end instead of "]"
-fn parse_array_index(chars: &str) -> Option<(usize, usize)> {
+/// Returns (0, 0) if "[" is detected at end instead of "]", and an error if "]" is detected without
+/// a matching "[".
+fn parse_array_index(chars: &str) -> Result<Option<(usize, usize)>, String> {
     let mut chariter = chars.chars().rev();
     match chariter.next().unwrap() {
         ']' => {
@@ -494,25 +650,36 @@ fn parse_array_index(chars: &str) -> Option<(usize, usize)> {
                                            c.is_digit(10) | c.is_whitespace()}
                                            ).next() {
                 Some('[') => (),
-                Some(c)   => panic!(format!("Expected '['; found '{}'", c)),
-                None      => panic!("Expected '['; found nothing")
+                Some(c)   => return Err(format!("Expected '['; found '{}'", c)),
+                None      => return Err("Expected '['; found nothing".to_owned())
             }
-            Some((size, name_len))
+            Ok(Some((size, name_len)))
         }
-        '[' => Some((0, 0)),
-        _   => None
+        '[' => Ok(Some((0, 0))),
+        _   => Ok(None)
     }
 }
 
-fn process_type(chars: &str, field: &mut VkElType) {
+/// Get the name of the platform guarded by a `VK_USE_PLATFORM_*` macro, like `win32` for
+/// `VK_USE_PLATFORM_WIN32_KHR`.
+fn platform_from_protect(protect: &str) -> Option<String> {
+    if !protect.starts_with("VK_USE_PLATFORM_") {
+        return None;
+    }
+    let platform = &protect["VK_USE_PLATFORM_".len()..];
+    let platform = platform.rfind('_').map_or(platform, |i| &platform[..i]);
+    Some(platform.to_lowercase())
+}
+
+fn process_type(chars: &str, field: &mut VkElType) -> Result<(), String> {
     match chars {
         "const" => field.make_const(),
         "*"     => field.make_ptr(1),
         _
             if &chars[0..1] == "[" =>
-            match parse_array_index(chars) {
+            match parse_array_index(chars)? {
                 Some((size, _)) => {field.make_array(size);}
-                None => panic!(format!("Unexpected characters after name: {}", chars))
+                None => return Err(format!("Unexpected characters after name: {}", chars))
             },
         _       => {
             let ptr_count = chars.chars().fold(0, |acc, x| if x == '*' {acc + 1} else {acc});
@@ -521,6 +688,7 @@ fn process_type(chars: &str, field: &mut VkElType) {
             }
         }
     }
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -563,5 +731,9 @@ enum VkBlock {
     None
 }
 
+/// Top-level blocks in newer registries that nothing is generated from. Their children would
+/// otherwise be crawled as if they were part of whatever block came before them.
+const SKIPPED_BLOCKS: &[&str] = &["formats", "spirvextensions", "spirvcapabilities", "sync"];
+
 const BASE_VALUE: isize = 1000000000;
 const RANGE_SIZE: isize = 1000;
@@ -1,11 +1,23 @@
 mod crawler;
+mod arena;
+mod cache;
+
+pub use self::arena::StringArena;
 
 use to_option;
-use generator::GenRegistry;
+use generator::{GenRegistry, GenStats};
 use xml::{EventReader, ParserConfig};
+use xml::reader::XmlEvent;
 
 use std::{fmt, mem};
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const XINCLUDE_NAMESPACE: &str = "http://www.w3.org/2001/XInclude";
 
 #[inline]
 fn null_str() -> *const str {
@@ -42,6 +54,11 @@ fn null_str() -> *const str {
 /// loaded. In any case, `load_with()` can be called again in order to attempt to re-load any
 /// unloaded functions.
 ///
+/// If the bindings include `VkInstance`, there's also `vk::load_with_loader(get_instance_proc_addr,
+/// instance)`, which loads everything through `vkGetInstanceProcAddr`. The global commands, like
+/// `vkCreateInstance`, are always loaded with a null instance, so it can be called with a null
+/// instance first and with the created instance afterwards.
+///
 /// ```
 /// mod vk {
 ///     // include!{concat!(env!("OUT_DIR"), "vk.rs")}
@@ -85,31 +102,576 @@ fn null_str() -> *const str {
 ///
 /// [`GenConfig`]: ./struct.GenConfig.html
 pub struct VkRegistry<'a> {
-    string_buffer: String,
+    string_buffer: StringArena,
     types: HashMap<&'a str, VkType>,
     core_consts: Vec<&'a str>,
     commands: HashMap<&'a str, VkCommand>,
     features: HashMap<VkVersion, VkFeature>,
-    extns: HashMap<&'a str, VkExtn>
+    extns: HashMap<&'a str, VkExtn>,
+    /// The API that the crawler kept the definitions of, like `vulkan` or `vulkansc`.
+    api: String,
+    crawl_time: Duration,
+    // `FnMut` sinks are wrapped in a `RefCell` inside the boxed closure rather than outside of it,
+    // which keeps the registry covariant over `'a`.
+    diagnostics: Option<Box<dyn Fn(Diagnostic) + 'a>>
 }
 
 impl<'a> VkRegistry<'a> {
     /// Create a new registry based off of the supplied xml file. Said xml should be sourced from the
-    /// [`vk_api`] crate, and can be based off of any version of the API.
+    /// [`vk_api`] crate, and can be based off of any version of the API. Panics if the xml isn't a
+    /// valid registry; [`from_file()`] returns an error instead.
     ///
+    /// [`from_file()`]: #method.from_file
     /// [`vk_api`]: ../../vk_api/index.html
     pub fn new(vk_xml: &[u8]) -> VkRegistry<'a> {
-        let mut registry = VkRegistry {
-            string_buffer: String::with_capacity(vk_xml.len()),
+        VkRegistry::with_capacity(vk_xml, 0)
+    }
+
+    /// Create a new registry, reserving `extra_capacity` bytes of headroom in the internal string
+    /// buffer on top of the default heuristic (the length of the xml file). The generators size
+    /// their own buffers of derived identifiers off of this capacity too. The buffers grow as needed
+    /// either way, so this is only a hint to avoid extra allocations.
+    pub fn with_capacity(vk_xml: &[u8], extra_capacity: usize) -> VkRegistry<'a> {
+        VkRegistry::crawl_xml(vk_xml, extra_capacity, "vulkan", None)
+    }
+
+    /// Create a new registry for the API named `api`, like `vulkansc` for Vulkan SC. Types,
+    /// commands, enums, members, parameters, features and extensions whose `api` attribute (or
+    /// `supported` attribute, for extensions) lists only other APIs are left out, as if they weren't
+    /// in the xml at all. Every other constructor targets `vulkan`.
+    ///
+    /// ```
+    /// # extern crate vk_api;
+    /// # extern crate vk_generator;
+    /// # use vk_generator::VkRegistry;
+    /// # fn main() {
+    /// let registry = VkRegistry::new_for_api(vk_api::VK_XML, "vulkansc");
+    /// assert_eq!(registry.api(), "vulkansc");
+    /// # }
+    /// ```
+    pub fn new_for_api(vk_xml: &[u8], api: &str) -> VkRegistry<'a> {
+        VkRegistry::crawl_xml(vk_xml, 0, api, None)
+    }
+
+    /// Create a new registry like [`new()`], passing a [`Diagnostic`] to `diagnostics` for each
+    /// element the crawler skips over and for each stage of crawling and generation that finishes.
+    /// This is mostly useful for finding out why a custom `vk.xml` doesn't produce the expected
+    /// bindings.
+    ///
+    /// ```
+    /// # extern crate vk_api;
+    /// # extern crate vk_generator;
+    /// # use vk_generator::{Diagnostic, VkRegistry};
+    /// # fn main() {
+    /// let mut skipped = Vec::new();
+    /// VkRegistry::new_with_diagnostics(vk_api::VK_XML, |d| if let Diagnostic::UnhandledType{name, ..} = d {
+    ///     skipped.push(name);
+    /// });
+    /// assert!(skipped.iter().any(|s| s == "VkAttachmentLoadOp"));
+    /// # }
+    /// ```
+    ///
+    /// [`new()`]: #method.new
+    /// [`Diagnostic`]: ./enum.Diagnostic.html
+    pub fn new_with_diagnostics<F: FnMut(Diagnostic) + 'a>(vk_xml: &[u8], diagnostics: F) -> VkRegistry<'a> {
+        let diagnostics = RefCell::new(diagnostics);
+        VkRegistry::crawl_xml(vk_xml, 0, "vulkan", Some(Box::new(move |d| diagnostics.borrow_mut()(d))))
+    }
+
+    fn crawl_xml(vk_xml: &[u8], extra_capacity: usize, api: &str, diagnostics: Option<Box<dyn Fn(Diagnostic) + 'a>>) -> VkRegistry<'a> {
+        let start = Instant::now();
+        let mut registry = VkRegistry::empty(vk_xml.len() + extra_capacity);
+        registry.api = api.to_owned();
+        registry.diagnostics = diagnostics;
+        let xml_reader = EventReader::new_with_config(vk_xml, ParserConfig::new().trim_whitespace(true));
+        if let Err(message) = crawler::crawl(xml_reader.into_iter(), &mut registry) {
+            panic!("Invalid registry: {}", message);
+        }
+        registry.crawl_time = start.elapsed();
+        registry.diagnose_with(|| Diagnostic::Crawled {
+            types: registry.types.len(),
+            commands: registry.commands.len(),
+            time: registry.crawl_time
+        });
+        registry
+    }
+
+    /// Create a new registry from the xml file at `path`, which makes it possible to generate
+    /// bindings for a newer registry than the one bundled with [`vk_api`]. Any XInclude references
+    /// in the file are resolved relative to the directory of the file containing them, and the
+    /// combined document is loaded as though it were a single file.
+    ///
+    /// Returns an error if a file can't be read, an include can't be resolved, the xml is malformed,
+    /// or the document isn't laid out like a Vulkan registry.
+    ///
+    /// [`vk_api`]: ../../vk_api/index.html
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<VkRegistry<'a>> {
+        let start = Instant::now();
+        let path = path.as_ref();
+        let mut events = Vec::with_capacity(2usize.pow(16));
+        let len = read_with_includes(path, &mut events, 0)?;
+        VkRegistry::crawl_events(events, len, start, &path.display())
+    }
+
+    /// Parse `vk_xml` into a binary cache that [`from_cache()`] can create a registry from without
+    /// running the xml parser. Parsing is nearly all of the time `new()` takes, so a build script that
+    /// writes the cache out once can load the registry much faster on later runs. The cache holds the
+    /// parsed document rather than the registry, so it doesn't depend on where anything ends up in
+    /// memory.
+    ///
+    /// ```
+    /// # extern crate vk_api;
+    /// # extern crate vk_generator;
+    /// # use vk_generator::VkRegistry;
+    /// # fn main() {
+    /// let cache = VkRegistry::write_cache(vk_api::VK_XML);
+    /// // The cache would normally be written to `OUT_DIR` and read back on the next build.
+    /// let registry = VkRegistry::from_cache(&cache).unwrap();
+    /// assert_eq!(registry.command_names(), VkRegistry::new(vk_api::VK_XML).command_names());
+    /// # }
+    /// ```
+    ///
+    /// A registry is never modified by the generators, so when generating more than one set of
+    /// bindings in a single run it's cheaper still to create the registry once and call each of the
+    /// `gen_*` functions on it.
+    ///
+    /// # Panics
+    /// Panics if `vk_xml` isn't valid xml.
+    ///
+    /// [`from_cache()`]: #method.from_cache
+    pub fn write_cache(vk_xml: &[u8]) -> Vec<u8> {
+        let xml_reader = EventReader::new_with_config(vk_xml, ParserConfig::new().trim_whitespace(true));
+        cache::encode(xml_reader.into_iter().map(|e| e.unwrap()), vk_xml.len())
+    }
+
+    /// Create a registry from a cache written by [`write_cache()`], which produces the same registry
+    /// as creating it from the xml the cache was written from.
+    ///
+    /// Returns an error if the cache is truncated or corrupt, was written by a version of the
+    /// generator with a different cache format, or doesn't hold a Vulkan registry.
+    ///
+    /// Like [`new()`], the registry holds the definitions for `vulkan` and has no diagnostics sink.
+    /// Use [`new_for_api()`] or [`new_with_diagnostics()`] with the xml when either is needed.
+    ///
+    /// [`write_cache()`]: #method.write_cache
+    /// [`new()`]: #method.new
+    /// [`new_for_api()`]: #method.new_for_api
+    /// [`new_with_diagnostics()`]: #method.new_with_diagnostics
+    pub fn from_cache(cache: &[u8]) -> io::Result<VkRegistry<'a>> {
+        let start = Instant::now();
+        let (events, len) = cache::decode(cache)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, format!("invalid registry cache: {}", message)))?;
+        VkRegistry::crawl_events(events, len, start, &"the cache")
+    }
+
+    /// Crawl `events` into a new registry, with errors naming `source`.
+    fn crawl_events(events: Vec<XmlEvent>, len: usize, start: Instant, source: &dyn fmt::Display) -> io::Result<VkRegistry<'a>> {
+        let mut registry = VkRegistry::empty(len);
+        crawler::crawl(events.into_iter().map(Ok), &mut registry)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, format!("{} is not a valid registry: {}", source, message)))?;
+        registry.crawl_time = start.elapsed();
+        Ok(registry)
+    }
+
+    fn empty(buffer_capacity: usize) -> VkRegistry<'a> {
+        VkRegistry {
+            string_buffer: StringArena::with_capacity(buffer_capacity),
             types: HashMap::with_capacity(512),
             core_consts: Vec::with_capacity(16),
             commands: HashMap::with_capacity(256),
             features: HashMap::with_capacity(8),
-            extns: HashMap::with_capacity(64)
+            extns: HashMap::with_capacity(64),
+            api: "vulkan".to_owned(),
+            crawl_time: Duration::from_secs(0),
+            diagnostics: None
+        }
+    }
+
+    /// The API that the registry holds the definitions for, like `vulkan`.
+    pub fn api(&self) -> &str {
+        &self.api
+    }
+
+    /// Pass the diagnostic built by `diagnostic` to the registry's diagnostics sink, if it has one.
+    /// The diagnostic isn't built otherwise.
+    fn diagnose_with<F: FnOnce() -> Diagnostic>(&self, diagnostic: F) {
+        if let Some(ref sink) = self.diagnostics {
+            sink(diagnostic());
+        }
+    }
+
+    /// How long it took to read and crawl the xml this registry was created from.
+    pub fn crawl_time(&self) -> Duration {
+        self.crawl_time
+    }
+
+    /// Follow the typedef or alias `name` a single hop, returning the name of the type it aliases.
+    /// Anything that isn't a typedef or alias resolves to itself.
+    ///
+    /// This is what should be used when emitting code, so that `VkBufferUsageFlags` is bound as
+    /// `VkFlags` and not collapsed straight down to `uint32_t`, matching the C headers.
+    pub fn resolve_name<'b>(&'b self, name: &'b str) -> &'b str {
+        match self.types.get(name) {
+            Some(&VkType::TypeDef{typ, ..})     => to_option(typ).unwrap_or(name),
+            Some(&VkType::Alias{alias_of, ..}) => unsafe{ &*alias_of },
+            _                                  => name
+        }
+    }
+
+    /// Follow the typedef chain starting at `name` all the way down to the concrete type at the end
+    /// of it. For example, `VkBufferUsageFlags` resolves to `VkFlags`, which in turn resolves to
+    /// `uint32_t`.
+    ///
+    /// This is what should be used when computing the size or layout of a type.
+    pub fn resolve_concrete<'b>(&'b self, mut name: &'b str) -> &'b str {
+        // Bounded by the number of types so that a malformed, cyclic registry can't hang us.
+        for _ in 0..self.types.len() {
+            let next = self.resolve_name(name);
+            if next == name {
+                break;
+            }
+            name = next;
+        }
+        name
+    }
+
+    /// Look up the API version of the feature named `name`, like `VK_VERSION_1_0`. Returns `None` if
+    /// the registry doesn't contain a feature by that name.
+    pub fn feature_version(&self, name: &str) -> Option<VkVersion> {
+        self.features.values()
+            .find(|f| name == unsafe{ &*f.name })
+            .map(|f| f.version)
+    }
+
+    /// Get the names of every type in the registry, grouped by what kind of type they are. Each
+    /// group is sorted by name. Constants, defines, and types the crawler doesn't handle aren't
+    /// included.
+    pub fn types_by_category<'b>(&'b self) -> TypesByCategory<'b> {
+        let mut categories = TypesByCategory::default();
+        for (name, t) in &self.types {
+            let group = match *t {
+                VkType::Struct{..}      => &mut categories.structs,
+                VkType::Union{..}       => &mut categories.unions,
+                VkType::Enum{..}        => &mut categories.enums,
+                VkType::Bitmask{..}     => &mut categories.bitmasks,
+                VkType::Handle{..}      => &mut categories.handles,
+                VkType::TypeDef{..}     => &mut categories.typedefs,
+                VkType::FuncPointer{..} => &mut categories.funcpointers,
+                VkType::ExternType{..}  => &mut categories.externs,
+                VkType::Alias{..}       => &mut categories.aliases,
+                VkType::ApiConst{..}    |
+                VkType::Define{..}      |
+                VkType::Unhandled       => continue
+            };
+            group.push(*name);
+        }
+
+        for group in &mut [&mut categories.structs, &mut categories.unions, &mut categories.enums,
+                           &mut categories.bitmasks, &mut categories.handles, &mut categories.typedefs,
+                           &mut categories.funcpointers, &mut categories.externs, &mut categories.aliases] {
+            group.sort();
+        }
+        categories
+    }
+
+    /// Look up the type named `name`, returning a view of its definition that can be inspected
+    /// without touching any raw pointers. Returns `None` if the registry has no type by that name.
+    pub fn lookup_type<'b>(&'b self, name: &str) -> Option<VkTypeView<'b>> {
+        use self::VkType::*;
+
+        let (name, t) = self.types.get_key_value(name)?;
+        let mut view = VkTypeView {
+            name,
+            kind: VkTypeKind::Struct,
+            fields: Vec::new(),
+            variants: Vec::new(),
+            aliases: None
         };
-        let xml_reader = EventReader::new_with_config(vk_xml, ParserConfig::new().trim_whitespace(true));
-        crawler::crawl(xml_reader.into_iter(), &mut registry);
-        registry
+        match *t {
+            Struct{ref fields, ..}       => view.fields = fields.iter().map(|f| self.field_view(f)).collect(),
+            Union{ref variants, ..}      => {
+                view.kind = VkTypeKind::Union;
+                view.fields = variants.iter().map(|f| self.field_view(f)).collect();
+            }
+            Enum{ref variants, ..}       => {
+                view.kind = VkTypeKind::Enum;
+                view.variants = variants.iter().map(|v| unsafe{ &*v.name() }).collect();
+            }
+            Bitmask{ref variants, ..}    => {
+                view.kind = VkTypeKind::Bitmask;
+                view.variants = variants.iter().map(|v| unsafe{ &*v.name() }).collect();
+            }
+            Handle{..}                   => view.kind = VkTypeKind::Handle,
+            TypeDef{typ, ..}             => {
+                view.kind = VkTypeKind::TypeDef;
+                view.aliases = to_option(typ);
+            }
+            ApiConst{..}                 => view.kind = VkTypeKind::ApiConst,
+            Define{..}                   => view.kind = VkTypeKind::Define,
+            FuncPointer{..}              => view.kind = VkTypeKind::FuncPointer,
+            ExternType{..}               => view.kind = VkTypeKind::ExternType,
+            Alias{alias_of, ..}          => {
+                view.kind = VkTypeKind::Alias;
+                view.aliases = to_option(alias_of);
+            }
+            Unhandled                    => return None
+        }
+        Some(view)
+    }
+
+    /// Get the names of every extension in the registry, like `VK_KHR_surface`, sorted by name.
+    pub fn extension_names<'b>(&'b self) -> Vec<&'b str> {
+        let mut names: Vec<&'b str> = self.extns.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Get the names of every command in the registry, like `vkCreateInstance`, sorted by name.
+    pub fn command_names<'b>(&'b self) -> Vec<&'b str> {
+        let mut names: Vec<&'b str> = self.commands.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Get the names of the commands that the extension named `extension` requires, in the order
+    /// the registry lists them. Returns `None` if the registry has no extension by that name.
+    ///
+    /// ```
+    /// # extern crate vk_api;
+    /// # extern crate vk_generator;
+    /// # use vk_generator::VkRegistry;
+    /// # fn main() {
+    /// let registry = VkRegistry::new(vk_api::VK_XML);
+    /// let commands = registry.commands_in_extension("VK_KHR_surface").unwrap();
+    ///
+    /// assert!(commands.contains(&"vkDestroySurfaceKHR"));
+    /// assert!(commands.contains(&"vkGetPhysicalDeviceSurfaceSupportKHR"));
+    /// # }
+    /// ```
+    pub fn commands_in_extension<'b>(&'b self, extension: &str) -> Option<Vec<&'b str>> {
+        self.extns.get(extension).map(|e| e.require.iter()
+            .filter_map(|i| match *i {
+                VkInterface::Command{name, ..} => Some(unsafe{ &*name }),
+                _                              => None
+            })
+            .collect())
+    }
+
+    /// Get the extensions that `extensions` depend on, directly or through each other, which aren't in
+    /// `extensions` already. Where a dependency can be met by any one of several extensions or core
+    /// versions and `version` and `extensions` don't already meet it, the first alternative is used.
+    /// Names that aren't extensions in the registry are left out.
+    ///
+    /// ```
+    /// # extern crate vk_api;
+    /// # extern crate vk_generator;
+    /// # use vk_generator::{VkRegistry, VkVersion};
+    /// # fn main() {
+    /// let registry = VkRegistry::new(vk_api::VK_XML);
+    /// assert_eq!(registry.extension_dependencies(VkVersion(1, 0), &["VK_KHR_swapchain"]), ["VK_KHR_surface"]);
+    /// assert!(registry.extension_dependencies(VkVersion(1, 0), &["VK_KHR_swapchain", "VK_KHR_surface"]).is_empty());
+    /// # }
+    /// ```
+    pub fn extension_dependencies<'b>(&'b self, version: VkVersion, extensions: &[&str]) -> Vec<&'b str> {
+        let mut added: Vec<&'b str> = Vec::new();
+        let mut queue: Vec<&'b VkExtn> = extensions.iter().filter_map(|e| self.extns.get(*e)).collect();
+
+        while let Some(ex) = queue.pop() {
+            // Expressions that can't be parsed are treated as not depending on anything.
+            let depends = match ex.depends().and_then(Depends::parse) {
+                Some(depends) => depends,
+                None          => continue
+            };
+            let is_met = |name: &str| match self.feature_version(name) {
+                Some(v) => (v.0, v.1) <= (version.0, version.1),
+                None    => extensions.contains(&name) || added.contains(&name)
+            };
+
+            let mut needed = Vec::new();
+            depends.needed(&is_met, &|name| self.extns.contains_key(name), &mut needed);
+            for name in needed {
+                let (&name, ex) = self.extns.get_key_value(name).unwrap();
+                if !added.contains(&name) {
+                    added.push(name);
+                    queue.push(ex);
+                }
+            }
+        }
+        added
+    }
+
+    fn field_view<'b>(&'b self, member: &'b VkMember) -> VkFieldView<'b> {
+        use self::VkElType::*;
+
+        let const_len = |c: *const str| match self.types.get(unsafe{ &*c }) {
+            Some(&VkType::ApiConst{value, ..}) => unsafe{ &*value }.parse().ok(),
+            _                                  => None
+        };
+        let (is_const, pointer_depth, array_len) = match member.field_type {
+            Var(_)                => (false, 0, None),
+            Const(_)              => (true, 0, None),
+            ConstPtr(_, depth)    => (true, depth, None),
+            MutPtr(_, depth)      => (false, depth, None),
+            ConstArray(_, len)    => (true, 0, Some(len)),
+            MutArray(_, len)      => (false, 0, Some(len)),
+            ConstArrayEnum(_, c)  => (true, 0, const_len(c)),
+            MutArrayEnum(_, c)    => (false, 0, const_len(c)),
+            Void                  |
+            Unknown               => (false, 0, None)
+        };
+
+        VkFieldView {
+            name: unsafe{ &*member.field_name },
+            type_name: member.field_type.type_ptr().and_then(to_option).unwrap_or("void"),
+            is_const,
+            pointer_depth,
+            array_len,
+            optional: member.optional
+        }
+    }
+
+    /// Give the variants that alias another variant the value of the variant they alias. Aliases can
+    /// refer to variants added by extensions later on in the registry, so this is done once the whole
+    /// registry has been loaded.
+    fn resolve_variant_aliases(&mut self) {
+        let mut variants = HashMap::new();
+        {
+            let type_variants = self.types.values()
+                .filter_map(|t| match *t {
+                    VkType::Enum{ref variants, ..}     |
+                    VkType::Bitmask{ref variants, ..} => Some(variants.iter()),
+                    _                                 => None
+                })
+                .flatten();
+            let extn_variants = self.extns.values()
+                .flat_map(|e| e.require.iter().chain(e.remove.iter()))
+                .filter_map(|i| match *i {
+                    VkInterface::ExtnEnum{ref variant, ..} => Some(variant),
+                    _                                      => None
+                });
+
+            for v in type_variants.chain(extn_variants) {
+                variants.insert(unsafe{ &*v.name() }, v.clone());
+            }
+        }
+
+        // Aliases can be of other aliases, so follow the chain down to the variant that has a value.
+        // The variant at the end of the chain can be missing if it's only defined for another API,
+        // in which case its name is returned instead.
+        let find_canonical = |v: &VkVariant| {
+            let mut canonical = v.clone();
+            for _ in 0..variants.len() {
+                match canonical.alias_of() {
+                    Some(alias_of) => canonical = variants.get(alias_of).ok_or_else(|| alias_of.to_owned())?.clone(),
+                    None           => break
+                }
+            }
+            Ok(canonical)
+        };
+
+        let mut dangling = Vec::new();
+        {
+            let type_variants = self.types.values_mut()
+                .filter_map(|t| match *t {
+                    VkType::Enum{ref mut variants, ..}     |
+                    VkType::Bitmask{ref mut variants, ..} => Some(variants.iter_mut()),
+                    _                                     => None
+                })
+                .flatten();
+            let extn_variants = self.extns.values_mut()
+                .flat_map(|e| e.require.iter_mut().chain(e.remove.iter_mut()))
+                .filter_map(|i| match *i {
+                    VkInterface::ExtnEnum{ref mut variant, ..} => Some(variant),
+                    _                                          => None
+                });
+
+            for v in type_variants.chain(extn_variants).filter(|v| v.alias_of().is_some()) {
+                match find_canonical(v) {
+                    Ok(canonical) => v.set_value_of(&canonical),
+                    Err(alias_of) => dangling.push((unsafe{ &*v.name() }, alias_of))
+                }
+            }
+        }
+        if dangling.is_empty() {
+            return;
+        }
+
+        let is_dangling = |v: &VkVariant| dangling.iter().any(|&(name, _)| unsafe{ &*v.name() } == name);
+        for t in self.types.values_mut() {
+            match *t {
+                VkType::Enum{ref mut variants, ..}     |
+                VkType::Bitmask{ref mut variants, ..} => variants.retain(|v| !is_dangling(v)),
+                _                                     => ()
+            }
+        }
+        for e in self.extns.values_mut() {
+            for interfaces in [&mut e.require, &mut e.remove] {
+                interfaces.retain(|i| match *i {
+                    VkInterface::ExtnEnum{ref variant, ..} => !is_dangling(variant),
+                    _                                      => true
+                });
+            }
+        }
+        for (name, alias_of) in dangling {
+            self.diagnose_with(|| Diagnostic::DanglingAlias {
+                name: name.to_owned(),
+                alias_of
+            });
+        }
+    }
+
+    /// Widen the bitmasks whose flags typedef is `VkFlags64` to 64 bits, for registries that don't
+    /// give their width with a `bitwidth` attribute.
+    fn resolve_bitmask_widths(&mut self) {
+        let wide: Vec<&'a str> = self.types.values()
+            .filter_map(|t| match *t {
+                VkType::TypeDef{typ, requires, ..} if Some("VkFlags64") == to_option(typ) => to_option(requires),
+                _                                                                         => None
+            })
+            .collect();
+
+        for bitmask in wide {
+            if let Some(&mut VkType::Bitmask{ref mut bitwidth, ..}) = self.types.get_mut(bitmask) {
+                *bitwidth = 64;
+            }
+        }
+    }
+
+    /// Give the commands that alias another command the signature of the command they alias. They
+    /// keep their own names, so that they get loaded by the name the extension that added them uses.
+    fn resolve_command_aliases(&mut self) {
+        let aliases: Vec<&str> = self.commands.iter()
+            .filter(|&(_, c)| to_option(c.alias_of).is_some())
+            .map(|(name, _)| *name)
+            .collect();
+
+        for name in aliases {
+            let mut canonical = self.commands[name].alias_of;
+            // Bounded by the number of commands so that a malformed, cyclic registry can't hang us.
+            for _ in 0..self.commands.len() {
+                match self.commands.get(unsafe{ &*canonical }) {
+                    Some(c) if to_option(c.alias_of).is_some() => canonical = c.alias_of,
+                    _                                          => break
+                }
+            }
+
+            // The command being aliased can be missing if it's only defined for another API.
+            let canonical = match self.commands.get(unsafe{ &*canonical }) {
+                Some(c) => c.clone(),
+                None    => {
+                    self.commands.remove(name);
+                    self.diagnose_with(|| Diagnostic::DanglingAlias {
+                        name: name.to_owned(),
+                        alias_of: unsafe{ &*canonical }.to_owned()
+                    });
+                    continue;
+                }
+            };
+            let alias = self.commands.get_mut(name).unwrap();
+            alias.ret = canonical.ret;
+            alias.params = canonical.params;
+            alias.success_codes = canonical.success_codes;
+        }
     }
 
     fn push_type(&mut self, vk_type: VkType) -> Result<(), ()> {
@@ -131,9 +693,18 @@ impl<'a> VkRegistry<'a> {
 
     fn push_command(&mut self, vk_command: Option<VkCommand>) -> Result<(), ()> {
         if let Some(mut cmd) = vk_command {
-            while VkElType::Unknown == cmd.params.last().unwrap().typ {
+            let len = cmd.params.len();
+            // Aliases don't have any parameters of their own until `resolve_command_aliases()` runs,
+            // and malformed commands can run out of parameters while they're being popped.
+            while let Some(&VkParam{typ: VkElType::Unknown, ..}) = cmd.params.last() {
                 cmd.params.pop();
             }
+            if len != cmd.params.len() {
+                self.diagnose_with(|| Diagnostic::TruncatedParams {
+                    name: unsafe{ &*cmd.name }.to_owned(),
+                    dropped: len - cmd.params.len()
+                });
+            }
 
             unsafe{ self.commands.insert(&*cmd.name, cmd) };
             Ok(())
@@ -156,21 +727,55 @@ impl<'a> VkRegistry<'a> {
 
     /// Append a given attribute to the internal string buffer and return an unsafe slice into the buffer string
     fn append_str(&mut self, string: &str) -> *const str {
-        use std::{slice, str};
-
-        let prepushcap = self.string_buffer.capacity();
-        // We want to have all of the string in one block of memory in order to save heap allocation time.
-        self.string_buffer.push_str(string);
-
-        if prepushcap != self.string_buffer.capacity() {
-            panic!("Allocation detected in string buffer")
-        }
+        self.string_buffer.append(string)
+    }
+}
 
-        unsafe {
-            let ptr = self.string_buffer.as_ptr().offset((self.string_buffer.len() - string.len()) as isize);
-            str::from_utf8_unchecked(slice::from_raw_parts(ptr, string.len())) as *const str
+/// Parse the xml file at `path` into `events`, splicing in the contents of any files it XIncludes.
+/// Returns the total length of all of the files that were read, which is used to size the registry's
+/// string buffer.
+fn read_with_includes(path: &Path, events: &mut Vec<XmlEvent>, depth: usize) -> io::Result<usize> {
+    // Deep enough for any sane registry, shallow enough to catch a file that includes itself.
+    if depth > 16 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("XIncludes nested too deeply at {}", path.display())));
+    }
+
+    let mut xml = Vec::new();
+    File::open(path).and_then(|mut f| f.read_to_end(&mut xml))
+        .map_err(|e| io::Error::new(e.kind(), format!("Could not read {}: {}", path.display(), e)))?;
+    let mut len = xml.len();
+
+    // How many levels deep we are into an include element. Its children are fallbacks, which are
+    // skipped because the include itself gets resolved.
+    let mut include_depth = 0;
+    for event in EventReader::new_with_config(&xml[..], ParserConfig::new().trim_whitespace(true)) {
+        let event = event.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))?;
+        match event {
+            XmlEvent::StartElement{ref name, ref attributes, ..}
+                if include_depth == 0 &&
+                   "include" == name.local_name &&
+                   Some(XINCLUDE_NAMESPACE) == name.namespace.as_ref().map(|n| &n[..]) =>
+            {
+                include_depth = 1;
+                let href = attributes.iter().find(|a| "href" == a.name.local_name).map(|a| &a.value[..])
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("XInclude without an href in {}", path.display())))?;
+                let include_path = path.parent().unwrap_or(Path::new("")).join(href);
+                len += read_with_includes(&include_path, events, depth + 1)
+                    .map_err(|e| io::Error::new(e.kind(), format!("Could not resolve XInclude \"{}\" in {}: {}", href, path.display(), e)))?;
+            }
+            XmlEvent::StartElement{..} if include_depth > 0 => include_depth += 1,
+            XmlEvent::EndElement{..}   if include_depth > 0 => include_depth -= 1,
+            _ if include_depth > 0 => (),
+
+            // Included files are spliced into the middle of the including document, so only the
+            // outermost file gets to start and end it.
+            XmlEvent::StartDocument{..} |
+            XmlEvent::EndDocument       if depth > 0 => (),
+            event => events.push(event)
         }
     }
+
+    Ok(len)
 }
 
 impl<'a> GenRegistry for VkRegistry<'a> {
@@ -197,6 +802,52 @@ impl<'a> GenRegistry for VkRegistry<'a> {
     fn core_consts(&self) -> &Vec<&str> {
         &self.core_consts
     }
+
+    fn diagnose(&self, diagnostic: Diagnostic) {
+        self.diagnose_with(|| diagnostic)
+    }
+}
+
+/// Something that happened while crawling a registry or generating bindings from it, passed to the
+/// closure given to [`VkRegistry::new_with_diagnostics()`].
+///
+/// [`VkRegistry::new_with_diagnostics()`]: ./struct.VkRegistry.html#method.new_with_diagnostics
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// The `<type>` element `name` was skipped by the crawler, for `reason`.
+    UnhandledType {
+        name: String,
+        reason: &'static str
+    },
+    /// The last `dropped` parameters of the command `name` couldn't be parsed, and were left off.
+    TruncatedParams {
+        name: String,
+        dropped: usize
+    },
+    /// The command or enum variant alias `name` was left out, because `alias_of`, the command or
+    /// variant it aliases, isn't in the registry.
+    DanglingAlias {
+        name: String,
+        alias_of: String
+    },
+    /// The registry finished crawling the xml, having found `types` types and `commands` commands.
+    Crawled {
+        types: usize,
+        commands: usize,
+        time: Duration
+    },
+    /// The types and commands required by the version and extensions being generated were selected.
+    Selected {
+        types: usize,
+        commands: usize
+    },
+    /// The extensions in `extensions` are generated too, because the requested extensions depend on
+    /// them.
+    DependenciesAdded {
+        extensions: Vec<String>
+    },
+    /// A set of bindings finished generating.
+    Generated(GenStats)
 }
 
 /// The type of a Vulkan element (struct members, union variants, function parameters, etc.) and
@@ -274,13 +925,15 @@ impl VkElType {
         }
     }
 
+    /// Add `count` levels of indirection. Pointers that get pointed to again keep their mutability,
+    /// since only the outermost `const` is tracked.
     fn make_ptr(&mut self, count: u8) {
         use self::VkElType::*;
         match *self {
             Var(s)              => *self = MutPtr(s, count),
             Const(s)            => *self = ConstPtr(s, count),
-            MutPtr(_, _)         |
-            ConstPtr(_, _)      => panic!("Attempted to change type from pointer to pointer"),
+            MutPtr(s, depth)    => *self = MutPtr(s, depth + count),
+            ConstPtr(s, depth)  => *self = ConstPtr(s, depth + count),
             ConstArray(_, _)     |
             ConstArrayEnum(_, _) |
             MutArrayEnum(_, _)   |
@@ -383,7 +1036,13 @@ impl fmt::Debug for VkElType {
 pub struct VkMember {
     pub field_type: VkElType,
     pub field_name: *const str,
-    pub optional: bool
+    pub optional: bool,
+    /// The only value the member may hold, from `values`. This is how the registry says which
+    /// `VkStructureType` goes in a struct's `sType`. Optional
+    pub values: *const str,
+    /// What the length of the array the member points to comes from, like `waitSemaphoreCount`, if
+    /// it points to an array. Optional
+    pub len: *const str
 }
 
 impl fmt::Debug for VkMember {
@@ -392,6 +1051,8 @@ impl fmt::Debug for VkMember {
             .field("type", &self.field_type)
             .field("name", &to_option(self.field_name))
             .field("optional", &self.optional)
+            .field("values", &to_option(self.values))
+            .field("len", &to_option(self.len))
             .finish()
     }
 }
@@ -401,15 +1062,18 @@ impl VkMember {
         VkMember {
             field_type: VkElType::Unknown,
             field_name: null_str(),
-            optional: optional
+            optional: optional,
+            values: null_str(),
+            len: null_str()
         }
     }
 
-    fn set_name(&mut self, field_name: *const str) {
+    fn set_name(&mut self, field_name: *const str) -> Result<(), String> {
         if self.field_name != null_str() {
-            panic!("Unexpected \"name\" tag");
+            Err("Unexpected \"name\" tag".to_owned())
         } else {
             self.field_name = field_name;
+            Ok(())
         }
     }
 }
@@ -419,12 +1083,20 @@ impl VkMember {
 pub enum VkVariant {
     Value {
         name: *const str,
-        value: isize
+        value: isize,
+        /// The name of the variant this is an alias of. Optional
+        alias_of: *const str,
+        /// The registry's `comment` on the variant. Optional
+        comment: *const str
     },
 
     Bitpos {
         name: *const str,
-        bitpos: u32
+        bitpos: u32,
+        /// Ditto.
+        alias_of: *const str,
+        /// Ditto.
+        comment: *const str
     }
 }
 
@@ -442,7 +1114,8 @@ impl fmt::Debug for VkVariant {
         match *self {
             Value{value, ..} => fmt_struct.field("value", &value),
             Bitpos{bitpos, ..} => fmt_struct.field("bitpos", &bitpos),
-        }.finish()
+        };
+        fmt_struct.field("alias_of", &self.alias_of()).field("comment", &self.comment()).finish()
     }
 }
 
@@ -450,17 +1123,74 @@ impl VkVariant {
     fn new_value(name: *const str, value: isize) -> Self {
         VkVariant::Value {
             name,
-            value: value
+            value: value,
+            alias_of: null_str(),
+            comment: null_str()
         }
     }
 
     fn new_bitpos(name: *const str, bitpos: u32) -> Self {
         VkVariant::Bitpos {
             name,
-            bitpos: bitpos
+            bitpos: bitpos,
+            alias_of: null_str(),
+            comment: null_str()
+        }
+    }
+
+    /// Create an alias of the variant `alias_of`. The alias doesn't get its value until the registry
+    /// has been fully loaded and `VkRegistry::resolve_variant_aliases()` is run.
+    fn new_alias(name: *const str, alias_of: *const str) -> Self {
+        VkVariant::Value {
+            name,
+            value: 0,
+            alias_of,
+            comment: null_str()
+        }
+    }
+
+    /// Attach the registry's `comment` on the variant, if it has one.
+    fn with_comment(mut self, new_comment: Option<*const str>) -> Self {
+        use self::VkVariant::*;
+        if let Some(new_comment) = new_comment {
+            match self {
+                Value{ref mut comment, ..} |
+                Bitpos{ref mut comment, ..} => *comment = new_comment
+            }
+        }
+        self
+    }
+
+    /// The name of the variant this variant is an alias of, if it is one. For example, a variant added
+    /// as `VK_X_KHR` by an extension that was later promoted to core reports `VK_X`.
+    pub fn alias_of(&self) -> Option<&str> {
+        use self::VkVariant::*;
+        match *self {
+            Value{alias_of, ..} |
+            Bitpos{alias_of, ..} => to_option(alias_of)
         }
     }
 
+    /// The registry's comment on the variant, if it has one.
+    pub fn comment(&self) -> Option<&str> {
+        use self::VkVariant::*;
+        match *self {
+            Value{comment, ..} |
+            Bitpos{comment, ..} => to_option(comment)
+        }
+    }
+
+    /// Give this variant the value of `canonical`, keeping its own name, alias and comment.
+    fn set_value_of(&mut self, canonical: &VkVariant) {
+        use self::VkVariant::*;
+        let (name, alias_of) = (self.name(), self.alias_of().map(|a| a as *const str).unwrap_or(null_str()));
+        let comment = self.comment().map(|c| c as *const str).unwrap_or(null_str());
+        *self = match *canonical {
+            Value{value, ..}   => Value{name, value, alias_of, comment},
+            Bitpos{bitpos, ..} => Bitpos{name, bitpos, alias_of, comment}
+        };
+    }
+
     pub fn name(&self) -> *const str {
         use self::VkVariant::*;
         match *self {
@@ -469,6 +1199,26 @@ impl VkVariant {
         }
     }
 
+    /// The value of the variant. Bitmask variants defined by their bit position (like
+    /// `bitpos="4"`) give the value of that bit, `16` in this case. Bits are counted in a `u64`, so
+    /// bit 63 of a 64-bit bitmask gives `i64::MIN`, which has the same bit pattern.
+    pub fn value(&self) -> i64 {
+        use self::VkVariant::*;
+        match *self {
+            Value{value, ..}   => value as i64,
+            // The crawler only accepts bit positions below 64.
+            Bitpos{bitpos, ..} => (1u64 << bitpos) as i64
+        }
+    }
+
+    /// Whether the variant is defined by its bit position rather than by its value.
+    pub fn is_bitpos(&self) -> bool {
+        match *self {
+            VkVariant::Value{..}  => false,
+            VkVariant::Bitpos{..} => true
+        }
+    }
+
     pub fn set_name(&mut self, new_name: *const str) {
         use self::VkVariant::*;
         match *self {
@@ -478,31 +1228,113 @@ impl VkVariant {
     }
 }
 
+/// The names of the types in a registry, grouped by category. Returned by
+/// [`VkRegistry::types_by_category()`].
+///
+/// [`VkRegistry::types_by_category()`]: ./struct.VkRegistry.html#method.types_by_category
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypesByCategory<'b> {
+    pub structs: Vec<&'b str>,
+    pub unions: Vec<&'b str>,
+    pub enums: Vec<&'b str>,
+    pub bitmasks: Vec<&'b str>,
+    pub handles: Vec<&'b str>,
+    pub typedefs: Vec<&'b str>,
+    pub funcpointers: Vec<&'b str>,
+    pub externs: Vec<&'b str>,
+    pub aliases: Vec<&'b str>
+}
+
+/// What kind of type a [`VkTypeView`] is a view of.
+///
+/// [`VkTypeView`]: ./struct.VkTypeView.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VkTypeKind {
+    Struct,
+    Union,
+    Enum,
+    Bitmask,
+    Handle,
+    TypeDef,
+    ApiConst,
+    Define,
+    FuncPointer,
+    ExternType,
+    Alias
+}
+
+/// A view of a type's definition in a registry. Returned by [`VkRegistry::lookup_type()`].
+///
+/// [`VkRegistry::lookup_type()`]: ./struct.VkRegistry.html#method.lookup_type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VkTypeView<'b> {
+    pub name: &'b str,
+    pub kind: VkTypeKind,
+    /// The members of a struct, or the variants of a union. Empty for every other kind of type.
+    pub fields: Vec<VkFieldView<'b>>,
+    /// The names of the variants of an enum or bitmask. Empty for every other kind of type.
+    pub variants: Vec<&'b str>,
+    /// The type that a typedef aliases.
+    pub aliases: Option<&'b str>
+}
+
+/// A view of a struct member or union variant, as part of a [`VkTypeView`].
+///
+/// [`VkTypeView`]: ./struct.VkTypeView.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VkFieldView<'b> {
+    pub name: &'b str,
+    /// The name of the type of the field, without any pointers or arrays.
+    pub type_name: &'b str,
+    /// Whether or not the field, or the data it points to, is `const`.
+    pub is_const: bool,
+    /// How many pointers the field's type is behind. `0` if it isn't a pointer.
+    pub pointer_depth: u8,
+    /// The length of the field if it's an array, with API constants resolved to their values.
+    pub array_len: Option<usize>,
+    pub optional: bool
+}
+
 #[derive(Clone)]
 pub enum VkType {
     Struct {
         name: *const str,
         fields: Vec<VkMember>,
+        /// The structs whose `pNext` chains this struct can be a part of, from `structextends`
+        extends: Vec<*const str>,
+        /// The registry's `comment` on the type. Optional
+        comment: *const str,
     },
 
     Union {
         name: *const str,
         variants: Vec<VkMember>,
+        /// Ditto.
+        comment: *const str,
     },
 
     Enum {
         name: *const str,
         variants: Vec<VkVariant>,
+        /// Ditto.
+        comment: *const str,
     },
 
     Bitmask {
         name: *const str,
         variants: Vec<VkVariant>,
+        /// Ditto.
+        comment: *const str,
+        /// The width of the flags in bits, which is `64` for bitmasks backed by `VkFlags64` and `32`
+        /// for the ones backed by `VkFlags`.
+        bitwidth: u32,
     },
 
     Handle {
         name: *const str,
         dispatchable: bool,
+        /// The `VkObjectType` variant for the handle. Optional
+        objtypeenum: *const str,
     },
 
     TypeDef {
@@ -535,6 +1367,17 @@ pub enum VkType {
         requires: *const str,
     },
 
+    /// Another name for an existing type, usually left behind when an extension type gets promoted
+    /// to core
+    Alias {
+        name: *const str,
+        /// The name of the type being aliased
+        alias_of: *const str,
+        /// Why the alias is deprecated, usually `aliased` for names that were only kept around
+        /// for compatibility. Optional
+        deprecated: *const str,
+    },
+
     Unhandled,
 }
 
@@ -551,11 +1394,36 @@ impl VkType {
             ApiConst{name, ..}     |
             Define{name, ..}       |
             FuncPointer{name, ..}  |
-            ExternType{name, ..}  => Some(name),
+            ExternType{name, ..}   |
+            Alias{name, ..}       => Some(name),
             Unhandled             => None,
         }
     }
 
+    /// The registry's comment on the type, if it has one. Only structs, unions, enums and bitmasks
+    /// carry comments.
+    pub fn comment(&self) -> Option<&str> {
+        use self::VkType::*;
+        match *self {
+            Struct{comment, ..}  |
+            Union{comment, ..}   |
+            Enum{comment, ..}    |
+            Bitmask{comment, ..} => to_option(comment),
+            _                    => None
+        }
+    }
+
+    pub fn set_comment(&mut self, new_comment: *const str) -> Result<(), ()> {
+        use self::VkType::*;
+        match *self {
+            Struct{ref mut comment, ..}  |
+            Union{ref mut comment, ..}   |
+            Enum{ref mut comment, ..}    |
+            Bitmask{ref mut comment, ..} => {*comment = new_comment; Ok(())}
+            _                            => Err(())
+        }
+    }
+
     pub fn set_name(&mut self, new_name: *const str) -> Result<(), ()> {
         use self::VkType::*;
         match *self {
@@ -568,7 +1436,8 @@ impl VkType {
             ApiConst{ref mut name, ..}     |
             Define{ref mut name, ..}       |
             FuncPointer{ref mut name, ..}  |
-            ExternType{ref mut name, ..}  => {*name = new_name; Ok(())}
+            ExternType{ref mut name, ..}   |
+            Alias{ref mut name, ..}       => {*name = new_name; Ok(())}
             Unhandled                     => Err(())
         }
     }
@@ -576,35 +1445,42 @@ impl VkType {
     pub fn new_struct(name: *const str) -> Self {
         VkType::Struct {
             name,
-            fields: Vec::with_capacity(8)
+            fields: Vec::with_capacity(8),
+            extends: Vec::new(),
+            comment: null_str()
         }
     }
 
     pub fn new_union(name: *const str) -> Self {
         VkType::Union {
             name,
-            variants: Vec::with_capacity(8)
+            variants: Vec::with_capacity(8),
+            comment: null_str()
         }
     }
 
     pub fn new_enum(name: *const str) -> Self {
         VkType::Enum {
             name,
-            variants: Vec::with_capacity(8)
+            variants: Vec::with_capacity(8),
+            comment: null_str()
         }
     }
 
-    pub fn new_bitmask(name: *const str) -> Self {
+    pub fn new_bitmask(name: *const str, bitwidth: u32) -> Self {
         VkType::Bitmask {
             name,
-            variants: Vec::with_capacity(8)
+            variants: Vec::with_capacity(8),
+            comment: null_str(),
+            bitwidth
         }
     }
 
-    pub fn empty_handle() -> Self {
+    pub fn empty_handle(objtypeenum: Option<*const str>) -> Self {
         VkType::Handle {
             name: null_str(),
-            dispatchable: true
+            dispatchable: true,
+            objtypeenum: objtypeenum.unwrap_or(null_str())
         }
     }
 
@@ -649,6 +1525,22 @@ impl VkType {
             requires,
         }
     }
+
+    pub fn new_alias(name: *const str, alias_of: *const str) -> Self {
+        VkType::Alias {
+            name,
+            alias_of,
+            deprecated: null_str(),
+        }
+    }
+
+    /// Mark an alias as deprecated for the given reason. Does nothing to other types.
+    fn with_deprecated(mut self, reason: Option<*const str>) -> Self {
+        if let (&mut VkType::Alias{ref mut deprecated, ..}, Some(reason)) = (&mut self, reason) {
+            *deprecated = reason;
+        }
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -657,6 +1549,12 @@ pub struct VkCommand {
     pub ret: VkElType,
     pub name: *const str,
     pub params: Vec<VkParam>,
+    /// The `VkResult` variants the command can return on success, from its `successcodes`
+    pub success_codes: Vec<*const str>,
+    /// The registry's `comment` on the command. Optional
+    pub comment: *const str,
+    /// The name of the command this is an alias of. Optional
+    pub alias_of: *const str,
 }
 
 impl fmt::Debug for VkCommand {
@@ -665,6 +1563,9 @@ impl fmt::Debug for VkCommand {
             .field("ret", &self.ret)
             .field("name", &to_option(self.name))
             .field("params", &self.params)
+            .field("success_codes", &self.success_codes.iter().map(|c| to_option(*c)).collect::<Vec<_>>())
+            .field("comment", &to_option(self.comment))
+            .field("alias_of", &to_option(self.alias_of))
             .finish()
     }
 }
@@ -675,6 +1576,9 @@ impl VkCommand {
             ret: VkElType::Unknown,
             name: null_str(),
             params: Vec::with_capacity(8),
+            success_codes: Vec::new(),
+            comment: null_str(),
+            alias_of: null_str(),
         }
     }
 }
@@ -683,6 +1587,14 @@ impl VkCommand {
 pub struct VkParam {
     pub typ: VkElType,
     pub name: *const str,
+    /// The `externsync` attribute. Either `"true"`, or the members of the parameter that have to be
+    /// synchronized. Optional
+    pub externsync: *const str,
+    /// Whether or not the parameter has valid usage rules that can't be checked automatically
+    pub noautovalidity: bool,
+    /// The `len` attribute, naming what the length of the array the parameter points to comes from.
+    /// Optional
+    pub len: *const str,
 }
 
 impl fmt::Debug for VkParam {
@@ -690,6 +1602,9 @@ impl fmt::Debug for VkParam {
         fmt .debug_struct("VkParam")
             .field("typ", &self.typ)
             .field("name", &to_option(self.name))
+            .field("externsync", &to_option(self.externsync))
+            .field("noautovalidity", &self.noautovalidity)
+            .field("len", &to_option(self.len))
             .finish()
     }
 }
@@ -698,72 +1613,108 @@ impl VkParam {
     fn empty() -> VkParam {
         VkParam {
             typ: VkElType::Unknown,
-            name: null_str()
+            name: null_str(),
+            externsync: null_str(),
+            noautovalidity: false,
+            len: null_str()
         }
     }
+
+    /// What the caller has to synchronize access to when passing this parameter, if anything. This is
+    /// the parameter itself if the registry says `"true"`, and a comma-separated list of members
+    /// otherwise.
+    pub fn externsync(&self) -> Option<&str> {
+        match to_option(self.externsync) {
+            Some("true") => to_option(self.name),
+            externsync   => externsync
+        }
+    }
+
+    pub fn noautovalidity(&self) -> bool {
+        self.noautovalidity
+    }
+
+    /// What the length of the array the parameter points to comes from, like `submitCount` or
+    /// `null-terminated`, if it points to an array.
+    pub fn len(&self) -> Option<&str> {
+        to_option(self.len)
+    }
 }
 
+/// A version of the Vulkan API, as `VkVersion(major, minor)`. Bindings are generated for a minor
+/// version, so the patch number of a feature tagged like `1.2.0` is kept on the feature instead.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct VkVersion(pub u16, pub u16);
 
 impl VkVersion {
-    fn from_str(num: &str) -> Self {
-        use std::u16;
-
-        let mut ver = [0; 2];
-        for (i, digit) in num.split('.').enumerate() {
-            ver[i] = u16::from_str_radix(digit, 10).unwrap();
+    /// Parse a feature's `number`, like `1.0` or `1.2.0`, into its version and its patch number,
+    /// which is `0` if there isn't one.
+    fn from_str(num: &str) -> Result<(VkVersion, u16), String> {
+        let parts = num.split('.')
+            .map(|part| part.parse().map_err(|_| format!("Invalid feature number {}", num)))
+            .collect::<Result<Vec<u16>, String>>()?;
+
+        match parts[..] {
+            [major, minor]        => Ok((VkVersion(major, minor), 0)),
+            [major, minor, patch] => Ok((VkVersion(major, minor), patch)),
+            _                     => Err(format!("Invalid feature number {}", num))
         }
-
-        VkVersion(ver[0], ver[1])
     }
 }
 
 pub struct VkFeature {
     pub name: *const str,
     pub version: VkVersion,
+    /// The patch number the feature is tagged with, like the `0` in `1.2.0`. `0` if it isn't tagged
+    /// with one, which is usually the case. Only read by `dump_json()`.
+    #[cfg_attr(not(feature = "serde_json"), allow(dead_code))]
+    pub patch: u16,
     pub require: Vec<VkInterface>,
     pub remove: Vec<VkInterface>,
 }
 
 impl VkFeature {
-    fn new(name: *const str, version: VkVersion) -> Self {
+    fn new(name: *const str, (version, patch): (VkVersion, u16)) -> Self {
         VkFeature {
             name,
             version: version,
+            patch,
             require: Vec::with_capacity(16),
             remove: Vec::with_capacity(16),
         }
     }
 
-    fn push_command(&mut self, name: *const str, reqrem: &VkReqRem) {
+    fn push_command(&mut self, name: *const str, reqrem: &VkReqRem) -> Result<(), String> {
         use self::VkReqRem::*;
 
         match *reqrem {
             Require(profile) => self.require.push(VkInterface::new_command(name, profile)),
             Remove(profile)  => self.remove.push(VkInterface::new_command(name, profile)),
-            None             => panic!("Invalid reqrem")
+            None             => return Err(format!("`{}` is outside of a <require> or <remove> block", unsafe{ &*name }))
         }
+        Ok(())
     }
 
-    fn push_const(&mut self, name: *const str, reqrem: &VkReqRem) {
+    fn push_const(&mut self, name: *const str, reqrem: &VkReqRem) -> Result<(), String> {
         use self::VkReqRem::*;
 
         match *reqrem {
             Require(profile) => self.require.push(VkInterface::new_api_const(name, profile)),
             Remove(profile)  => self.remove.push(VkInterface::new_api_const(name, profile)),
-            None             => panic!("Invalid reqrem")
+            None             => return Err(format!("`{}` is outside of a <require> or <remove> block", unsafe{ &*name }))
         }
+        Ok(())
     }
 
-    fn push_type(&mut self, name: *const str, reqrem: &VkReqRem) {
+    fn push_type(&mut self, name: *const str, reqrem: &VkReqRem) -> Result<(), String> {
         use self::VkReqRem::*;
 
         match *reqrem {
             Require(profile) => self.require.push(VkInterface::new_type(name, profile)),
             Remove(profile)  => self.remove.push(VkInterface::new_type(name, profile)),
-            None             => panic!("Invalid reqrem")
+            None             => return Err(format!("`{}` is outside of a <require> or <remove> block", unsafe{ &*name }))
         }
+        Ok(())
     }
 }
 
@@ -882,9 +1833,120 @@ enum VkReqRem {
     None,
 }
 
+/// A parsed `depends` expression, where `,` separates alternatives, `+` separates things that are all
+/// needed, and parentheses group.
+#[derive(Debug, PartialEq)]
+enum Depends<'b> {
+    Name(&'b str),
+    All(Vec<Depends<'b>>),
+    Any(Vec<Depends<'b>>)
+}
+
+impl<'b> Depends<'b> {
+    /// Parse `expr`, returning `None` if it isn't a valid expression.
+    fn parse(expr: &'b str) -> Option<Depends<'b>> {
+        match Depends::parse_any(expr.trim())? {
+            (depends, "") => Some(depends),
+            _             => None
+        }
+    }
+
+    fn parse_any(expr: &'b str) -> Option<(Depends<'b>, &'b str)> {
+        let (first, mut rest) = Depends::parse_all(expr)?;
+        let mut any = vec![first];
+        while let Some(after) = rest.strip_prefix(',') {
+            let (next, after) = Depends::parse_all(after.trim_start())?;
+            any.push(next);
+            rest = after;
+        }
+        Some((if any.len() == 1 {any.pop().unwrap()} else {Depends::Any(any)}, rest))
+    }
+
+    fn parse_all(expr: &'b str) -> Option<(Depends<'b>, &'b str)> {
+        let (first, mut rest) = Depends::parse_term(expr)?;
+        let mut all = vec![first];
+        while let Some(after) = rest.strip_prefix('+') {
+            let (next, after) = Depends::parse_term(after.trim_start())?;
+            all.push(next);
+            rest = after;
+        }
+        Some((if all.len() == 1 {all.pop().unwrap()} else {Depends::All(all)}, rest))
+    }
+
+    fn parse_term(expr: &'b str) -> Option<(Depends<'b>, &'b str)> {
+        if let Some(inner) = expr.strip_prefix('(') {
+            let (inner, rest) = Depends::parse_any(inner.trim_start())?;
+            let rest = rest.strip_prefix(')')?;
+            Some((inner, rest.trim_start()))
+        } else {
+            let end = expr.find([',', '+', '(', ')']).unwrap_or(expr.len());
+            // Feature dependencies like `VK_KHR_foo::feature` are met by the extension itself.
+            let name = expr[..end].trim();
+            let name = name.find("::").map_or(name, |i| &name[..i]);
+            if name.is_empty() {
+                return None;
+            }
+            Some((Depends::Name(name), expr[end..].trim_start()))
+        }
+    }
+
+    fn is_met<F: Fn(&str) -> bool>(&self, is_met: &F) -> bool {
+        match *self {
+            Depends::Name(name)    => is_met(name),
+            Depends::All(ref all)  => all.iter().all(|d| d.is_met(is_met)),
+            Depends::Any(ref any)  => any.iter().any(|d| d.is_met(is_met))
+        }
+    }
+
+    /// Push the names that aren't met by `is_met` but are needed to meet `self` onto `needed`, only
+    /// picking names that `can_add` accepts. Returns `false` if `self` can't be met that way.
+    fn needed<F, G>(&self, is_met: &F, can_add: &G, needed: &mut Vec<&'b str>) -> bool
+        where F: Fn(&str) -> bool,
+              G: Fn(&str) -> bool
+    {
+        match *self {
+            Depends::Name(name) if is_met(name)    => true,
+            Depends::Name(name) if can_add(name)   => {
+                needed.push(name);
+                true
+            },
+            Depends::Name(_)                       => false,
+            Depends::All(ref all)                  => {
+                // Every term is visited even once one can't be met, so that the names the others
+                // need are still found.
+                let mut met = true;
+                for d in all {
+                    met &= d.needed(is_met, can_add, needed);
+                }
+                met
+            },
+            Depends::Any(_) if self.is_met(is_met) => true,
+            Depends::Any(ref any)                  => any.iter().any(|d| {
+                let mut alternative = Vec::new();
+                let met = d.needed(is_met, can_add, &mut alternative);
+                if met {
+                    needed.extend(alternative);
+                }
+                met
+            })
+        }
+    }
+}
+
 pub struct VkExtn {
     pub name: *const str,
     pub num: isize,
+    /// What the extension depends on, in the syntax of the registry's `depends` attribute. Optional
+    pub depends: *const str,
+    /// The platform the extension is specific to, like `win32` or `xlib`. Optional
+    pub platform: *const str,
+    /// The core version or extension that the extension was promoted to. Optional
+    pub promoted_to: *const str,
+    /// The extension that replaces this one, or an empty string if it was deprecated without a
+    /// replacement. Optional
+    pub deprecated_by: *const str,
+    /// The extension that made this one obsolete. Optional
+    pub obsoleted_by: *const str,
     pub require: Vec<VkInterface>,
     pub remove: Vec<VkInterface>,
 }
@@ -894,49 +1956,423 @@ impl VkExtn {
         VkExtn {
             name,
             num,
+            depends: null_str(),
+            platform: null_str(),
+            promoted_to: null_str(),
+            deprecated_by: null_str(),
+            obsoleted_by: null_str(),
             require: Vec::with_capacity(8),
             // Most, if not all, extensions don't have remove tags so this is just here for contingency
             remove: Vec::new(),
         }
     }
 
-    fn push_command(&mut self, name: *const str, reqrem: &VkReqRem) {
+    /// Attach the extension's dependency expression, if it has one.
+    fn with_depends(mut self, depends: Option<*const str>) -> Self {
+        if let Some(depends) = depends {
+            self.depends = depends;
+        }
+        self
+    }
+
+    /// Attach the platform the extension is specific to, if it has one.
+    fn with_platform(mut self, platform: Option<*const str>) -> Self {
+        if let Some(platform) = platform {
+            self.platform = platform;
+        }
+        self
+    }
+
+    /// Attach what the extension was promoted to, deprecated by and obsoleted by, if anything.
+    fn with_replacements(mut self, promoted_to: Option<*const str>, deprecated_by: Option<*const str>, obsoleted_by: Option<*const str>) -> Self {
+        self.promoted_to = promoted_to.unwrap_or(self.promoted_to);
+        self.deprecated_by = deprecated_by.unwrap_or(self.deprecated_by);
+        self.obsoleted_by = obsoleted_by.unwrap_or(self.obsoleted_by);
+        self
+    }
+
+    /// The core version or extension the extension was promoted to, like `VK_VERSION_1_1`.
+    pub fn promoted_to(&self) -> Option<&str> {
+        to_option(self.promoted_to)
+    }
+
+    /// The extension that replaces this one. `Some("")` if it was deprecated without a replacement.
+    pub fn deprecated_by(&self) -> Option<&str> {
+        to_option(self.deprecated_by)
+    }
+
+    /// The extension that made this one obsolete.
+    pub fn obsoleted_by(&self) -> Option<&str> {
+        to_option(self.obsoleted_by)
+    }
+
+    /// The platform the extension is specific to, like `win32`, if it is specific to one.
+    pub fn platform(&self) -> Option<&str> {
+        to_option(self.platform)
+    }
+
+    /// The extension's dependency expression, like `VK_KHR_surface` or
+    /// `VK_KHR_get_physical_device_properties2,VK_VERSION_1_1`.
+    pub fn depends(&self) -> Option<&str> {
+        to_option(self.depends)
+    }
+
+    fn push_command(&mut self, name: *const str, reqrem: &VkReqRem) -> Result<(), String> {
         use self::VkReqRem::*;
 
         match *reqrem {
             Require(profile) => self.require.push(VkInterface::new_command(name, profile)),
             Remove(profile)  => self.remove.push(VkInterface::new_command(name, profile)),
-            None             => panic!("Invalid reqrem")
+            None             => return Err(format!("`{}` is outside of a <require> or <remove> block", unsafe{ &*name }))
         }
+        Ok(())
     }
 
-    fn push_const(&mut self, name: *const str, value: *const str, reqrem: &VkReqRem) {
+    fn push_const(&mut self, name: *const str, value: *const str, reqrem: &VkReqRem) -> Result<(), String> {
         use self::VkReqRem::*;
 
         match *reqrem {
             Require(profile) => self.require.push(VkInterface::new_const_def(name, value, profile)),
             Remove(profile)  => self.remove.push(VkInterface::new_const_def(name, value, profile)),
-            None             => panic!("Invalid reqrem")
+            None             => return Err(format!("`{}` is outside of a <require> or <remove> block", unsafe{ &*name }))
         }
+        Ok(())
     }
 
-    fn push_enum(&mut self, variant: VkVariant, extends: Option<*const str>, reqrem: &VkReqRem) {
+    fn push_enum(&mut self, variant: VkVariant, extends: Option<*const str>, reqrem: &VkReqRem) -> Result<(), String> {
         use self::VkReqRem::*;
 
         match *reqrem {
             Require(profile) => self.require.push(VkInterface::new_extn_enum(variant, extends, profile)),
             Remove(profile)  => self.remove.push(VkInterface::new_extn_enum(variant, extends, profile)),
-            None             => panic!("Invalid reqrem")
+            None             => return Err(format!("`{}` is outside of a <require> or <remove> block", unsafe{ &*variant.name() }))
         }
+        Ok(())
     }
 
-    fn push_type(&mut self, name: *const str, reqrem: &VkReqRem) {
+    fn push_type(&mut self, name: *const str, reqrem: &VkReqRem) -> Result<(), String> {
         use self::VkReqRem::*;
 
         match *reqrem {
             Require(profile) => self.require.push(VkInterface::new_type(name, profile)),
             Remove(profile)  => self.remove.push(VkInterface::new_type(name, profile)),
-            None             => panic!("Invalid reqrem")
+            None             => return Err(format!("`{}` is outside of a <require> or <remove> block", unsafe{ &*name }))
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generator::GenConfig;
+
+    #[test]
+    fn variant_aliases() {
+        // `VK_FOO_BAR_KHR` was promoted to `VK_FOO_BAR`, and `VK_FOO_BAZ_EXT` aliases a variant that
+        // the extension after it adds.
+        let registry = VkRegistry::new(br#"<registry>
+            <types>
+                <type name="VkFoo" category="enum"/>
+            </types>
+            <enums name="VkFoo" type="enum">
+                <enum value="0" name="VK_FOO_ZERO"/>
+                <enum value="5" name="VK_FOO_BAR"/>
+                <enum name="VK_FOO_RENAMED" alias="VK_FOO_ZERO"/>
+            </enums>
+            <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+                <require>
+                    <type name="VkFoo"/>
+                </require>
+            </feature>
+            <extensions>
+                <extension name="VK_KHR_foo" number="1" supported="vulkan">
+                    <require>
+                        <enum extends="VkFoo" name="VK_FOO_BAR_KHR" alias="VK_FOO_BAR"/>
+                        <enum extends="VkFoo" name="VK_FOO_BAZ_EXT" alias="VK_FOO_BAZ_KHR"/>
+                    </require>
+                </extension>
+                <extension name="VK_KHR_baz" number="2" supported="vulkan">
+                    <require>
+                        <enum offset="0" extends="VkFoo" name="VK_FOO_BAZ_KHR"/>
+                    </require>
+                </extension>
+            </extensions>
+        </registry>"#);
+
+        let value_of = |v: &VkVariant| match *v {
+            VkVariant::Value{value, ..} => value,
+            VkVariant::Bitpos{..}       => panic!("Unexpected bitpos")
+        };
+        let extn_variant = |extn: &str, i: usize| match registry.extns()[extn].require[i] {
+            VkInterface::ExtnEnum{ref variant, ..} => variant.clone(),
+            _                                      => panic!("Expected enum extension")
+        };
+
+        let bar_khr = extn_variant("VK_KHR_foo", 0);
+        assert_eq!(bar_khr.alias_of(), Some("VK_FOO_BAR"));
+        assert_eq!(value_of(&bar_khr), 5);
+
+        let baz_ext = extn_variant("VK_KHR_foo", 1);
+        assert_eq!(baz_ext.alias_of(), Some("VK_FOO_BAZ_KHR"));
+        assert_eq!(value_of(&baz_ext), value_of(&extn_variant("VK_KHR_baz", 0)));
+        assert_eq!(extn_variant("VK_KHR_baz", 0).alias_of(), None);
+
+        if let VkType::Enum{ref variants, ..} = registry.types()["VkFoo"] {
+            assert_eq!(variants[1].alias_of(), None);
+            assert_eq!(variants[2].alias_of(), Some("VK_FOO_ZERO"));
+            assert_eq!(value_of(&variants[2]), 0);
+        } else {panic!("VkFoo not an enum")}
+
+        // Native enums can't hold aliases as variants, so they become associated constants instead.
+        let mut out = Vec::new();
+        registry.gen_struct(&mut out, VkVersion(1, 0), &["VK_KHR_foo", "VK_KHR_baz"], GenConfig::default()).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("    Bar = 5,"));
+        assert!(!out.contains("    BarKhr = 5,"));
+        assert!(out.contains("    pub const BarKhr: VkFoo = VkFoo::Bar;"));
+
+        let mut out = Vec::new();
+        registry.gen_struct(&mut out, VkVersion(1, 0), &["VK_KHR_foo", "VK_KHR_baz"], GenConfig {
+            use_native_enums: false,
+            ..GenConfig::default()
+        }).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("pub const BarKhr: u32 = 5;"));
+        assert!(out.contains("pub const Renamed: u32 = 0;"));
+    }
+
+    #[test]
+    fn param_sync_metadata() {
+        let registry = VkRegistry::new(br#"<registry>
+            <types>
+                <type requires="vk_platform" name="void"/>
+                <type requires="vk_platform" name="uint32_t"/>
+                <type category="handle" name="VkQueue"><type>VK_DEFINE_HANDLE</type>(<name>VkQueue</name>)</type>
+                <type category="struct" name="VkSubmitInfo">
+                    <member><type>uint32_t</type> <name>count</name></member>
+                </type>
+            </types>
+            <commands>
+                <command>
+                    <proto><type>void</type> <name>vkQueueSubmit</name></proto>
+                    <param externsync="true"><type>VkQueue</type> <name>queue</name></param>
+                    <param><type>uint32_t</type> <name>submitCount</name></param>
+                    <param noautovalidity="true" externsync="pSubmits[].pWaitSemaphores[]" len="submitCount">const <type>VkSubmitInfo</type>* <name>pSubmits</name></param>
+                </command>
+            </commands>
+            <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+                <require>
+                    <command name="vkQueueSubmit"/>
+                </require>
+            </feature>
+        </registry>"#);
+
+        let params = &registry.commands()["vkQueueSubmit"].params;
+        assert_eq!(params[0].externsync(), Some("queue"));
+        assert_eq!(params[1].externsync(), None);
+        assert_eq!(params[2].externsync(), Some("pSubmits[].pWaitSemaphores[]"));
+        assert_eq!(params.iter().map(|p| p.noautovalidity()).collect::<Vec<_>>(), [false, false, true]);
+        assert_eq!(params.iter().map(|p| p.len()).collect::<Vec<_>>(), [None, None, Some("submitCount")]);
+
+        let mut out = Vec::new();
+        registry.gen_global(&mut out, VkVersion(1, 0), &[], GenConfig {
+            param_docs: true,
+            ..GenConfig::default()
+        }).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("    /// Externally synchronized: `queue`, `p_submits[].p_wait_semaphores[]`\n    ///\n    /// Not automatically validated: `p_submits`\n"));
+    }
+
+    #[test]
+    fn extension_enum_values() {
+        let registry = VkRegistry::new(br#"<registry>
+            <types>
+                <type name="VkResult" category="enum"/>
+                <type name="VkFooFlagBits" category="enum"/>
+            </types>
+            <enums name="VkResult" type="enum">
+                <enum value="0" name="VK_SUCCESS"/>
+            </enums>
+            <enums name="VkFooFlagBits" type="bitmask">
+                <enum bitpos="0" name="VK_FOO_A_BIT"/>
+            </enums>
+            <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+                <require>
+                    <type name="VkResult"/>
+                </require>
+            </feature>
+            <extensions>
+                <extension name="VK_KHR_maintenance1" number="70" supported="vulkan">
+                    <require>
+                        <enum offset="0" dir="-" extends="VkResult" name="VK_ERROR_OUT_OF_POOL_MEMORY_KHR"/>
+                        <enum offset="3" extends="VkResult" name="VK_FOO_KHR"/>
+                        <enum offset="1" extnumber="2" extends="VkResult" name="VK_BORROWED_KHR"/>
+                        <enum bitpos="4" extends="VkFooFlagBits" name="VK_FOO_E_BIT_KHR"/>
+                    </require>
+                </extension>
+            </extensions>
+        </registry>"#);
+
+        let values: Vec<_> = registry.extns()["VK_KHR_maintenance1"].require.iter()
+            .map(|i| match *i {
+                VkInterface::ExtnEnum{ref variant, ..} => (variant.value(), variant.is_bitpos()),
+                _                                      => panic!("Expected enum extension")
+            })
+            .collect();
+        assert_eq!(values, [(-1000069000, false), (1000069003, false), (1000001001, false), (16, true)]);
+        // The top bit of a 64-bit bitmask.
+        assert_eq!(VkVariant::new_bitpos("VK_FOO_63_BIT", 63).value(), i64::MIN);
+    }
+
+    #[test]
+    fn member_set_name() {
+        let mut member = VkMember::empty(false);
+        assert!(member.set_name("count").is_ok());
+        assert_eq!(to_option(member.field_name), Some("count"));
+
+        // A member only has one `name` tag, so a second one means the xml is malformed.
+        assert!(member.set_name("other").is_err());
+        assert_eq!(to_option(member.field_name), Some("count"));
+    }
+
+    #[test]
+    fn depends_expressions() {
+        assert_eq!(Depends::parse("VK_KHR_a"), Some(Depends::Name("VK_KHR_a")));
+        assert_eq!(Depends::parse("(VK_KHR_a+VK_KHR_b),VK_VERSION_1_1"), Some(Depends::Any(vec![
+            Depends::All(vec![Depends::Name("VK_KHR_a"), Depends::Name("VK_KHR_b")]),
+            Depends::Name("VK_VERSION_1_1")
+        ])));
+        assert_eq!(Depends::parse("VK_KHR_a::feature+VK_KHR_b"), Some(Depends::All(vec![
+            Depends::Name("VK_KHR_a"),
+            Depends::Name("VK_KHR_b")
+        ])));
+        assert_eq!(Depends::parse("(VK_KHR_a+VK_KHR_b"), None);
+        assert_eq!(Depends::parse("VK_KHR_a,"), None);
+        assert_eq!(Depends::parse("VK_KHR_a)"), None);
+    }
+
+    #[test]
+    fn extension_dependencies() {
+        let registry = VkRegistry::new(br#"<registry>
+            <feature api="vulkan" name="VK_VERSION_1_0" number="1.0"/>
+            <feature api="vulkan" name="VK_VERSION_1_1" number="1.1"/>
+            <extensions>
+                <extension name="VK_KHR_a" number="1" supported="vulkan"/>
+                <extension name="VK_KHR_b" number="2" supported="vulkan" requires="VK_KHR_a"/>
+                <extension name="VK_KHR_c" number="3" supported="vulkan" depends="VK_VERSION_1_1,VK_KHR_b"/>
+                <extension name="VK_KHR_d" number="4" supported="vulkan" depends="(VK_KHR_c+VK_KHR_a),VK_KHR_e"/>
+                <extension name="VK_KHR_e" number="5" supported="vulkan"/>
+                <extension name="VK_KHR_f" number="6" supported="vulkan" depends="(VK_KHR_a+VK_KHR_b"/>
+            </extensions>
+        </registry>"#);
+
+        assert_eq!(registry.extension_dependencies(VkVersion(1, 0), &["VK_KHR_b"]), ["VK_KHR_a"]);
+        // A malformed expression doesn't depend on anything.
+        assert!(registry.extension_dependencies(VkVersion(1, 0), &["VK_KHR_f"]).is_empty());
+        // Either the core version or the extension does.
+        assert!(registry.extension_dependencies(VkVersion(1, 1), &["VK_KHR_c"]).is_empty());
+        assert_eq!(registry.extension_dependencies(VkVersion(1, 0), &["VK_KHR_c"]), ["VK_KHR_b", "VK_KHR_a"]);
+        assert_eq!(registry.extension_dependencies(VkVersion(1, 1), &["VK_KHR_d"]), ["VK_KHR_c", "VK_KHR_a"]);
+        assert!(registry.extension_dependencies(VkVersion(1, 0), &["VK_KHR_d", "VK_KHR_e"]).is_empty());
+    }
+
+    #[test]
+    fn extension_platforms() {
+        let registry = VkRegistry::new(br#"<registry>
+            <extensions>
+                <extension name="VK_KHR_a" number="1" supported="vulkan"/>
+                <extension name="VK_KHR_b" number="2" supported="vulkan" platform="wayland"/>
+                <extension name="VK_KHR_c" number="3" supported="vulkan" protect="VK_USE_PLATFORM_WIN32_KHR"/>
+                <extension name="VK_EXT_d" number="4" supported="vulkan" protect="VK_USE_PLATFORM_XLIB_XRANDR_EXT"/>
+            </extensions>
+        </registry>"#);
+
+        let platform = |name| registry.extns().get(name).unwrap().platform();
+        assert_eq!(platform("VK_KHR_a"), None);
+        assert_eq!(platform("VK_KHR_b"), Some("wayland"));
+        assert_eq!(platform("VK_KHR_c"), Some("win32"));
+        assert_eq!(platform("VK_EXT_d"), Some("xlib_xrandr"));
+    }
+
+    /// Whether `typ` is a const pointer, what it points to, and how many levels deep it is.
+    fn ptr_info(typ: &VkElType) -> Option<(bool, &str, u8)> {
+        match *typ {
+            VkElType::ConstPtr(s, depth) => Some((true, unsafe{ &*s }, depth)),
+            VkElType::MutPtr(s, depth)   => Some((false, unsafe{ &*s }, depth)),
+            _                            => None
+        }
+    }
+
+    #[test]
+    fn make_ptr_adds_levels() {
+        let mut pp_enabled_layer_names = VkElType::Var("char");
+        pp_enabled_layer_names.make_const();
+        pp_enabled_layer_names.make_ptr(1);
+        pp_enabled_layer_names.make_ptr(1);
+        assert_eq!(ptr_info(&pp_enabled_layer_names), Some((true, "char", 2)));
+
+        let mut pp_data = VkElType::Var("void");
+        pp_data.make_ptr(1);
+        pp_data.make_ptr(1);
+        assert_eq!(ptr_info(&pp_data), Some((false, "void", 2)));
+    }
+
+    #[test]
+    fn double_pointers() {
+        let registry = VkRegistry::new(br#"<registry>
+            <types>
+                <type name="VkInstanceCreateInfo" category="struct">
+                    <member>const <type>char</type>* const*      <name>ppEnabledLayerNames</name></member>
+                </type>
+            </types>
+            <commands>
+                <command>
+                    <proto><type>void</type> <name>vkMapMemory</name></proto>
+                    <param><type>void</type>** <name>ppData</name></param>
+                </command>
+            </commands>
+        </registry>"#);
+
+        match registry.types().get("VkInstanceCreateInfo") {
+            Some(VkType::Struct{fields, ..}) => assert_eq!(ptr_info(&fields[0].field_type), Some((true, "char", 2))),
+            _                                 => panic!("VkInstanceCreateInfo isn't a struct")
         }
+        assert_eq!(ptr_info(&registry.commands().get("vkMapMemory").unwrap().params[0].typ), Some((false, "void", 2)));
+    }
+
+    #[test]
+    fn commands_without_params() {
+        let registry = VkRegistry::new(br#"<registry>
+            <commands>
+                <command>
+                    <proto><type>void</type> <name>vkNoParams</name></proto>
+                </command>
+                <command>
+                    <proto><type>void</type> <name>vkUnknownParams</name></proto>
+                    <param/>
+                    <param/>
+                </command>
+            </commands>
+        </registry>"#);
+
+        assert!(registry.commands()["vkNoParams"].params.is_empty());
+        assert!(registry.commands()["vkUnknownParams"].params.is_empty());
+    }
+
+    #[test]
+    fn extension_replacements() {
+        let registry = VkRegistry::new(br#"<registry>
+            <extensions>
+                <extension name="VK_KHR_old" number="1" supported="vulkan" promotedto="VK_VERSION_1_1"/>
+                <extension name="VK_EXT_gone" number="2" supported="vulkan" deprecatedby=""/>
+                <extension name="VK_EXT_replaced" number="3" supported="vulkan" obsoletedby="VK_KHR_old"/>
+            </extensions>
+        </registry>"#);
+
+        let old = &registry.extns()["VK_KHR_old"];
+        assert_eq!((old.promoted_to(), old.deprecated_by(), old.obsoleted_by()), (Some("VK_VERSION_1_1"), None, None));
+        assert_eq!(registry.extns()["VK_EXT_gone"].deprecated_by(), Some(""));
+        assert_eq!(registry.extns()["VK_EXT_replaced"].obsoleted_by(), Some("VK_KHR_old"));
     }
 }
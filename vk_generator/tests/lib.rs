@@ -1,12 +1,20 @@
 extern crate vk_generator;
 extern crate vk_api;
+#[cfg(feature = "proc-macro2")]
+extern crate proc_macro2;
+#[cfg(feature = "proc-macro2")]
+extern crate syn;
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
 
+use std::env;
 use std::path::Path;
 use std::fs::{File, DirBuilder};
 use std::process::Command;
-use std::io::Write;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
 
-use vk_generator::{VkVersion, GenConfig, VariantPaddingConfig};
+use vk_generator::{Diagnostic, VkRegistry, VkTypeKind, VkVersion, GenConfig, IdentKind, LoadReporting, Renamer, TypesSubset, VariantAction, VariantPaddingConfig, Visibility};
 
 #[test]
 fn default_global() {
@@ -21,7 +29,7 @@ fn default_global() {
             VkVersion(1, 0),
             &["VK_KHR_surface", "VK_EXT_debug_report"],
             Default::default()
-        );
+        ).unwrap();
     writeln!(file, "}}").unwrap();
 
     let error = String::from_utf8(Command::new("rustc").current_dir(&out).arg("default_global.rs").output().unwrap().stderr).unwrap();
@@ -43,7 +51,7 @@ fn default_struct() {
             VkVersion(1, 0),
             &["VK_KHR_surface", "VK_EXT_debug_report"],
             Default::default()
-        );
+        ).unwrap();
     writeln!(file, "}}").unwrap();
 
     let error = String::from_utf8(Command::new("rustc").current_dir(&out).arg("default_struct.rs").output().unwrap().stderr).unwrap();
@@ -52,6 +60,36 @@ fn default_struct() {
     }
 }
 
+#[cfg(feature = "proc-macro2")]
+#[test]
+fn gen_tokens() {
+    use proc_macro2::{TokenStream, TokenTree};
+
+    fn has_struct(tokens: TokenStream, name: &str) -> bool {
+        let mut after_struct = false;
+        for tt in tokens {
+            match tt {
+                TokenTree::Ident(ref ident) if after_struct && ident == name => return true,
+                TokenTree::Group(ref group) if has_struct(group.stream(), name) => return true,
+                _ => ()
+            }
+            after_struct = match tt {
+                TokenTree::Ident(ref ident) => ident == "struct",
+                _                           => false
+            };
+        }
+        false
+    }
+
+    let registry = VkRegistry::new(vk_api::VK_XML);
+    let tokens = registry.gen_struct_tokens(VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    syn::parse2::<syn::File>(tokens.clone()).unwrap();
+    assert!(has_struct(tokens, "Vk"));
+
+    let tokens = registry.gen_global_tokens(VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    syn::parse2::<syn::File>(tokens).unwrap();
+}
+
 #[test]
 fn nondefault_global() {
     let out = env!("OUT_DIR");
@@ -82,7 +120,7 @@ fn nondefault_global() {
                 use_libc_types: true,
                 ..GenConfig::default()
             },
-        );
+        ).unwrap();
     writeln!(file, "}}").unwrap();
 
     let error = String::from_utf8(Command::new("rustc").current_dir(&out).arg("nondefault_global.rs").output().unwrap().stderr).unwrap();
@@ -121,7 +159,7 @@ fn nondefault_struct() {
                 use_libc_types: true,
                 ..GenConfig::default()
             }
-        );
+        ).unwrap();
     writeln!(file, "}}").unwrap();
 
     let error = String::from_utf8(Command::new("rustc").current_dir(&out).arg("nondefault_struct.rs").output().unwrap().stderr).unwrap();
@@ -129,3 +167,2911 @@ fn nondefault_struct() {
         panic!("{}", error);
     }
 }
+
+/// Writes `main_body` into a `main` function alongside the bindings produced by `gen` (which are
+/// placed in `mod vk`), then compiles and runs the result, panicking if either step fails.
+fn run_generated<F: FnOnce(&mut File)>(name: &str, gen: F, main_body: &str) {
+    let out = env!("OUT_DIR");
+    DirBuilder::new().recursive(true).create(out).unwrap();
+
+    let mut file = File::create(Path::new(&out).join(name).with_extension("rs")).unwrap();
+    writeln!(file, "#![allow(dead_code)]\nfn main() {{\n{}\n}}\nmod vk {{", main_body).unwrap();
+    gen(&mut file);
+    writeln!(file, "}}").unwrap();
+
+    let compile = Command::new("rustc").current_dir(out).arg(Path::new(name).with_extension("rs")).output().unwrap();
+    if !compile.status.success() {
+        panic!("{}", String::from_utf8(compile.stderr).unwrap());
+    }
+
+    let run = Command::new(Path::new(&out).join(name).with_extension(env::consts::EXE_EXTENSION)).output().unwrap();
+    if !run.status.success() {
+        panic!("{}", String::from_utf8(run.stderr).unwrap());
+    }
+}
+
+#[test]
+fn bitmask_debug_names() {
+    run_generated("bitmask_debug_names", |file| {
+        vk_generator::VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], Default::default()).unwrap();
+    }, r#"
+        let flags = vk::BUFFER_USAGE_TRANSFER_SRC_BIT | vk::BUFFER_USAGE_INDEX_BUFFER_BIT;
+        assert_eq!(
+            format!("{:?}", flags),
+            "VkBufferUsageFlagBits(BUFFER_USAGE_TRANSFER_SRC_BIT | BUFFER_USAGE_INDEX_BUFFER_BIT)");
+        assert_eq!(format!("{:?}", vk::VkBufferUsageFlagBits::empty()), "VkBufferUsageFlagBits(0b0)");
+    "#);
+}
+
+#[test]
+fn bitmask_64_bit() {
+    // `VkPipelineStageFlagBits2` leaves its width to be inferred from the `VkFlags64` typedef, the way
+    // registries without `bitwidth` attributes would.
+    const REGISTRY: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type requires="vk_platform" name="uint64_t"/>
+            <type category="basetype">typedef <type>uint32_t</type> <name>VkFlags</name>;</type>
+            <type category="basetype">typedef <type>uint64_t</type> <name>VkFlags64</name>;</type>
+            <type bitvalues="VkAccessFlagBits2" category="bitmask">typedef <type>VkFlags64</type> <name>VkAccessFlags2</name>;</type>
+            <type requires="VkPipelineStageFlagBits2" category="bitmask">typedef <type>VkFlags64</type> <name>VkPipelineStageFlags2</name>;</type>
+            <type category="struct" name="VkMemoryBarrier2">
+                <member><type>VkPipelineStageFlags2</type> <name>srcStageMask</name></member>
+                <member><type>VkAccessFlags2</type> <name>srcAccessMask</name></member>
+            </type>
+        </types>
+        <enums name="VkAccessFlagBits2" type="bitmask" bitwidth="64">
+            <enum bitpos="0" name="VK_ACCESS_2_INDIRECT_COMMAND_READ_BIT"/>
+            <enum bitpos="32" name="VK_ACCESS_2_SHADER_SAMPLED_READ_BIT"/>
+            <enum bitpos="63" name="VK_ACCESS_2_HIGHEST_BIT"/>
+        </enums>
+        <enums name="VkPipelineStageFlagBits2" type="bitmask">
+            <enum bitpos="40" name="VK_PIPELINE_STAGE_2_HIGH_BIT"/>
+        </enums>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <type name="VkMemoryBarrier2"/>
+            </require>
+        </feature>
+    </registry>"#;
+
+    run_generated("bitmask_64_bit", |file| {
+        VkRegistry::new(REGISTRY).gen_struct(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        let high: u64 = 1 << 63;
+        let flags = vk::VkAccessFlagBits2::from_flags(high | 1).unwrap();
+        assert_eq!(flags, vk::ACCESS_2_HIGHEST_BIT | vk::ACCESS_2_INDIRECT_COMMAND_READ_BIT);
+        assert_eq!(flags.flags(), high | 1);
+        assert_eq!(vk::ACCESS_2_SHADER_SAMPLED_READ_BIT.flags(), 1 << 32);
+        assert_eq!(vk::VkAccessFlagBits2::from_flags(1 << 1), None);
+        assert_eq!(vk::PIPELINE_STAGE_2_HIGH_BIT.flags(), 1u64 << 40);
+        assert_eq!(std::mem::size_of::<vk::VkPipelineStageFlagBits2>(), 8);
+    "#);
+
+    let mut out = Vec::new();
+    VkRegistry::new(REGISTRY).gen_struct(&mut out, VkVersion(1, 0), &[], GenConfig {
+        wrap_bitmasks: false,
+        ..GenConfig::default()
+    }).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("pub type VkAccessFlagBits2 = VkFlags64;"), "{}", out);
+    assert!(out.contains(&format!("pub const ACCESS_2_HIGHEST_BIT: VkAccessFlagBits2 = 0b{:b};", 1u64 << 63)));
+}
+
+#[test]
+fn registry_extra_capacity() {
+    let registry = vk_generator::VkRegistry::with_capacity(vk_api::VK_XML, 1 << 16);
+
+    let mut out = Vec::new();
+    registry.gen_struct(
+        &mut out,
+        VkVersion(1, 0),
+        &["VK_KHR_surface", "VK_KHR_swapchain", "VK_EXT_debug_report"],
+        Default::default()
+    ).unwrap();
+    assert!(String::from_utf8(out).unwrap().contains("create_swapchain_khr"));
+}
+
+#[test]
+fn field_offsets() {
+    run_generated("field_offsets", |file| {
+        vk_generator::VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                field_offsets: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use std::mem;
+
+        let info: vk::VkApplicationInfo = unsafe{ mem::zeroed() };
+        let base = &info as *const _ as usize;
+        assert_eq!(&info.p_next as *const _ as usize - base, vk::VkApplicationInfo::OFFSET_p_next);
+        assert_eq!(&info.api_version as *const _ as usize - base, vk::VkApplicationInfo::OFFSET_api_version);
+
+        let props: vk::VkPhysicalDeviceProperties = unsafe{ mem::zeroed() };
+        let base = &props as *const _ as usize;
+        assert_eq!(&props.limits as *const _ as usize - base, vk::VkPhysicalDeviceProperties::OFFSET_limits);
+        assert_eq!(&props.sparse_properties as *const _ as usize - base, vk::VkPhysicalDeviceProperties::OFFSET_sparse_properties);
+    "#);
+}
+
+#[test]
+fn pub_crate_visibility() {
+    let mut out = Vec::new();
+    vk_generator::VkRegistry::new(vk_api::VK_XML)
+        .gen_struct(&mut out, VkVersion(1, 0), &[], GenConfig {
+            visibility: Visibility::PubCrate,
+            ..GenConfig::default()
+        }).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("pub(crate) struct VkApplicationInfo"));
+    assert!(out.contains("pub(crate) mod cmds"));
+    assert!(!out.contains("pub struct"));
+    assert!(!out.contains("pub fn"));
+
+    run_generated("pub_crate_visibility", |file| {
+        vk_generator::VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig {
+                visibility: Visibility::PubCrate,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        let info: vk::VkApplicationInfo = unsafe{ ::std::mem::zeroed() };
+        assert!(info.p_next.is_null());
+    "#);
+}
+
+#[test]
+fn command_offsets() {
+    run_generated("command_offsets", |file| {
+        vk_generator::VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        let mut vk = vk::Vk::new();
+        // Hand out a distinct fake pointer for every command.
+        let mut names = Vec::new();
+        vk.load_with(|name| { names.push(name.to_owned()); names.len() as *const () }).unwrap();
+
+        assert_eq!(names.len(), vk::Vk::COMMAND_OFFSETS.len());
+        let base = &vk as *const vk::Vk as *const u8;
+        for &(name, offset) in vk::Vk::COMMAND_OFFSETS {
+            let fn_ptr = unsafe{ *(base.offset(offset as isize) as *const *const ()) };
+            let expected = names.iter().position(|n| n == name).unwrap() + 1;
+            assert_eq!(fn_ptr as usize, expected, "{}", name);
+        }
+    "#);
+}
+
+#[test]
+fn chain_builders() {
+    run_generated("chain_builders", |file| {
+        vk_generator::VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_EXT_validation_flags"], GenConfig {
+                chain_builders: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use std::{mem, ptr};
+
+        let mut first: vk::VkValidationFlagsEXT = unsafe{ mem::zeroed() };
+        first.s_type = vk::VkStructureType::ValidationFlagsExt;
+        first.disabled_validation_check_count = 1;
+        let mut second = first;
+        second.disabled_validation_check_count = 2;
+
+        let mut create_info: vk::VkInstanceCreateInfo = unsafe{ mem::zeroed() };
+        create_info.s_type = vk::VkStructureType::InstanceCreateInfo;
+        create_info.p_next = ptr::null();
+
+        let builder = create_info.builder()
+            .push_next(&mut first)
+            .push_next(&mut second);
+
+        // Walk the chain and make sure the structs come out in the order they were pushed.
+        let mut link = builder.p_next as *const vk::VkValidationFlagsEXT;
+        let mut counts = Vec::new();
+        while !link.is_null() {
+            unsafe {
+                assert_eq!((*link).s_type, vk::VkStructureType::ValidationFlagsExt);
+                counts.push((*link).disabled_validation_check_count);
+                link = (*link).p_next as *const vk::VkValidationFlagsEXT;
+            }
+        }
+        assert_eq!(counts, [1, 2]);
+    "#);
+}
+
+#[test]
+fn gen_chain_helpers() {
+    run_generated("gen_chain_helpers", |file| {
+        vk_generator::VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_NV_dedicated_allocation", "VK_NV_external_memory"], GenConfig {
+                gen_chain_helpers: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use std::mem;
+        use vk::StructureChain;
+
+        let mut dedicated: vk::VkDedicatedAllocationMemoryAllocateInfoNV = unsafe{ mem::zeroed() };
+        dedicated.s_type = vk::VkDedicatedAllocationMemoryAllocateInfoNV::STRUCTURE_TYPE;
+        assert_eq!(dedicated.s_type, vk::VkStructureType::DedicatedAllocationMemoryAllocateInfoNv);
+        let mut export: vk::VkExportMemoryAllocateInfoNV = unsafe{ mem::zeroed() };
+        export.s_type = vk::VkExportMemoryAllocateInfoNV::STRUCTURE_TYPE;
+        export.handle_types = 1;
+
+        let mut allocate_info: vk::VkMemoryAllocateInfo = unsafe{ mem::zeroed() };
+        allocate_info.s_type = vk::VkMemoryAllocateInfo::STRUCTURE_TYPE;
+        unsafe {
+            allocate_info.link_next(&mut dedicated);
+            allocate_info.link_next(&mut export);
+        }
+
+        // Read the chain back, relying on every chainable struct starting with the same header.
+        unsafe {
+            let first = allocate_info.p_next as *const vk::VkDedicatedAllocationMemoryAllocateInfoNV;
+            assert_eq!(first, &dedicated as *const _);
+            assert_eq!((*first).s_type, vk::VkDedicatedAllocationMemoryAllocateInfoNV::STRUCTURE_TYPE);
+
+            let second = (*first).p_next as *const vk::VkExportMemoryAllocateInfoNV;
+            assert_eq!(second, &export as *const _);
+            assert_eq!((*second).s_type, vk::VkStructureType::ExportMemoryAllocateInfoNv);
+            assert_eq!((*second).handle_types, 1);
+            assert!((*second).p_next.is_null());
+        }
+    "#);
+}
+
+#[test]
+fn newtype_enum_int_eq() {
+    run_generated("newtype_enum_int_eq", |file| {
+        vk_generator::VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                use_native_enums: false,
+                newtype_enums: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        let result = vk::VkResult(-4);
+        assert!(result == vk::VkResult::ErrorDeviceLost);
+        assert!(result != vk::VkResult::Success);
+        assert!(result == -4);
+        assert!(-4 == result);
+        assert!(vk::VkResult::Success == 0);
+        assert_eq!(format!("{:?}", result), "ErrorDeviceLost");
+    "#);
+}
+
+#[test]
+fn xinclude_registry() {
+    let dir = Path::new(env!("OUT_DIR")).join("xinclude");
+    DirBuilder::new().recursive(true).create(&dir).unwrap();
+
+    let mut main = File::create(dir.join("registry.xml")).unwrap();
+    write!(main, r#"<?xml version="1.0" encoding="UTF-8"?>
+        <registry xmlns:xi="http://www.w3.org/2001/XInclude">
+            <xi:include href="types.xml"/>
+            <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+                <require>
+                    <type name="VkIncluded"/>
+                </require>
+            </feature>
+        </registry>"#).unwrap();
+    let mut types = File::create(dir.join("types.xml")).unwrap();
+    write!(types, r#"<?xml version="1.0" encoding="UTF-8"?>
+        <types>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type category="struct" name="VkIncluded">
+                <member><type>uint32_t</type> <name>value</name></member>
+            </type>
+        </types>"#).unwrap();
+    let mut missing = File::create(dir.join("missing.xml")).unwrap();
+    write!(missing, r#"<registry xmlns:xi="http://www.w3.org/2001/XInclude"><xi:include href="nonexistent.xml"/></registry>"#).unwrap();
+    drop((main, types, missing));
+
+    let mut out = Vec::new();
+    VkRegistry::from_file(dir.join("registry.xml")).unwrap()
+        .gen_struct(&mut out, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("pub struct VkIncluded {\n    pub value: uint32_t,"));
+
+    let error = VkRegistry::from_file(dir.join("missing.xml")).err().unwrap();
+    assert!(error.to_string().contains("nonexistent.xml"), "{}", error);
+}
+
+#[test]
+fn from_file_errors() {
+    let dir = Path::new(env!("OUT_DIR")).join("from_file_errors");
+    DirBuilder::new().recursive(true).create(&dir).unwrap();
+
+    let mut malformed = File::create(dir.join("malformed.xml")).unwrap();
+    write!(malformed, r#"<registry><types><type category="struct" name="VkBroken"></types></registry>"#).unwrap();
+    let mut unexpected = File::create(dir.join("unexpected.xml")).unwrap();
+    write!(unexpected, r#"<registry><types><type category="nonsense" name="VkOdd"/></types></registry>"#).unwrap();
+    let mut stray = File::create(dir.join("stray.xml")).unwrap();
+    write!(stray, r#"<registry><feature api="vulkan" name="VK_VERSION_1_0" number="1.0"><command name="vkDoThing"/></feature></registry>"#).unwrap();
+    let mut extnumber = File::create(dir.join("extnumber.xml")).unwrap();
+    write!(extnumber, r#"<registry><extensions><extension name="VK_KHR_thing" number="1"><require><enum offset="0" extnumber="one" extends="VkResult" name="VK_THING_KHR"/></require></extension></extensions></registry>"#).unwrap();
+    drop((malformed, unexpected, stray, extnumber));
+
+    let error = VkRegistry::from_file(dir.join("malformed.xml")).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    let error = VkRegistry::from_file(dir.join("unexpected.xml")).err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("Unexpected category"), "{}", error);
+    let error = VkRegistry::from_file(dir.join("stray.xml")).err().unwrap();
+    assert!(error.to_string().contains("`vkDoThing` is outside of a <require> or <remove> block"), "{}", error);
+    let error = VkRegistry::from_file(dir.join("extnumber.xml")).err().unwrap();
+    assert!(error.to_string().contains("Invalid extension number one"), "{}", error);
+}
+
+#[test]
+fn registry_cache() {
+    const EXTENSIONS: &[&str] = &["VK_KHR_surface", "VK_KHR_swapchain", "VK_KHR_win32_surface"];
+    let config = GenConfig {
+        emit_docs: true,
+        ..GenConfig::default()
+    };
+    let cached = VkRegistry::from_cache(&VkRegistry::write_cache(vk_api::VK_XML)).unwrap();
+    let (mut from_cache, mut from_xml) = (Vec::new(), Vec::new());
+    cached.gen_struct(&mut from_cache, VkVersion(1, 0), EXTENSIONS, config).unwrap();
+    VkRegistry::new(vk_api::VK_XML).gen_struct(&mut from_xml, VkVersion(1, 0), EXTENSIONS, config).unwrap();
+    assert!(from_cache == from_xml);
+
+    let error = VkRegistry::from_cache(b"<registry/>").err().unwrap();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    let cache = VkRegistry::write_cache(br#"<registry><types><type category="nonsense" name="VkOdd"/></types></registry>"#);
+    let error = VkRegistry::from_cache(&cache).err().unwrap();
+    assert!(error.to_string().contains("Unexpected category"), "{}", error);
+}
+
+#[test]
+fn strict_bundled_registry() {
+    let mut out = Vec::new();
+    VkRegistry::new(vk_api::VK_XML)
+        .gen_struct(&mut out, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain"], GenConfig {
+            strict: true,
+            ..GenConfig::default()
+        }).unwrap();
+}
+
+#[test]
+fn strict_dangling_reference() {
+    // `VkMissing` has been removed from the registry, but `VkFoo` still refers to it.
+    let registry = VkRegistry::new(br#"<registry>
+        <types>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type category="struct" name="VkFoo">
+                <member><type>uint32_t</type> <name>count</name></member>
+                <member><type>VkMissing</type> <name>missing</name></member>
+            </type>
+        </types>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <type name="VkFoo"/>
+            </require>
+        </feature>
+    </registry>"#);
+    let mut out = Vec::new();
+    let error = registry.gen_struct(&mut out, VkVersion(1, 0), &[], GenConfig {
+        strict: true,
+        ..GenConfig::default()
+    }).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert!(error.to_string().contains("`VkFoo` references `VkMissing`"));
+    assert!(out.is_empty());
+}
+
+#[test]
+fn geometry_helpers() {
+    run_generated("geometry_helpers", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                geometry_helpers: true,
+                remove_type_prefix: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        let a = vk::Extent2D::new(640, 720);
+        let b = vk::Extent2D::new(1280, 480);
+        let min = a.min(b);
+        assert_eq!((min.width, min.height), (640, 480));
+        let max = a.max(b);
+        assert_eq!((max.width, max.height), (1280, 720));
+
+        let rect = vk::Rect2D::new(vk::Offset2D::new(-10, 10), a);
+        assert!(rect.contains(vk::Offset2D::new(-10, 10)));
+        assert!(!rect.contains(vk::Offset2D::new(630, 10)));
+        assert!(rect.contains_rect(&vk::Rect2D::new(vk::Offset2D::new(0, 20), vk::Extent2D::new(630, 700))));
+        assert!(!rect.contains_rect(&vk::Rect2D::new(vk::Offset2D::new(0, 20), vk::Extent2D::new(631, 700))));
+
+        let extent: vk::Extent3D = a.into();
+        assert_eq!((extent.width, extent.height, extent.depth), (640, 720, 1));
+        let offset = vk::Offset3D::from(vk::Offset2D::new(-10, 10));
+        assert_eq!((offset.x, offset.y, offset.z), (-10, 10, 0));
+        let rect: vk::Rect2D = b.into();
+        assert_eq!((rect.offset.x, rect.offset.y), (0, 0));
+        assert!(rect.contains(vk::Offset2D::new(1279, 479)));
+    "#);
+}
+
+#[test]
+fn macro_root() {
+    run_generated("macro_root", |file| {
+        writeln!(file, "pub mod nested {{").unwrap();
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                macro_root: Some("vk::nested"),
+                ..GenConfig::default()
+            }).unwrap();
+        writeln!(file, "}}").unwrap();
+    }, r#"
+        let version = vk_make_version!(1, 2, 3);
+        assert_eq!(version, (1 << 22) | (2 << 12) | 3);
+    "#);
+}
+
+#[test]
+fn version_const_fns() {
+    run_generated("version_const_fns", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                macro_root: Some("vk"),
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        const VERSION: u32 = vk::make_version(1, 2, 3);
+        assert_eq!(VERSION, vk_make_version!(1, 2, 3));
+        assert_eq!(vk::version_major(VERSION), 1);
+        assert_eq!(vk::version_minor(VERSION), 2);
+        assert_eq!(vk::version_patch(VERSION), 3);
+    "#);
+}
+
+#[test]
+fn variant_overrides() {
+    // With the type name stripped from both ends, `VK_FOO_BAR` and `VK_FOO_BAR_FOO` both become `Bar`.
+    const XML: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type name="VkFoo" category="enum"/>
+        </types>
+        <enums name="VkFoo" type="enum">
+            <enum value="0" name="VK_FOO_BAR"/>
+            <enum value="1" name="VK_FOO_BAR_FOO"/>
+            <enum value="2" name="VK_FOO_BAZ"/>
+        </enums>
+        <commands>
+            <command>
+                <proto><type>void</type> <name>vkUseFoo</name></proto>
+                <param><type>VkFoo</type> <name>foo</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkUseFoo"/>
+            </require>
+        </feature>
+    </registry>"#;
+
+    run_generated("variant_overrides", |file| {
+        VkRegistry::new(XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig {
+                variant_overrides: &[
+                    ("VkFoo", "VK_FOO_BAR_FOO", VariantAction::Rename("BarFoo")),
+                    ("VkFoo", "VK_FOO_BAZ", VariantAction::Remove)
+                ],
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        assert_eq!(vk::VkFoo::Bar as i32, 0);
+        assert_eq!(vk::VkFoo::BarFoo as i32, 1);
+    "#);
+
+    let mut out = Vec::new();
+    VkRegistry::new(XML).gen_global(&mut out, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("    Bar = ").count(), 2);
+}
+
+#[test]
+fn emit_self_tests() {
+    let out = env!("OUT_DIR");
+    DirBuilder::new().recursive(true).create(out).unwrap();
+
+    let mut file = File::create(Path::new(&out).join("emit_self_tests.rs")).unwrap();
+    writeln!(file, "#![allow(dead_code)]\nmod vk {{").unwrap();
+    VkRegistry::new(vk_api::VK_XML)
+        .gen_struct(&mut file, VkVersion(1, 0), &["VK_KHR_surface", "VK_EXT_debug_report"], GenConfig {
+            emit_self_tests: true,
+            ..GenConfig::default()
+        }).unwrap();
+    writeln!(file, "}}").unwrap();
+    drop(file);
+
+    let compile = Command::new("rustc").current_dir(out).args(["--test", "emit_self_tests.rs"]).output().unwrap();
+    if !compile.status.success() {
+        panic!("{}", String::from_utf8(compile.stderr).unwrap());
+    }
+
+    let run = Command::new(Path::new(&out).join("emit_self_tests").with_extension(env::consts::EXE_EXTENSION)).output().unwrap();
+    let stdout = String::from_utf8(run.stdout).unwrap();
+    if !run.status.success() {
+        panic!("{}", stdout);
+    }
+    assert!(stdout.contains("generated_abi_tests::struct_layouts ... ok"), "{}", stdout);
+}
+
+#[test]
+fn gen_by_feature_name() {
+    let registry = VkRegistry::new(vk_api::VK_XML);
+    assert_eq!(registry.feature_version("VK_VERSION_1_0"), Some(VkVersion(1, 0)));
+    assert_eq!(registry.feature_version("VK_VERSION_0_9"), None);
+
+    let (mut by_version, mut by_feature) = (Vec::new(), Vec::new());
+    registry.gen_struct(&mut by_version, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default()).unwrap();
+    registry.gen_struct_by_feature(&mut by_feature, "VK_VERSION_1_0", &["VK_KHR_surface"], GenConfig::default()).unwrap();
+    assert!(by_version == by_feature);
+
+    let (mut by_version, mut by_feature) = (Vec::new(), Vec::new());
+    registry.gen_global(&mut by_version, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    registry.gen_global_by_feature(&mut by_feature, "VK_VERSION_1_0", &[], GenConfig::default()).unwrap();
+    assert!(by_version == by_feature);
+}
+
+#[test]
+fn bitmask_default() {
+    run_generated("bitmask_default", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        // `VkBufferCreateFlags` is a plain `VkFlags` typedef; the wrapper is the `FlagBits` type.
+        assert!(vk::VkBufferCreateFlagBits::default().is_empty());
+        assert_eq!(vk::VkBufferCreateFlagBits::default(), vk::VkBufferCreateFlagBits::empty());
+    "#);
+}
+
+#[test]
+fn gen_stats() {
+    let mut out = Vec::new();
+    let stats = VkRegistry::new(vk_api::VK_XML)
+        .gen_struct(&mut out, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default()).unwrap();
+    assert_eq!(stats.bytes, out.len());
+    assert_eq!(stats.lines, out.iter().filter(|b| **b == b'\n').count());
+    assert!(stats.types > 0);
+    assert!(stats.commands > 0);
+}
+
+#[test]
+fn gen_timings() {
+    let start = Instant::now();
+    let registry = VkRegistry::new(vk_api::VK_XML);
+    let mut out = Vec::new();
+    let timings = registry.gen_global(&mut out, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default()).unwrap().timings;
+    let elapsed = start.elapsed();
+
+    let zero = Duration::from_secs(0);
+    assert_eq!(timings.crawl, registry.crawl_time());
+    assert!(timings.crawl > zero);
+    assert!(timings.select > zero);
+    assert!(timings.transform > zero);
+    assert!(timings.emit > zero);
+    assert!(timings.total() <= elapsed);
+}
+
+#[test]
+fn load_with_dyn() {
+    run_generated("load_with_dyn_struct", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        extern "system" fn fake() {}
+        let loader: Box<dyn Fn(&str) -> *const ()> = Box::new(|name| {
+            if "vkCreateInstance" == name {fake as *const ()} else {std::ptr::null()}
+        });
+
+        let mut vk = vk::Vk::new();
+        let unloaded = vk.load_with_dyn(&*loader).unwrap_err();
+        assert_eq!(unloaded.len(), vk::Vk::COMMAND_OFFSETS.len() - 1);
+        assert!(!unloaded.contains(&"vkCreateInstance"));
+    "#);
+
+    run_generated("load_with_dyn_global", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        extern "system" fn fake() {}
+        let loader: Box<dyn Fn(&str) -> *const ()> = Box::new(|name| {
+            if "vkCreateInstance" == name {fake as *const ()} else {std::ptr::null()}
+        });
+
+        let unloaded = vk::cmds::load_with_dyn(&*loader).unwrap_err();
+        assert!(!unloaded.contains(&"vkCreateInstance"));
+        assert!(unloaded.contains(&"vkDestroyInstance"));
+    "#);
+}
+
+#[test]
+fn handle_object_types() {
+    // The bundled registry predates `objtypeenum`.
+    const XML: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type requires="vk_platform" name="uint64_t"/>
+            <type category="handle" objtypeenum="VK_OBJECT_TYPE_INSTANCE"><type>VK_DEFINE_HANDLE</type>(<name>VkInstance</name>)</type>
+            <type category="handle" objtypeenum="VK_OBJECT_TYPE_FENCE"><type>VK_DEFINE_NON_DISPATCHABLE_HANDLE</type>(<name>VkFence</name>)</type>
+            <type name="VkObjectType" category="enum"/>
+        </types>
+        <enums name="VkObjectType" type="enum">
+            <enum value="0" name="VK_OBJECT_TYPE_UNKNOWN"/>
+            <enum value="1" name="VK_OBJECT_TYPE_INSTANCE"/>
+            <enum value="7" name="VK_OBJECT_TYPE_FENCE"/>
+        </enums>
+        <commands>
+            <command>
+                <proto><type>void</type> <name>vkUseHandles</name></proto>
+                <param><type>VkInstance</type> <name>instance</name></param>
+                <param><type>VkFence</type> <name>fence</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <type name="uint64_t"/>
+                <type name="VkObjectType"/>
+                <command name="vkUseHandles"/>
+            </require>
+        </feature>
+    </registry>"#;
+
+    run_generated("handle_object_types", |file| {
+        VkRegistry::new(XML).gen_struct(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        use vk::HasObjectType;
+        assert!(vk::VkInstance::OBJECT_TYPE == vk::VkObjectType::Instance);
+        assert!(vk::VkFence::OBJECT_TYPE == vk::VkObjectType::Fence);
+    "#);
+
+    run_generated("handle_object_types_consts", |file| {
+        VkRegistry::new(XML).gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+            use_native_enums: false,
+            variant_padding: VariantPaddingConfig::RemovePrefix,
+            camel_case_variants: false,
+            ..GenConfig::default()
+        }).unwrap();
+    }, r#"
+        use vk::HasObjectType;
+        assert_eq!(vk::VkInstance::OBJECT_TYPE, vk::OBJECT_TYPE_INSTANCE);
+        assert_eq!(vk::VkFence::OBJECT_TYPE, 7);
+    "#);
+}
+
+#[test]
+fn handle_trait() {
+    let config = GenConfig {
+        handle_trait: true,
+        ..GenConfig::default()
+    };
+
+    run_generated("handle_trait", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_EXT_debug_report"], config).unwrap();
+    }, r#"
+        fn describe<H: vk::VkHandle>(handle: H) -> (vk::VkObjectType, u64) {
+            (H::OBJECT_TYPE, handle.as_raw())
+        }
+
+        let instance = 0x10 as vk::VkInstance;
+        let fence: vk::VkFence = unsafe{ std::mem::transmute(0x20u64) };
+        assert!(describe(instance) == (vk::VkObjectType::Instance, 0x10));
+        assert!(describe(fence) == (vk::VkObjectType::Fence, 0x20));
+        assert!(<vk::VkSurfaceKHR as vk::VkHandle>::OBJECT_TYPE == vk::VkObjectType::SurfaceKhr);
+    "#);
+
+    // Every handle gets an impl, even though the bundled registry predates `objtypeenum`.
+    let mut out = Vec::new();
+    VkRegistry::new(vk_api::VK_XML)
+        .gen_struct(&mut out, VkVersion(1, 0), &["VK_KHR_surface", "VK_EXT_debug_report"], config).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let handles = out.matches("handle_nondispatchable!(").count() + out.matches("_T (u8);").count();
+    assert_eq!(out.matches("impl VkHandle for ").count(), handles);
+    assert!(!out.contains("HasObjectType"));
+}
+
+#[test]
+fn load_reporting() {
+    let modes = [
+        (LoadReporting::Full, "Vec<&'static str>", "assert_eq!(unloaded.len(), vk::Vk::COMMAND_OFFSETS.len() - 1);"),
+        (LoadReporting::CountOnly, "usize", "assert_eq!(unloaded, vk::Vk::COMMAND_OFFSETS.len() - 1);"),
+        (LoadReporting::None, "()", "")
+    ];
+
+    for &(mode, error_type, check) in &modes {
+        run_generated(&format!("load_reporting_{:?}", mode), |file| {
+            VkRegistry::new(vk_api::VK_XML)
+                .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                    load_reporting: mode,
+                    ..GenConfig::default()
+                }).unwrap();
+        }, &format!(r#"
+            extern "system" fn fake() {{}}
+            let mut vk = vk::Vk::new();
+            let loaded: Result<(), {}> = vk.load_with(|_| fake as *const ());
+            assert!(loaded.is_ok());
+
+            let mut vk = vk::Vk::new();
+            let result: Result<(), {0}> = vk.load_with(|name| if "vkCreateInstance" == name {{fake as *const ()}} else {{std::ptr::null()}});
+            let unloaded = result.unwrap_err();
+            {}
+        "#, error_type, check));
+    }
+}
+
+#[test]
+fn gen_raw() {
+    run_generated("gen_raw", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_raw(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default()).unwrap();
+    }, r#"
+        extern "system" fn fake() {}
+        let fns = vk::load(|name| if "vkCreateInstance" == name {fake as *const ()} else {std::ptr::null()});
+        assert_eq!(fns.len(), vk::COMMAND_NAMES.len());
+
+        let create_instance = vk::COMMAND_NAMES.iter().position(|n| *n == "vkCreateInstance").unwrap();
+        assert_eq!(fns[create_instance], fake as *const ());
+        assert_eq!(fns.iter().filter(|f| !f.is_null()).count(), 1);
+        assert!(vk::COMMAND_NAMES.contains(&"vkDestroySurfaceKHR"));
+
+        let _: Option<vk::PFN_vkCreateInstance> = None;
+    "#);
+
+    let mut out = Vec::new();
+    VkRegistry::new(vk_api::VK_XML)
+        .gen_raw(&mut out, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("pub const COMMAND_NAMES: ["));
+    assert!(!out.contains("pub struct Vk "));
+    assert!(!out.contains("pub unsafe extern \"system\" fn create_instance"));
+}
+
+#[test]
+fn newtype_enum_by_value() {
+    run_generated("newtype_enum_by_value", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                use_native_enums: false,
+                newtype_enums: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use std::collections::HashMap;
+
+        let format = vk::VkFormat::R8g8b8a8Unorm;
+        let copied = format;
+        assert_eq!(format, copied);
+        assert_eq!(std::mem::size_of::<vk::VkFormat>(), std::mem::size_of::<i32>());
+
+        let mut sizes = HashMap::new();
+        sizes.insert(format, 4);
+        sizes.insert(vk::VkFormat::R8Unorm, 1);
+        assert_eq!(sizes[&copied], 4);
+        assert_eq!(sizes[&vk::VkFormat::R8Unorm], 1);
+    "#);
+}
+
+#[test]
+fn per_command_success_checks() {
+    let main_body = r#"
+        // `vkEnumeratePhysicalDevices` documents `VK_INCOMPLETE` as a success code, but `vkCreateInstance` doesn't.
+        assert!(vk::enumerate_physical_devices_is_success(vk::VkResult::Success));
+        assert!(vk::enumerate_physical_devices_is_success(vk::VkResult::Incomplete));
+        assert!(!vk::enumerate_physical_devices_is_success(vk::VkResult::ErrorInitializationFailed));
+        assert!(vk::create_instance_is_success(vk::VkResult::Success));
+        assert!(!vk::create_instance_is_success(vk::VkResult::Incomplete));
+    "#;
+    let config = GenConfig {
+        per_command_success_checks: true,
+        ..GenConfig::default()
+    };
+
+    run_generated("per_command_success_checks_struct", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_struct(file, VkVersion(1, 0), &[], config).unwrap();
+    }, main_body);
+    run_generated("per_command_success_checks_global", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_global(file, VkVersion(1, 0), &[], config).unwrap();
+    }, main_body);
+}
+
+#[test]
+fn consts_module() {
+    run_generated("consts_module", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig {
+                consts_module: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        let _: vk::types::VkInstance = std::ptr::null_mut();
+        let _: unsafe extern "system" fn(vk::VkInstance, *const vk::VkAllocationCallbacks) = vk::cmds::destroy_instance;
+        assert_eq!(vk::consts::MAX_EXTENSION_NAME_SIZE, 256);
+        assert_eq!(vk::MAX_EXTENSION_NAME_SIZE, vk::consts::MAX_EXTENSION_NAME_SIZE);
+    "#);
+}
+
+#[test]
+fn result_severity() {
+    run_generated("result_severity_native", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain"], GenConfig {
+                result_severity: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        assert_eq!(vk::severity(vk::VkResult::Success), vk::ResultSeverity::Success);
+        assert_eq!(vk::severity(vk::VkResult::SuboptimalKhr), vk::ResultSeverity::Warning);
+        assert_eq!(vk::severity(vk::VkResult::Incomplete), vk::ResultSeverity::Warning);
+        assert_eq!(vk::severity(vk::VkResult::ErrorDeviceLost), vk::ResultSeverity::Error);
+        assert!(matches!(vk::severity(vk::VkResult::ErrorOutOfDateKhr), vk::ResultSeverity::Error));
+    "#);
+    run_generated("result_severity_consts", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain"], GenConfig {
+                result_severity: true,
+                remove_vk_result_prefix: false,
+                variant_padding: VariantPaddingConfig::Keep,
+                camel_case_variants: false,
+                use_native_enums: false,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        assert_eq!(vk::severity(vk::VK_SUBOPTIMAL_KHR), vk::ResultSeverity::Warning);
+        assert_eq!(vk::severity(vk::VK_ERROR_DEVICE_LOST), vk::ResultSeverity::Error);
+        assert_eq!(vk::severity(vk::VK_SUCCESS), vk::ResultSeverity::Success);
+    "#);
+}
+
+#[test]
+fn clear_value_layout() {
+    let main_body = r#"
+        assert_eq!(std::mem::size_of::<vk::VkClearValue>(), 16);
+        assert_eq!(std::mem::align_of::<vk::VkClearValue>(), 4);
+        assert_eq!(std::mem::size_of::<vk::VkClearColorValue>(), 16);
+        assert_eq!(std::mem::align_of::<vk::VkClearColorValue>(), 4);
+    "#;
+
+    for &native in &[true, false] {
+        run_generated(if native {"clear_value_layout_native"} else {"clear_value_layout_hardcoded"}, |file| {
+            VkRegistry::new(vk_api::VK_XML)
+                .gen_global(file, VkVersion(1, 0), &[], GenConfig {
+                    use_native_unions: native,
+                    ..GenConfig::default()
+                }).unwrap();
+        }, main_body);
+    }
+}
+
+#[test]
+fn types_subset_enums_and_consts() {
+    let config = GenConfig {
+        types_subset: TypesSubset::EnumsAndConstsOnly,
+        ..GenConfig::default()
+    };
+    run_generated("types_subset_enums_and_consts", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_types_only(file, VkVersion(1, 0), &["VK_KHR_surface"], config).unwrap();
+    }, r#"
+        assert_eq!(vk::MAX_EXTENSION_NAME_SIZE, 256);
+        let _ = vk::VkFormat::R8g8b8a8Unorm;
+        let _ = vk::BUFFER_USAGE_TRANSFER_SRC_BIT | vk::BUFFER_USAGE_INDEX_BUFFER_BIT;
+    "#);
+
+    let mut out = Vec::new();
+    let stats = VkRegistry::new(vk_api::VK_XML)
+        .gen_types_only(&mut out, VkVersion(1, 0), &["VK_KHR_surface"], config).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(stats.commands, 0);
+    assert!(out.contains("pub enum VkFormat {"));
+    assert!(!out.contains("pub struct VkInstanceCreateInfo {"));
+    assert!(!out.contains("pub struct VkExtent2D {"));
+    assert!(!out.contains("pub union VkClearValue {"));
+    assert!(!out.contains("pub struct VkClearValue {"));
+    assert!(!out.contains("create_instance"));
+}
+
+#[test]
+fn limit_accessors() {
+    run_generated("limit_accessors", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig {
+                limit_accessors: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        let mut limits: vk::VkPhysicalDeviceLimits = unsafe{ std::mem::zeroed() };
+        limits.max_compute_work_group_count = [65535, 16, 1];
+        limits.point_size_range = [1.0, 64.0];
+        assert_eq!(limits.max_compute_work_group_count_x(), 65535);
+        assert_eq!(limits.max_compute_work_group_count_y(), 16);
+        assert_eq!(limits.max_compute_work_group_count_z(), 1);
+        assert_eq!(limits.point_size_range_min(), 1.0);
+        assert_eq!(limits.point_size_range_max(), 64.0);
+    "#);
+}
+
+#[test]
+fn promoted_enum_aliases() {
+    // `VK_FOO_BAZ` was promoted out of `VK_KHR_foo`, leaving the old name behind as an alias.
+    const XML: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type name="VkFoo" category="enum"/>
+        </types>
+        <enums name="VkFoo" type="enum">
+            <enum value="0" name="VK_FOO_BAR"/>
+            <enum value="1" name="VK_FOO_BAZ"/>
+        </enums>
+        <commands>
+            <command>
+                <proto><type>void</type> <name>vkUseFoo</name></proto>
+                <param><type>VkFoo</type> <name>foo</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkUseFoo"/>
+            </require>
+        </feature>
+        <extensions>
+            <extension name="VK_KHR_foo" number="1" supported="vulkan">
+                <require>
+                    <enum extends="VkFoo" name="VK_FOO_BAZ_KHR" alias="VK_FOO_BAZ"/>
+                </require>
+            </extension>
+        </extensions>
+    </registry>"#;
+    let main_body = r#"
+        let baz: vk::VkFoo = vk::VkFoo::BazKhr;
+        assert_eq!(baz, vk::VkFoo::Baz);
+        match baz {
+            vk::VkFoo::BazKhr => (),
+            _                 => panic!("alias didn't match")
+        }
+    "#;
+
+    run_generated("promoted_enum_aliases_native", |file| {
+        VkRegistry::new(XML).gen_global(file, VkVersion(1, 0), &["VK_KHR_foo"], GenConfig::default()).unwrap();
+    }, main_body);
+    run_generated("promoted_enum_aliases_newtype", |file| {
+        VkRegistry::new(XML).gen_global(file, VkVersion(1, 0), &["VK_KHR_foo"], GenConfig {
+            use_native_enums: false,
+            newtype_enums: true,
+            ..GenConfig::default()
+        }).unwrap();
+    }, main_body);
+}
+
+#[test]
+fn types_by_category() {
+    let registry = VkRegistry::new(vk_api::VK_XML);
+    let categories = registry.types_by_category();
+    assert!(!categories.structs.is_empty());
+    assert!(categories.structs.contains(&"VkInstanceCreateInfo"));
+    assert!(categories.unions.contains(&"VkClearValue"));
+    assert!(categories.enums.contains(&"VkFormat"));
+    assert!(categories.bitmasks.contains(&"VkBufferUsageFlagBits"));
+    assert!(categories.handles.contains(&"VkInstance"));
+    assert!(categories.typedefs.contains(&"VkBufferUsageFlags"));
+    assert!(categories.funcpointers.contains(&"PFN_vkAllocationFunction"));
+    assert!(categories.externs.contains(&"uint32_t"));
+    assert!(!categories.structs.contains(&"VkFormat"));
+
+    let mut sorted = categories.structs.clone();
+    sorted.sort();
+    assert_eq!(sorted, categories.structs);
+}
+
+#[test]
+fn handle_debug_type_name() {
+    run_generated("handle_debug_type_name", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        let buffer: vk::VkBuffer = unsafe{ std::mem::transmute(0x2au64) };
+        assert_eq!(format!("{:?}", buffer), "VkBuffer(0x2a)");
+        assert_eq!(format!("{}", buffer), "VkBuffer(0x2a)");
+        assert_eq!(format!("{:p}", buffer), "0x2a");
+    "#);
+}
+
+#[test]
+fn handle_raw_conversions() {
+    run_generated("handle_raw_conversions", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        use std::mem::{size_of, align_of};
+
+        assert_eq!(size_of::<vk::VkBuffer>(), size_of::<u64>());
+        assert_eq!(align_of::<vk::VkBuffer>(), align_of::<u64>());
+        assert!(vk::VkBuffer::NULL.is_null());
+        assert_eq!(vk::VkBuffer::null(), vk::VkBuffer::from_raw(0));
+
+        let buffer = vk::VkBuffer::from_raw(0x2a);
+        assert!(!buffer.is_null());
+        assert_eq!(buffer.as_raw(), 0x2a);
+        let zeroed: vk::VkBuffer = unsafe{ std::mem::zeroed() };
+        assert_eq!(zeroed, vk::VkBuffer::NULL);
+    "#);
+}
+
+#[test]
+fn niche_handles() {
+    let main_body = r#"
+        use std::mem::size_of;
+
+        assert_eq!(size_of::<Option<vk::VkBuffer>>(), size_of::<vk::VkBuffer>());
+        assert_eq!(size_of::<vk::VkBuffer>(), size_of::<u64>());
+        assert!(vk::VkBuffer::from_raw(0).is_none());
+        let buffer = vk::VkBuffer::from_raw(0x2a).unwrap();
+        assert_eq!(buffer.as_raw(), 0x2a);
+        assert_eq!(format!("{:?}", buffer), "VkBuffer(0x2a)");
+
+        // `VK_NULL_HANDLE` is `None`, so zeroed structs hold null handles.
+        let info: vk::VkDescriptorBufferInfo = unsafe{ std::mem::zeroed() };
+        assert!(info.buffer.is_none());
+        let _: unsafe extern "system" fn(vk::VkDevice, Option<vk::VkBuffer>, *const vk::VkAllocationCallbacks) = vk::destroy_buffer;
+    "#;
+
+    run_generated("niche_handles_global", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig {
+                niche_handles: true,
+                handle_trait: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, main_body);
+    run_generated("niche_handles_struct", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig {
+                niche_handles: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        assert_eq!(std::mem::size_of::<Option<vk::VkSurfaceKHR>>(), std::mem::size_of::<u64>());
+        let info: vk::VkDescriptorBufferInfo = unsafe{ std::mem::zeroed() };
+        assert!(info.buffer.is_none());
+    "#);
+}
+
+#[test]
+fn struct_new_initializes_every_command() {
+    run_generated("struct_new_initializes_every_command", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default()).unwrap();
+    }, r#"
+        // Commands are only reported as unloaded if they still point at `unloaded_function_panic`.
+        let mut vk = vk::Vk::new();
+        let unloaded = vk.load_with(|_| std::ptr::null()).unwrap_err();
+        assert_eq!(unloaded.len(), vk::Vk::COMMAND_OFFSETS.len());
+        assert!(unloaded.contains(&"vkDestroySurfaceKHR"));
+    "#);
+}
+
+#[test]
+fn all_extension_names() {
+    run_generated("all_extension_names", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain", "VK_EXT_debug_report"], GenConfig::default()).unwrap();
+    }, r#"
+        assert_eq!(vk::ALL_EXTENSION_NAMES.len(), 3);
+        assert!(vk::ALL_EXTENSION_NAMES.contains(&vk::KHR_SURFACE_EXTENSION_NAME));
+        assert!(vk::ALL_EXTENSION_NAMES.contains(&"VK_KHR_surface"));
+    "#);
+
+    run_generated("all_extension_names_none", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        assert!(vk::ALL_EXTENSION_NAMES.is_empty());
+    "#);
+}
+
+#[test]
+fn safe_slice_wrappers() {
+    run_generated("safe_slice_wrappers", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                safe_slice_wrappers: true,
+                bool_params: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use std::{mem, ptr};
+
+        static mut UPDATED: (u32, usize, u32, usize) = (0, 0, 0, 0);
+        unsafe extern "system" fn fake_update(_: vk::VkDevice, write_count: u32, p_writes: *const vk::VkWriteDescriptorSet,
+                                              copy_count: u32, p_copies: *const vk::VkCopyDescriptorSet) {
+            UPDATED = (write_count, p_writes as usize, copy_count, p_copies as usize);
+        }
+        static mut WAITED: (u32, bool) = (0, false);
+        unsafe extern "system" fn fake_wait(_: vk::VkDevice, fence_count: u32, p_fences: *const vk::VkFence, wait_all: vk::VkBool32, _: u64) -> vk::VkResult {
+            WAITED = (fence_count, !p_fences.is_null() && wait_all == vk::TRUE);
+            vk::VkResult::Success
+        }
+
+        let mut vk = vk::Vk::new();
+        vk.load_with(|name| match name {
+            "vkUpdateDescriptorSets" => fake_update as *const (),
+            "vkWaitForFences"        => fake_wait as *const (),
+            _                        => ptr::null()
+        }).unwrap_err();
+
+        let writes: [vk::VkWriteDescriptorSet; 2] = unsafe{ mem::zeroed() };
+        unsafe {
+            vk.update_descriptor_sets_slice(ptr::null_mut(), &writes, &[]);
+            assert_eq!(UPDATED, (2, writes.as_ptr() as usize, 0, 0));
+
+            let fences = [vk::VkFence::null(); 3];
+            assert_eq!(vk.wait_for_fences_slice(ptr::null_mut(), &fences, true, 0), vk::VkResult::Success);
+            assert_eq!(WAITED, (3, true));
+
+            // The raw command is still there.
+            vk.update_descriptor_sets(ptr::null_mut(), 1, writes.as_ptr(), 0, ptr::null());
+            assert_eq!(UPDATED.0, 1);
+        }
+
+        let _: unsafe fn(&vk::Vk, vk::VkCommandBuffer, u32, &[vk::VkViewport]) = vk::Vk::cmd_set_viewport_slice;
+        let _: unsafe fn(&vk::Vk, vk::VkDevice, vk::VkDescriptorPool, &[vk::VkDescriptorSet]) -> vk::VkResult = vk::Vk::free_descriptor_sets_slice;
+    "#);
+}
+
+#[test]
+fn bool_params() {
+    let fake = r#"
+        static mut WAIT_ALL: vk::VkBool32 = 2;
+        unsafe extern "system" fn fake_wait(_: vk::VkDevice, _: u32, _: *const vk::VkFence, wait_all: vk::VkBool32, _: u64) -> vk::VkResult {
+            WAIT_ALL = wait_all;
+            vk::VkResult::Success
+        }
+        let load = |name: &str| if "vkWaitForFences" == name {fake_wait as *const ()} else {std::ptr::null()};
+    "#;
+    let config = GenConfig {
+        bool_params: true,
+        ..GenConfig::default()
+    };
+
+    run_generated("bool_params_global", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_global(file, VkVersion(1, 0), &[], config).unwrap();
+    }, &format!("{}{}", fake, r#"
+        vk::load_with(load).unwrap_err();
+        let _: unsafe extern "system" fn(vk::VkDevice, u32, *const vk::VkFence, bool, u64) -> vk::VkResult = vk::wait_for_fences;
+        unsafe {
+            vk::wait_for_fences(std::ptr::null_mut(), 0, std::ptr::null(), true, 0);
+            assert_eq!(WAIT_ALL, vk::TRUE);
+            vk::wait_for_fences(std::ptr::null_mut(), 0, std::ptr::null(), false, 0);
+            assert_eq!(WAIT_ALL, vk::FALSE);
+        }
+    "#));
+    run_generated("bool_params_struct", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_struct(file, VkVersion(1, 0), &[], config).unwrap();
+    }, &format!("{}{}", fake, r#"
+        let mut vk = vk::Vk::new();
+        vk.load_with(load).unwrap_err();
+        unsafe {
+            vk.wait_for_fences(std::ptr::null_mut(), 0, std::ptr::null(), true, 0);
+            assert_eq!(WAIT_ALL, vk::TRUE);
+        }
+    "#));
+}
+
+#[test]
+fn lookup_type() {
+    let registry = VkRegistry::new(vk_api::VK_XML);
+
+    let info = registry.lookup_type("VkImageCreateInfo").unwrap();
+    assert_eq!(info.kind, VkTypeKind::Struct);
+    let names: Vec<_> = info.fields.iter().map(|f| f.name).collect();
+    assert_eq!(&names[..6], &["sType", "pNext", "flags", "imageType", "format", "extent"]);
+    assert_eq!(info.fields[1].type_name, "void");
+    assert_eq!((info.fields[1].is_const, info.fields[1].pointer_depth), (true, 1));
+    assert_eq!(info.fields[5].type_name, "VkExtent3D");
+
+    let properties = registry.lookup_type("VkPhysicalDeviceProperties").unwrap();
+    let device_name = properties.fields.iter().find(|f| f.name == "deviceName").unwrap();
+    assert_eq!((device_name.type_name, device_name.array_len), ("char", Some(256)));
+
+    assert_eq!(registry.lookup_type("VkBufferUsageFlags").unwrap().aliases, Some("VkFlags"));
+    assert!(registry.lookup_type("VkFormat").unwrap().variants.contains(&"VK_FORMAT_R8G8B8A8_UNORM"));
+    assert!(registry.lookup_type("VkNotAType").is_none());
+}
+
+#[test]
+fn version_patch() {
+    const XML: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type requires="vk_platform" name="uint32_t"/>
+        </types>
+        <commands>
+            <command>
+                <proto><type>void</type> <name>vkOldCommand</name></proto>
+                <param><type>uint32_t</type> <name>count</name></param>
+            </command>
+            <command>
+                <proto><type>void</type> <name>vkNewCommand</name></proto>
+                <param><type>uint32_t</type> <name>count</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkOldCommand"/>
+            </require>
+        </feature>
+        <feature api="vulkan" name="VK_VERSION_1_1" number="1.1.0">
+            <require>
+                <command name="vkNewCommand"/>
+            </require>
+        </feature>
+    </registry>"#;
+
+    let registry = VkRegistry::new(XML);
+    assert_eq!(registry.feature_version("VK_VERSION_1_1"), Some(VkVersion(1, 1)));
+
+    let gen = |version| {
+        let mut out = Vec::new();
+        registry.gen_global(&mut out, version, &[], GenConfig::default()).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    assert!(!gen(VkVersion(1, 0)).contains("vkNewCommand"));
+    let v1_1 = gen(VkVersion(1, 1));
+    assert!(v1_1.contains("vkNewCommand"));
+
+    let dir = Path::new(env!("OUT_DIR")).join("version_patch");
+    DirBuilder::new().recursive(true).create(&dir).unwrap();
+    for &number in &["1", "1.x", "1.1.0.0"] {
+        let mut file = File::create(dir.join("invalid.xml")).unwrap();
+        write!(file, r#"<registry><feature api="vulkan" name="VK_VERSION_1_1" number="{}"/></registry>"#, number).unwrap();
+        drop(file);
+        let error = VkRegistry::from_file(dir.join("invalid.xml")).err().unwrap();
+        assert!(error.to_string().contains(&format!("Invalid feature number {}", number)), "{}", error);
+    }
+}
+
+#[test]
+fn structs_implement_debug() {
+    run_generated("structs_implement_debug", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        use std::mem;
+
+        // Raw pointer fields
+        let info: vk::VkInstanceCreateInfo = unsafe{ mem::zeroed() };
+        assert!(format!("{:?}", info).contains("p_application_info: 0x0"));
+        // Arrays longer than 32 elements
+        let props: vk::VkPhysicalDeviceProperties = unsafe{ mem::zeroed() };
+        assert!(format!("{:?}", props).starts_with("VkPhysicalDeviceProperties"));
+        let value: vk::VkClearColorValue = unsafe{ mem::zeroed() };
+        format!("{:?}", value);
+    "#);
+}
+
+#[test]
+fn feature_presets() {
+    run_generated("feature_presets", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                feature_presets: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        assert_eq!(vk::FEATURES_NONE.geometry_shader, vk::FALSE);
+        assert_eq!(vk::FEATURES_NONE.sparse_binding, vk::FALSE);
+        assert_eq!(vk::FEATURES_ALL.geometry_shader, vk::TRUE);
+
+        let mut features = vk::FEATURES_NONE;
+        features.sampler_anisotropy = vk::TRUE;
+        assert_eq!(features.robust_buffer_access, vk::FALSE);
+    "#);
+}
+
+#[test]
+fn derive_default() {
+    let body = r#"
+        let info = vk::VkApplicationInfo {
+            api_version: 1 << 22,
+            ..Default::default()
+        };
+        assert!(info.s_type == STRUCTURE_TYPE_APPLICATION_INFO);
+        assert!(info.p_next.is_null() && info.p_application_name.is_null());
+        assert_eq!(info.api_version, 1 << 22);
+
+        let create_info: vk::VkInstanceCreateInfo = Default::default();
+        assert!(create_info.s_type == STRUCTURE_TYPE_INSTANCE_CREATE_INFO);
+        assert!(create_info.pp_enabled_extension_names.is_null());
+
+        // `deviceName` is sized by an API constant
+        let props = vk::VkPhysicalDeviceProperties::default();
+        assert_eq!(&props.device_name[..], &[0; 256][..]);
+        let _ = vk::VkExtent2D::default();
+    "#;
+
+    run_generated("derive_default", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                derive_default: true,
+                snake_case_members: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, &format!("
+        const STRUCTURE_TYPE_APPLICATION_INFO: vk::VkStructureType = vk::VkStructureType::ApplicationInfo;
+        const STRUCTURE_TYPE_INSTANCE_CREATE_INFO: vk::VkStructureType = vk::VkStructureType::InstanceCreateInfo;
+        {}", body));
+
+    run_generated("derive_default_consts", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                derive_default: true,
+                snake_case_members: true,
+                use_native_enums: false,
+                variant_padding: VariantPaddingConfig::Keep,
+                camel_case_variants: false,
+                ..GenConfig::default()
+            }).unwrap();
+    }, &format!("
+        use vk::{{VK_STRUCTURE_TYPE_APPLICATION_INFO as STRUCTURE_TYPE_APPLICATION_INFO, VK_STRUCTURE_TYPE_INSTANCE_CREATE_INFO as STRUCTURE_TYPE_INSTANCE_CREATE_INFO}};
+        {}", body));
+}
+
+#[test]
+fn param_docs() {
+    let config = GenConfig {
+        param_docs: true,
+        ..GenConfig::default()
+    };
+    run_generated("param_docs_global", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_global(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain"], config).unwrap();
+    }, "");
+    run_generated("param_docs_struct", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain"], config).unwrap();
+    }, "");
+
+    let mut out = Vec::new();
+    VkRegistry::new(vk_api::VK_XML).gen_struct(&mut out, VkVersion(1, 0), &[], config).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("    /// Externally synchronized: `queue`, `p_submits[].p_wait_semaphores[]`, `p_submits[].p_signal_semaphores[]`, `fence`\n    \"vkQueueSubmit\""));
+}
+
+#[test]
+fn emit_docs() {
+    const XML: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type name="VkFoo" category="enum"/>
+        </types>
+        <enums name="VkFoo" type="enum" comment="Kinds of foo">
+            <enum value="0" name="VK_FOO_BAR" comment="A plain bar"/>
+            <enum value="1" name="VK_FOO_BAZ" comment="Ends a C comment */ and has a \ backslash, &quot;quotes&quot; and&#10;two lines"/>
+        </enums>
+        <commands>
+            <command comment="Uses a foo">
+                <proto><type>void</type> <name>vkUseFoo</name></proto>
+                <param><type>VkFoo</type> <name>foo</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkUseFoo"/>
+            </require>
+        </feature>
+    </registry>"#;
+    let config = GenConfig {
+        emit_docs: true,
+        ..GenConfig::default()
+    };
+
+    run_generated("emit_docs", |file| {
+        VkRegistry::new(XML).gen_struct(file, VkVersion(1, 0), &[], config).unwrap();
+    }, r#"
+        assert_eq!(vk::VkFoo::Baz as i32, 1);
+    "#);
+
+    let mut out = Vec::new();
+    VkRegistry::new(XML).gen_global(&mut out, VkVersion(1, 0), &[], config).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("#[doc = \" Kinds of foo\"]\n#[repr(C)]"));
+    assert!(out.contains("    #[doc = \" A plain bar\"]\n    Bar = 0,"));
+    assert!(out.contains(r#"    #[doc = " Ends a C comment */ and has a \\ backslash, \"quotes\" and\ntwo lines"]"#));
+    assert!(out.contains("    #[doc = \" Uses a foo\"]\n    \"vkUseFoo\""));
+
+    run_generated("emit_docs_registry", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_global(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain"], GenConfig {
+            use_native_enums: false,
+            variant_padding: VariantPaddingConfig::Keep,
+            camel_case_variants: false,
+            ..config
+        }).unwrap();
+    }, "");
+}
+
+#[test]
+fn type_and_command_aliases() {
+    const XML: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type category="handle"><type>VK_DEFINE_HANDLE</type>(<name>VkDevice</name>)</type>
+            <type name="VkFoo" category="enum"/>
+            <type category="enum" name="VkFooKHR" alias="VkFoo"/>
+            <type category="struct" name="VkBar">
+                <member><type>VkFoo</type> <name>foo</name></member>
+                <member><type>uint32_t</type> <name>count</name></member>
+            </type>
+            <type category="struct" name="VkBarKHR" alias="VkBar"/>
+        </types>
+        <enums name="VkFoo" type="enum">
+            <enum value="0" name="VK_FOO_ZERO"/>
+            <enum value="1" name="VK_FOO_ONE"/>
+        </enums>
+        <commands>
+            <command>
+                <proto><type>uint32_t</type> <name>vkGetBar</name></proto>
+                <param><type>VkDevice</type> <name>device</name></param>
+                <param><type>VkBar</type>* <name>pBar</name></param>
+            </command>
+            <command name="vkGetBarKHR" alias="vkGetBar"/>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkGetBar"/>
+            </require>
+        </feature>
+        <extensions>
+            <extension name="VK_KHR_bar" number="1" supported="vulkan">
+                <require>
+                    <type name="VkFooKHR"/>
+                    <type name="VkBarKHR"/>
+                    <command name="vkGetBarKHR"/>
+                </require>
+            </extension>
+        </extensions>
+    </registry>"#;
+
+    let registry = VkRegistry::new(XML);
+    let bar_khr = registry.lookup_type("VkBarKHR").unwrap();
+    assert_eq!((bar_khr.kind, bar_khr.aliases), (VkTypeKind::Alias, Some("VkBar")));
+    assert_eq!(registry.types_by_category().aliases, ["VkBarKHR", "VkFooKHR"]);
+
+    run_generated("type_and_command_aliases", |file| {
+        VkRegistry::new(XML).gen_global(file, VkVersion(1, 0), &["VK_KHR_bar"], GenConfig {
+            remove_type_prefix: true,
+            strict: true,
+            ..GenConfig::default()
+        }).unwrap();
+    }, r#"
+        let bar = vk::BarKHR {
+            foo: vk::FooKHR::One,
+            count: 2
+        };
+        let _: vk::Bar = bar;
+        let _: unsafe extern "system" fn(vk::Device, *mut vk::Bar) -> u32 = vk::get_bar_khr;
+        assert_eq!(vk::get_bar_khr::RAW_NAME, "vkGetBarKHR");
+    "#);
+}
+
+#[test]
+fn extension_enum_values() {
+    run_generated("extension_enum_values", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain", "VK_KHR_maintenance1", "VK_EXT_debug_report"], GenConfig::default()).unwrap();
+    }, r#"
+        assert_eq!(vk::VkResult::ErrorOutOfPoolMemoryKhr as i32, -1000069000);
+        assert_eq!(vk::VkResult::ErrorOutOfDateKhr as i32, -1000001004);
+        assert_eq!(vk::VkResult::SuboptimalKhr as i32, 1000001003);
+        assert_eq!(vk::VkStructureType::SwapchainCreateInfoKhr as i32, 1000001000);
+    "#);
+}
+
+#[test]
+fn snake_case_commands_keep_raw_names() {
+    let config = GenConfig {
+        snake_case_commands: true,
+        ..GenConfig::default()
+    };
+
+    run_generated("snake_case_commands_global", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_global(file, VkVersion(1, 0), &[], config).unwrap();
+    }, r#"
+        let _: unsafe extern "system" fn(vk::VkCommandBuffer, u32, u32, u32, u32) = vk::cmd_draw;
+        assert_eq!(vk::cmd_draw::RAW_NAME, "vkCmdDraw");
+
+        let mut requested = Vec::new();
+        vk::load_with(|name| { requested.push(name.to_owned()); std::ptr::null() }).unwrap_err();
+        assert!(requested.iter().any(|n| n == "vkCmdDraw"));
+    "#);
+    run_generated("snake_case_commands_struct", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_struct(file, VkVersion(1, 0), &[], config).unwrap();
+    }, r#"
+        let _: unsafe extern "system" fn(&vk::Vk, vk::VkCommandBuffer, u32, u32, u32, u32) = vk::Vk::cmd_draw;
+        assert!(vk::Vk::COMMAND_OFFSETS.iter().any(|&(name, _)| name == "vkCmdDraw"));
+
+        let mut requested = Vec::new();
+        vk::Vk::new().load_with(|name| { requested.push(name.to_owned()); std::ptr::null() }).unwrap_err();
+        assert!(requested.iter().any(|n| n == "vkCmdDraw"));
+    "#);
+}
+
+#[test]
+fn derive_default_chainable() {
+    run_generated("derive_default_chainable", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_get_physical_device_properties2"], GenConfig {
+                derive_default: true,
+                snake_case_members: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        // Named `..._FEATURES_2_KHR`, which can't be worked out from the struct name alone.
+        let features = vk::VkPhysicalDeviceFeatures2KHR::default();
+        assert!(features.s_type == vk::VkStructureType::PhysicalDeviceFeatures2Khr);
+        assert!(features.p_next.is_null());
+        assert_eq!(features.features.geometry_shader, vk::FALSE);
+    "#);
+}
+
+#[test]
+fn slice_ptr_len() {
+    run_generated("slice_ptr_len", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], Default::default()).unwrap();
+    }, r#"
+        let empty: &[u32] = &[];
+        assert_eq!(vk::slice_ptr_len(empty), (::std::ptr::null(), 0));
+        assert_eq!(vk::slice_ptr_len_mut::<u32>(&mut []), (::std::ptr::null_mut(), 0));
+
+        let mut values = [1u32, 2, 3];
+        assert_eq!(vk::slice_ptr_len(&values), (values.as_ptr(), 3));
+        let ptr = values.as_mut_ptr();
+        assert_eq!(vk::slice_ptr_len_mut(&mut values), (ptr, 3));
+
+        // Zero-sized elements make a slice this long free to create.
+        let max = vec![(); u32::max_value() as usize];
+        assert_eq!(vk::slice_ptr_len(&max).1, u32::max_value());
+        let too_long = vec![(); u32::max_value() as usize + 1];
+        ::std::panic::set_hook(Box::new(|_| ()));
+        assert!(::std::panic::catch_unwind(|| vk::slice_ptr_len(&too_long)).is_err());
+    "#);
+}
+
+#[test]
+fn write_errors_are_returned() {
+    let registry = VkRegistry::new(vk_api::VK_XML);
+
+    // A slice writer fails once it's full, like a file on a full disk.
+    let mut buf = [0u8; 256];
+    let error = registry.gen_global(&mut &mut buf[..], VkVersion(1, 0), &[], GenConfig::default()).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+    let error = registry.gen_struct(&mut &mut buf[..], VkVersion(1, 0), &[], GenConfig::default()).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn group_by_extension() {
+    let extensions = &["VK_KHR_surface", "VK_KHR_swapchain"];
+    run_generated("group_by_extension_global", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), extensions, GenConfig {
+                group_by_extension: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        let _: unsafe extern "system" fn(_, _, _) = vk::vk_khr_surface::destroy_surface_khr;
+        let _: Option<vk::vk_khr_surface::VkSurfaceKHR> = None;
+        assert_eq!(vk::vk_khr_surface::KHR_SURFACE_EXTENSION_NAME, "VK_KHR_surface");
+
+        let info: vk::vk_khr_swapchain::VkSwapchainCreateInfoKHR = unsafe{ ::std::mem::zeroed() };
+        let _ = info.image_usage;
+        let _ = vk::vk_khr_swapchain::queue_present_khr;
+    "#);
+
+    run_generated("group_by_extension_consts", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), extensions, GenConfig {
+                group_by_extension: true,
+                use_native_enums: false,
+                variant_padding: VariantPaddingConfig::Keep,
+                camel_case_variants: false,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use vk::vk_khr_swapchain::*;
+        assert_eq!(VK_STRUCTURE_TYPE_SWAPCHAIN_CREATE_INFO_KHR, vk::VK_STRUCTURE_TYPE_SWAPCHAIN_CREATE_INFO_KHR);
+        assert_eq!(KHR_SWAPCHAIN_SPEC_VERSION, vk::KHR_SWAPCHAIN_SPEC_VERSION);
+    "#);
+}
+
+#[test]
+fn include_nested() {
+    let out = env!("OUT_DIR");
+    DirBuilder::new().recursive(true).create(out).unwrap();
+    let registry = VkRegistry::new(vk_api::VK_XML);
+    let config = || GenConfig {
+        macro_root: Some("vk::nested::bindings"),
+        ..GenConfig::default()
+    };
+
+    let mut global = File::create(Path::new(&out).join("include_nested_global.in")).unwrap();
+    registry.gen_global(&mut global, VkVersion(1, 0), &["VK_KHR_surface"], config()).unwrap();
+    let mut structs = File::create(Path::new(&out).join("include_nested_struct.in")).unwrap();
+    registry.gen_struct(&mut structs, VkVersion(1, 0), &["VK_KHR_surface"], config()).unwrap();
+
+    // The bindings get included the way a build script's output would be, a couple of modules deep.
+    let include = |file: &mut File, name: &str| {
+        writeln!(file, "pub mod nested {{
+    pub mod bindings {{ include!(\"{}\"); }}
+}}", name).unwrap();
+    };
+    run_generated("include_nested_global", |file| include(file, "include_nested_global.in"), r#"
+        let _ = vk::nested::bindings::create_instance;
+        assert_eq!(vk::nested::bindings::version_major(vk::nested::bindings::make_version(1, 2, 3)), 1);
+    "#);
+    run_generated("include_nested_struct", |file| include(file, "include_nested_struct.in"), r#"
+        let _ = vk::nested::bindings::Vk::new();
+        assert_eq!(vk::nested::bindings::version_major(vk::nested::bindings::make_version(1, 2, 3)), 1);
+    "#);
+}
+
+#[test]
+fn bitflags_crate() {
+    // The bitflags crate isn't a dependency here, so this only checks the macro invocations.
+    let mut out = Vec::new();
+    VkRegistry::new(vk_api::VK_XML)
+        .gen_struct(&mut out, VkVersion(1, 0), &[], GenConfig {
+            bitflags_crate: true,
+            ..GenConfig::default()
+        }).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert!(out.contains("::bitflags::bitflags! {\n    #[repr(transparent)]\n    #[derive(Default)]\n    pub struct VkBufferUsageFlagBits: VkFlags {\n"));
+    assert!(out.contains("        const BUFFER_USAGE_TRANSFER_SRC_BIT = 0b1;\n        const BUFFER_USAGE_TRANSFER_DST_BIT = 0b10;\n"));
+    assert!(out.contains("pub const BUFFER_USAGE_TRANSFER_SRC_BIT: VkBufferUsageFlagBits = VkBufferUsageFlagBits::BUFFER_USAGE_TRANSFER_SRC_BIT;"));
+    assert!(!out.contains("vk_bitflags_wrapped!(VkBufferUsageFlagBits"));
+}
+
+#[test]
+fn single_inherent_impl() {
+    let mut out = Vec::new();
+    VkRegistry::new(vk_api::VK_XML)
+        .gen_struct(&mut out, VkVersion(1, 0), &[], GenConfig {
+            field_offsets: true,
+            limit_accessors: true,
+            ..GenConfig::default()
+        }).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert_eq!(out.matches("impl VkPhysicalDeviceLimits {").count(), 1);
+    let block = &out[out.find("impl VkPhysicalDeviceLimits {").unwrap()..];
+    let block = &block[..block.find("\n}\n").unwrap()];
+    assert!(block.contains("pub const OFFSET_max_image_dimension1d: usize = 0;"));
+    assert!(block.contains("pub fn max_compute_work_group_count_x(&self)"));
+}
+
+#[test]
+fn filtered_generation() {
+    let create_destroy = |name: &str| name.starts_with("vkCreate") || name.starts_with("vkDestroy");
+
+    let mut out = Vec::new();
+    let stats = VkRegistry::new(vk_api::VK_XML)
+        .gen_global_filtered(&mut out, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default(), create_destroy).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("\"vkCreateInstance\", create_instance("));
+    assert!(out.contains("\"vkDestroySurfaceKHR\", destroy_surface_khr("));
+    assert!(!out.contains("\"vkEnumeratePhysicalDevices\""));
+    // Pulled in by `vkCreateInstance`, even though the filter rejects it.
+    assert!(out.contains("pub struct VkInstanceCreateInfo {"));
+    assert!(stats.commands < VkRegistry::new(vk_api::VK_XML).gen_global(&mut io::sink(), VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default()).unwrap().commands);
+
+    run_generated("filtered_generation_global", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global_filtered(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default(), create_destroy).unwrap();
+    }, r#"
+        let _ = vk::create_instance;
+        let _ = vk::destroy_surface_khr;
+        let _: vk::VkImageCreateInfo = unsafe{ ::std::mem::zeroed() };
+    "#);
+
+    // Rejecting everything still leaves valid, if empty, bindings.
+    run_generated("filtered_generation_empty_global", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global_filtered(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default(), |_| false).unwrap();
+    }, "");
+    run_generated("filtered_generation_empty_struct", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct_filtered(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default(), |_| false).unwrap();
+    }, "let _ = vk::Vk::new();");
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn describe_json() {
+    let mut out = Vec::new();
+    VkRegistry::new(vk_api::VK_XML)
+        .describe_json(&mut out, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default()).unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+    let create_instance = json["commands"].as_array().unwrap().iter()
+        .find(|c| c["raw_name"] == "vkCreateInstance")
+        .unwrap();
+    assert_eq!(create_instance["name"], "create_instance");
+    assert_eq!(create_instance["return"], "VkResult");
+    let params: Vec<_> = create_instance["params"].as_array().unwrap().iter()
+        .map(|p| (p["name"].as_str().unwrap(), p["type"].as_str().unwrap()))
+        .collect();
+    assert_eq!(params, [
+        ("p_create_info", "*const VkInstanceCreateInfo"),
+        ("p_allocator", "*const VkAllocationCallbacks"),
+        ("p_instance", "*mut VkInstance")
+    ]);
+
+    let types = json["types"].as_array().unwrap();
+    let app_info = types.iter().find(|t| t["raw_name"] == "VkApplicationInfo").unwrap();
+    assert_eq!(app_info["kind"], "struct");
+    assert_eq!(app_info["fields"][0]["name"], "s_type");
+    let surface = types.iter().find(|t| t["raw_name"] == "VkSurfaceKHR").unwrap();
+    assert_eq!(surface["dispatchable"], false);
+
+    let max_name = json["constants"].as_array().unwrap().iter()
+        .find(|c| c["name"] == "MAX_EXTENSION_NAME_SIZE")
+        .unwrap();
+    assert_eq!(max_name["value"], "256");
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn dump_json() {
+    let registry = VkRegistry::new(vk_api::VK_XML);
+    let (mut out, mut again) = (Vec::new(), Vec::new());
+    registry.dump_json(&mut out).unwrap();
+    registry.dump_json(&mut again).unwrap();
+    assert!(out == again, "the dump should be the same every time");
+    let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+    let create_instance = json["commands"].as_array().unwrap().iter()
+        .find(|c| c["name"] == "vkCreateInstance")
+        .unwrap();
+    let create_info = &create_instance["params"][0];
+    assert_eq!(create_info["name"], "pCreateInfo");
+    assert_eq!(create_info["type"]["kind"], "const_ptr");
+    assert_eq!(create_info["type"]["type"], "VkInstanceCreateInfo");
+    assert_eq!(create_info["type"]["depth"], 1);
+    assert!(create_instance["alias_of"].is_null());
+
+    let types = json["types"].as_array().unwrap();
+    let layer_properties = types.iter().find(|t| t["name"] == "VkLayerProperties").unwrap();
+    assert_eq!(layer_properties["kind"], "struct");
+    let layer_name = &layer_properties["fields"][0]["type"];
+    assert_eq!(layer_name["kind"], "mut_array");
+    assert_eq!(layer_name["len"], "VK_MAX_EXTENSION_NAME_SIZE");
+    let names: Vec<_> = types.iter().map(|t| t["name"].as_str().unwrap()).collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+
+    let feature = &json["features"][0];
+    assert_eq!(feature["name"], "VK_VERSION_1_0");
+    assert_eq!(feature["version"], "1.0.0");
+
+    let surface = json["extensions"].as_array().unwrap().iter()
+        .find(|e| e["name"] == "VK_KHR_surface")
+        .unwrap();
+    assert_eq!(surface["number"], 1);
+    assert!(surface["require"].as_array().unwrap().iter()
+        .any(|i| i["kind"] == "command" && i["name"] == "vkDestroySurfaceKHR"));
+}
+
+#[test]
+fn gen_config_builder() {
+    let built = GenConfig::builder()
+        .remove_type_prefix(true)
+        .use_native_enums(false)
+        .newtype_enums(true)
+        .load_reporting(LoadReporting::CountOnly)
+        .build()
+        .unwrap();
+    assert_eq!(built, GenConfig {
+        remove_type_prefix: true,
+        use_native_enums: false,
+        newtype_enums: true,
+        load_reporting: LoadReporting::CountOnly,
+        ..GenConfig::default()
+    });
+    assert_eq!(GenConfig::builder().build(), Ok(GenConfig::default()));
+
+    let contradictions = [
+        GenConfig::builder().newtype_enums(true).build(),
+        GenConfig::builder().wrap_bitmasks(false).bitflags_crate(true).build(),
+        GenConfig::builder().wrap_non_dispatchable_handles(false).niche_handles(true).build(),
+        GenConfig::builder().use_native_enums(false).impl_tryfrom_enums(true).build()
+    ];
+    for result in &contradictions {
+        assert!(result.is_err());
+    }
+    assert!(contradictions[0].unwrap_err().contains("use_native_enums"));
+}
+
+#[test]
+fn api_const_types() {
+    run_generated("api_const_types", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default()).unwrap();
+    }, r#"
+        let lod_clamp: f32 = vk::LOD_CLAMP_NONE;
+        assert_eq!(lod_clamp, 1000.0);
+        let whole_size: u64 = vk::WHOLE_SIZE;
+        assert_eq!(whole_size, u64::max_value());
+        let remaining: u32 = vk::REMAINING_MIP_LEVELS;
+        assert_eq!(remaining, u32::max_value());
+        let name_size: usize = vk::MAX_EXTENSION_NAME_SIZE;
+        assert_eq!(name_size, 256);
+        assert_eq!(vk::KHR_SURFACE_EXTENSION_NAME, "VK_KHR_surface");
+    "#);
+}
+
+#[test]
+fn gen_dispatch_tables() {
+    run_generated("gen_dispatch_tables", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_dispatch_tables(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig::default()).unwrap();
+    }, r#"
+        use std::cell::RefCell;
+        use std::ptr;
+
+        let loaded = RefCell::new(Vec::new());
+        let load = |name: &str| {
+            loaded.borrow_mut().push(name.to_owned());
+            ptr::null()
+        };
+
+        vk::VkEntry::new().load_with(&load).unwrap_err();
+        let entry = loaded.replace(Vec::new());
+        vk::VkInstanceFns::new().load_with(&load).unwrap_err();
+        let instance = loaded.replace(Vec::new());
+        vk::VkDeviceFns::new().load_with(&load).unwrap_err();
+        let device = loaded.replace(Vec::new());
+
+        let has = |table: &Vec<String>, name: &str| table.iter().any(|n| n == name);
+        for name in &["vkCreateInstance", "vkEnumerateInstanceExtensionProperties", "vkGetInstanceProcAddr"] {
+            assert!(has(&entry, name), "{} isn't in VkEntry", name);
+        }
+        for name in &["vkDestroyInstance", "vkEnumeratePhysicalDevices", "vkCreateDevice", "vkGetDeviceProcAddr",
+                      "vkDestroySurfaceKHR", "vkGetPhysicalDeviceSurfaceSupportKHR"] {
+            assert!(has(&instance, name), "{} isn't in VkInstanceFns", name);
+        }
+        for name in &["vkDestroyDevice", "vkQueueSubmit", "vkCmdDraw", "vkAllocateMemory"] {
+            assert!(has(&device, name), "{} isn't in VkDeviceFns", name);
+        }
+        // Every command is in exactly one table.
+        for name in entry.iter().chain(instance.iter()) {
+            assert!(!has(&device, name), "{} is in VkDeviceFns too", name);
+        }
+        for name in &entry {
+            assert!(!has(&instance, name), "{} is in VkInstanceFns too", name);
+        }
+    "#);
+}
+
+#[test]
+fn impl_display_enums() {
+    run_generated("impl_display_enums", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_maintenance1"], GenConfig {
+                impl_display_enums: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        assert_eq!(vk::VkResult::Success.to_string(), "VK_SUCCESS");
+        assert_eq!(vk::VkResult::ErrorDeviceLost.to_string(), "VK_ERROR_DEVICE_LOST");
+        assert_eq!(vk::VkResult::ErrorSurfaceLostKhr.to_string(), "VK_ERROR_SURFACE_LOST_KHR");
+        assert_eq!(format!("{}", vk::VkFormat::R8g8b8a8Unorm), "VK_FORMAT_R8G8B8A8_UNORM");
+    "#);
+    run_generated("impl_display_newtype_enums", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_maintenance1"], GenConfig {
+                use_native_enums: false,
+                newtype_enums: true,
+                impl_display_enums: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        assert_eq!(vk::VkResult::Success.to_string(), "VK_SUCCESS");
+        assert_eq!(vk::VkResult(-1000069000).to_string(), "VK_ERROR_OUT_OF_POOL_MEMORY_KHR");
+        assert_eq!(vk::VkResult(12345).to_string(), "12345");
+    "#);
+}
+
+#[test]
+fn impl_tryfrom_enums() {
+    run_generated("impl_tryfrom_enums", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_maintenance1"], GenConfig {
+                impl_tryfrom_enums: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use std::convert::TryFrom;
+
+        assert_eq!(vk::VkResult::try_from(0), Ok(vk::VkResult::Success));
+        assert_eq!(vk::VkResult::try_from(-1000069000), Ok(vk::VkResult::ErrorOutOfPoolMemoryKhr));
+        assert_eq!(vk::VkResult::try_from(12345), Err(12345));
+        assert_eq!(vk::VkFormat::try_from(37), Ok(vk::VkFormat::R8g8b8a8Unorm));
+    "#);
+}
+
+#[test]
+fn enum_iter() {
+    const REGISTRY: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type name="VkMode" category="enum"/>
+        </types>
+        <enums name="VkMode" type="enum">
+            <enum value="0" name="VK_MODE_FIRST"/>
+            <enum value="1" name="VK_MODE_SECOND"/>
+            <enum value="2" name="VK_MODE_THIRD"/>
+            <enum alias="VK_MODE_SECOND" name="VK_MODE_SECOND_KHR"/>
+            <enum alias="VK_MODE_FIRST" name="VK_MODE_DEFAULT"/>
+        </enums>
+        <commands>
+            <command>
+                <proto><type>void</type> <name>vkSetMode</name></proto>
+                <param><type>VkMode</type> <name>mode</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkSetMode"/>
+            </require>
+        </feature>
+    </registry>"#;
+    let main_body = r#"
+        assert_eq!(vk::VkMode::ALL.len(), 3);
+        assert_eq!(vk::VkMode::ALL, &[vk::VkMode::First, vk::VkMode::Second, vk::VkMode::Third]);
+        assert_eq!(vk::VkMode::Default, vk::VkMode::First);
+    "#;
+
+    let registry = VkRegistry::new(REGISTRY);
+    let native = GenConfig {
+        enum_iter: true,
+        ..GenConfig::default()
+    };
+    let newtype = GenConfig {
+        use_native_enums: false,
+        newtype_enums: true,
+        enum_iter: true,
+        ..GenConfig::default()
+    };
+    run_generated("enum_iter_native", |file| {
+        registry.gen_global(file, VkVersion(1, 0), &[], native).unwrap();
+    }, main_body);
+    run_generated("enum_iter_newtype", |file| {
+        registry.gen_global(file, VkVersion(1, 0), &[], newtype).unwrap();
+    }, main_body);
+
+    // The alias constants and `ALL` share the one impl block.
+    let mut out = Vec::new();
+    registry.gen_global(&mut out, VkVersion(1, 0), &[], native).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("impl VkMode {").count(), 1);
+    assert!(out.contains("    pub const Default: VkMode = VkMode::First;\n    pub const ALL: &'static [VkMode] = &[VkMode::First, VkMode::Second, VkMode::Third];\n}"));
+
+    let mut out = Vec::new();
+    registry.gen_global(&mut out, VkVersion(1, 0), &[], newtype).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("vk_newtype_enum!(VkMode, [First = 0, Second = 1, Third = 2, SecondKhr = 1, Default = 0], ALL = [First, Second, Third]);"));
+}
+
+#[test]
+fn extension_dependencies_are_pulled_in() {
+    let mut diagnostics = Vec::new();
+    run_generated("extension_dependencies_are_pulled_in", |file| {
+        VkRegistry::new_with_diagnostics(vk_api::VK_XML, |d| diagnostics.push(d))
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_swapchain"], GenConfig::default()).unwrap();
+    }, r#"
+        let has = |name| vk::Vk::COMMAND_OFFSETS.iter().any(|&(n, _)| n == name);
+        assert!(has("vkCreateSwapchainKHR"));
+        assert!(has("vkDestroySurfaceKHR"));
+        assert_eq!(vk::KHR_SURFACE_EXTENSION_NAME, "VK_KHR_surface");
+    "#);
+    assert!(diagnostics.contains(&Diagnostic::DependenciesAdded{ extensions: vec!["VK_KHR_surface".to_owned()] }));
+}
+
+#[test]
+fn no_std() {
+    let out = env!("OUT_DIR");
+    DirBuilder::new().recursive(true).create(out).unwrap();
+
+    let configs = [
+        ("no_std_struct", GenConfig {
+            no_std: true,
+            ..GenConfig::default()
+        }),
+        ("no_std_global", GenConfig {
+            no_std: true,
+            wrap_bitmasks: true,
+            wrap_non_dispatchable_handles: true,
+            derive_default: true,
+            chain_builders: true,
+            geometry_helpers: true,
+            impl_display_enums: true,
+            emit_self_tests: true,
+            load_reporting: LoadReporting::CountOnly,
+            ..GenConfig::default()
+        })
+    ];
+    for &(name, config) in &configs {
+        let mut file = File::create(Path::new(&out).join(name).with_extension("rs")).unwrap();
+        // `LoadReporting::Full` needs `alloc`, which needs an allocator once the crate is linked.
+        writeln!(file, "#![no_std]
+#![allow(dead_code)]
+extern crate alloc;
+
+struct DummyAllocator;
+
+unsafe impl core::alloc::GlobalAlloc for DummyAllocator {{
+    unsafe fn alloc(&self, _: core::alloc::Layout) -> *mut u8 {{ core::ptr::null_mut() }}
+    unsafe fn dealloc(&self, _: *mut u8, _: core::alloc::Layout) {{}}
+}}
+
+#[global_allocator]
+static ALLOCATOR: DummyAllocator = DummyAllocator;
+
+mod vk {{").unwrap();
+        let registry = VkRegistry::new(vk_api::VK_XML);
+        if name == "no_std_struct" {
+            registry.gen_struct(&mut file, VkVersion(1, 0), &["VK_KHR_surface"], config).unwrap();
+        } else {
+            registry.gen_global(&mut file, VkVersion(1, 0), &["VK_KHR_surface"], config).unwrap();
+        }
+        writeln!(file, "}}").unwrap();
+
+        let compile = Command::new("rustc").current_dir(out)
+            .args(["--crate-type", "lib", "--crate-name", name])
+            .arg(Path::new(name).with_extension("rs"))
+            .output().unwrap();
+        if !compile.status.success() {
+            panic!("{}", String::from_utf8(compile.stderr).unwrap());
+        }
+    }
+}
+
+#[test]
+fn structure_type_table() {
+    run_generated("structure_type_table", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain"], GenConfig {
+                structure_type_table: true,
+                // Which also has a `STRUCTURE_TYPE`, on the `StructureChain` trait.
+                gen_chain_helpers: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        assert_eq!(vk::VkApplicationInfo::STRUCTURE_TYPE, vk::VkStructureType::ApplicationInfo);
+        assert_eq!(vk::VkSwapchainCreateInfoKHR::STRUCTURE_TYPE, vk::VkStructureType::SwapchainCreateInfoKhr);
+        assert_eq!(vk::structure_type_name(vk::VkStructureType::ApplicationInfo), Some("VkApplicationInfo"));
+        assert_eq!(vk::structure_type_name(vk::VkStructureType::SwapchainCreateInfoKhr), Some("VkSwapchainCreateInfoKHR"));
+        assert_eq!(vk::structure_type_name(vk::VkStructureType::LoaderInstanceCreateInfo), None);
+        assert!(vk::STRUCTURE_TYPES.iter().all(|&(_, name)| name != "VkExtent2D"));
+    "#);
+    run_generated("structure_type_table_consts", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                structure_type_table: true,
+                use_native_enums: false,
+                variant_padding: VariantPaddingConfig::RemovePrefix,
+                camel_case_variants: false,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        assert_eq!(vk::VkInstanceCreateInfo::STRUCTURE_TYPE, vk::STRUCTURE_TYPE_INSTANCE_CREATE_INFO);
+        assert_eq!(vk::structure_type_name(vk::STRUCTURE_TYPE_INSTANCE_CREATE_INFO), Some("VkInstanceCreateInfo"));
+    "#);
+}
+
+#[test]
+fn wrap_commands_in_trait() {
+    run_generated("wrap_commands_in_trait", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct_filtered(file, VkVersion(1, 0), &[], GenConfig {
+                wrap_commands_in_trait: true,
+                ..GenConfig::default()
+            }, |name| name == "vkCreateInstance" || name == "vkDestroyInstance").unwrap();
+    }, r#"
+        use std::cell::Cell;
+        use std::ptr;
+        use vk::VkCommands;
+
+        struct Mock {
+            created: Cell<u32>,
+            destroyed: Cell<u32>
+        }
+
+        impl vk::VkCommands for Mock {
+            unsafe fn create_instance(&self, _: *const vk::VkInstanceCreateInfo, _: *const vk::VkAllocationCallbacks,
+                                      instance: *mut vk::VkInstance) -> vk::VkResult {
+                self.created.set(self.created.get() + 1);
+                *instance = 1 as vk::VkInstance;
+                vk::VkResult::Success
+            }
+
+            unsafe fn destroy_instance(&self, instance: vk::VkInstance, _: *const vk::VkAllocationCallbacks) {
+                assert_eq!(instance, 1 as vk::VkInstance);
+                self.destroyed.set(self.destroyed.get() + 1);
+            }
+        }
+
+        fn create_and_destroy(vk: &dyn vk::VkCommands) -> vk::VkResult {
+            unsafe {
+                let mut instance = ptr::null_mut();
+                let result = vk.create_instance(ptr::null(), ptr::null(), &mut instance);
+                vk.destroy_instance(instance, ptr::null());
+                result
+            }
+        }
+
+        let mock = Mock{ created: Cell::new(0), destroyed: Cell::new(0) };
+        assert_eq!(create_and_destroy(&mock), vk::VkResult::Success);
+        assert_eq!((mock.created.get(), mock.destroyed.get()), (1, 1));
+
+        // `Vk` implements it too, forwarding to its own methods.
+        unsafe extern "system" fn fake_destroy(_: vk::VkInstance, _: *const vk::VkAllocationCallbacks) {}
+        let mut real = vk::Vk::new();
+        real.load_with(|name| if name == "vkDestroyInstance" {fake_destroy as *const ()} else {ptr::null()}).unwrap_err();
+        unsafe{ VkCommands::destroy_instance(&real, ptr::null_mut(), ptr::null()) };
+    "#);
+}
+
+#[test]
+fn double_pointers() {
+    run_generated("double_pointers", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        use vk::{c_char, c_void};
+
+        let info: vk::VkInstanceCreateInfo = unsafe{ std::mem::zeroed() };
+        let _: *const *const c_char = info.pp_enabled_layer_names;
+        let _: unsafe extern "system" fn(&vk::Vk, vk::VkDevice, vk::VkDeviceMemory, vk::VkDeviceSize, vk::VkDeviceSize,
+                                         vk::VkMemoryMapFlags, *mut *mut c_void) -> vk::VkResult = vk::Vk::map_memory;
+    "#);
+}
+
+#[test]
+fn platform_cfgs() {
+    const EXTENSIONS: &[&str] = &["VK_KHR_surface", "VK_KHR_win32_surface", "VK_KHR_xlib_surface",
+                                                  "VK_KHR_external_memory_win32"];
+    let config = GenConfig {
+        platform_cfgs: true,
+        wrap_commands_in_trait: true,
+        safe_slice_wrappers: true,
+        group_by_extension: true,
+        chain_builders: true,
+        ..GenConfig::default()
+    };
+
+    let mut source = Vec::new();
+    VkRegistry::new(vk_api::VK_XML).gen_struct(&mut source, VkVersion(1, 0), EXTENSIONS, config).unwrap();
+    let source = String::from_utf8(source).unwrap();
+    assert!(source.contains("vk_platform_cfg!{windows;\n#[derive(Debug, Clone, Copy)]\n#[repr(C)]\npub struct VkWin32SurfaceCreateInfoKHR"));
+    assert!(source.contains("    if cfg(windows)\n    \"vkCreateWin32SurfaceKHR\""));
+    assert!(source.contains("#[cfg(windows)]\npub mod vk_khr_win32_surface"));
+
+    // The Windows items are compiled out here, but everything else is left alone.
+    run_generated("platform_cfgs_struct", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_struct(file, VkVersion(1, 0), EXTENSIONS, config).unwrap();
+    }, r#"
+        let mut vk = vk::Vk::new();
+        let _ = vk::Vk::create_xlib_surface_khr;
+        let _ = vk::KHR_WIN32_SURFACE_EXTENSION_NAME;
+        #[cfg(windows)]
+        let _: vk::VkWin32SurfaceCreateInfoKHR = unsafe{ std::mem::zeroed() };
+        assert!(vk::Vk::COMMAND_OFFSETS.iter().any(|&(name, _)| name == "vkCreateWin32SurfaceKHR"));
+        assert!(vk.load_with(|_| std::ptr::null()).is_err());
+    "#);
+    run_generated("platform_cfgs_global", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_global(file, VkVersion(1, 0), EXTENSIONS, config).unwrap();
+    }, r#"
+        let _ = vk::cmds::create_xlib_surface_khr;
+        assert!(vk::load_with(|_| std::ptr::null()).is_err());
+    "#);
+}
+
+#[test]
+fn define_platform_types() {
+    const EXTENSIONS: &[&str] = &["VK_KHR_surface", "VK_KHR_win32_surface", "VK_KHR_xlib_surface",
+                                                  "VK_KHR_xcb_surface", "VK_KHR_wayland_surface", "VK_KHR_mir_surface",
+                                                  "VK_KHR_android_surface", "VK_EXT_acquire_xlib_display",
+                                                  "VK_KHR_external_semaphore_win32"];
+    run_generated("define_platform_types", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_struct(file, VkVersion(1, 0), EXTENSIONS, GenConfig {
+            define_platform_types: true,
+            extern_type_overrides: &[("LPCWSTR", "std::primitive::u64")],
+            ..GenConfig::default()
+        }).unwrap();
+    }, r#"
+        use std::os::raw::{c_ulong, c_void};
+        use std::ptr;
+
+        let _: vk::Window = 0 as c_ulong;
+        let _: vk::RROutput = 0 as c_ulong;
+        let _: vk::xcb_window_t = 0u32;
+        let _: vk::DWORD = 0u32;
+        let _: vk::HWND = ptr::null_mut::<c_void>();
+        let _: *mut vk::Display = ptr::null_mut();
+        let _: *mut vk::wl_surface = ptr::null_mut();
+        let _: *mut vk::ANativeWindow = ptr::null_mut();
+        let _ = vk::SECURITY_ATTRIBUTES{ nLength: 12, lpSecurityDescriptor: ptr::null_mut(), bInheritHandle: 0 };
+        // Overrides still win.
+        let _: vk::LPCWSTR = 0u64;
+    "#);
+}
+
+#[test]
+fn result_wrappers() {
+    run_generated("result_wrappers", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig {
+                result_wrappers: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use std::ptr;
+
+        unsafe extern "system" fn create_instance(_: *const vk::VkInstanceCreateInfo, _: *const vk::VkAllocationCallbacks,
+                                                  instance: *mut vk::VkInstance) -> vk::VkResult {
+            *instance = 1 as vk::VkInstance;
+            vk::VkResult::Success
+        }
+        unsafe extern "system" fn create_instance_fails(_: *const vk::VkInstanceCreateInfo, _: *const vk::VkAllocationCallbacks,
+                                                        _: *mut vk::VkInstance) -> vk::VkResult {
+            vk::VkResult::ErrorInitializationFailed
+        }
+        unsafe extern "system" fn device_wait_idle(_: vk::VkDevice) -> vk::VkResult {
+            vk::VkResult::Incomplete
+        }
+        unsafe extern "system" fn enumerate_physical_devices(_: vk::VkInstance, _: *mut u32, _: *mut vk::VkPhysicalDevice) -> vk::VkResult {
+            vk::VkResult::Incomplete
+        }
+
+        let mut vk = vk::Vk::new();
+        vk.load_with(|name| match name {
+            "vkCreateInstance"           => create_instance as *const (),
+            "vkDeviceWaitIdle"           => device_wait_idle as *const (),
+            "vkEnumeratePhysicalDevices" => enumerate_physical_devices as *const (),
+            _                            => ptr::null()
+        }).unwrap_err();
+        unsafe {
+            let instance: vk::VkInstance = vk.create_instance_checked(ptr::null(), ptr::null()).unwrap();
+            assert_eq!(instance, 1 as vk::VkInstance);
+            // Only the success codes documented for the command are `Ok`.
+            assert_eq!(vk.enumerate_physical_devices_checked(ptr::null_mut(), ptr::null_mut(), ptr::null_mut()), Ok(()));
+            assert_eq!(vk.device_wait_idle_checked(ptr::null_mut()), Err(vk::VkResult::Incomplete));
+        }
+
+        vk.load_with(|name| if name == "vkCreateInstance" {create_instance_fails as *const ()} else {ptr::null()}).unwrap_err();
+        unsafe {
+            assert_eq!(vk.create_instance_checked(ptr::null(), ptr::null()), Err(vk::VkResult::ErrorInitializationFailed));
+            // Arrays aren't mistaken for out-pointers.
+            let _: unsafe fn(&vk::Vk, vk::VkInstance, *mut u32, *mut vk::VkPhysicalDevice) -> Result<(), vk::VkResult> =
+                vk::Vk::enumerate_physical_devices_checked;
+        }
+    "#);
+    run_generated("result_wrappers_global", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig {
+                result_wrappers: true,
+                use_native_enums: false,
+                newtype_enums: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        let _: unsafe fn(*const vk::VkInstanceCreateInfo, *const vk::VkAllocationCallbacks) -> Result<vk::VkInstance, vk::VkResult> =
+            vk::cmds::create_instance_checked;
+    "#);
+}
+
+#[test]
+fn symbol_prefix() {
+    run_generated("symbol_prefix_global", |file| {
+        for &prefix in &["a_", "b_"] {
+            writeln!(file, "pub use self::{}::*;\npub mod {} {{", &prefix[..1], &prefix[..1]).unwrap();
+            VkRegistry::new(vk_api::VK_XML)
+                .gen_global(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig {
+                    symbol_prefix: Some(prefix),
+                    ..GenConfig::default()
+                }).unwrap();
+            writeln!(file, "}}").unwrap();
+        }
+    }, r#"
+        use std::ptr;
+
+        let _: vk::a_VkInstance = ptr::null_mut();
+        let _: vk::b_VkInstance = ptr::null_mut();
+        let _: vk::a_VkSurfaceKHR = vk::a_VkSurfaceKHR::null();
+        let _ = vk::a_create_instance;
+        let _ = vk::b_create_instance;
+        let _ = vk::b_destroy_surface_khr;
+        assert_eq!(vk::a_MAX_EXTENSION_NAME_SIZE, vk::b_MAX_EXTENSION_NAME_SIZE);
+        assert_eq!(vk::a_VkResult::Success as i32, vk::b_VkResult::Success as i32);
+        let _ = vk::a_BUFFER_USAGE_TRANSFER_SRC_BIT | vk::a_BUFFER_USAGE_INDEX_BUFFER_BIT;
+        let _ = vk::b_VkInstanceCreateInfo {
+            p_application_info: ptr::null::<vk::b_VkApplicationInfo>(),
+            ..unsafe{ std::mem::zeroed() }
+        };
+    "#);
+    run_generated("symbol_prefix_struct", |file| {
+        for &prefix in &["a_", "b_"] {
+            writeln!(file, "pub use self::{}::*;\npub mod {} {{", &prefix[..1], &prefix[..1]).unwrap();
+            VkRegistry::new(vk_api::VK_XML)
+                .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig {
+                    symbol_prefix: Some(prefix),
+                    remove_type_prefix: true,
+                    remove_const_prefix: true,
+                    ..GenConfig::default()
+                }).unwrap();
+            writeln!(file, "}}").unwrap();
+        }
+    }, r#"
+        let _: vk::a_Instance = std::ptr::null_mut();
+        let _: vk::b_Instance = std::ptr::null_mut();
+        assert_eq!(vk::a_MAX_EXTENSION_NAME_SIZE, 256);
+        assert_eq!(vk::a_Result::Success as i32, 0);
+        // The helper items keep their names, so they're reached through their modules.
+        let _ = vk::a::Vk::new();
+        let _ = vk::b::Vk::new();
+    "#);
+}
+
+#[test]
+fn funcpointers_are_nullable() {
+    run_generated("funcpointers_are_nullable", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_EXT_debug_report"], GenConfig::default()).unwrap();
+    }, r#"
+        use std::mem::size_of;
+        use std::os::raw::c_char;
+        use std::{mem, ptr};
+
+        unsafe extern "system" fn callback(_: vk::VkDebugReportFlagsEXT, _: vk::VkDebugReportObjectTypeEXT, _: u64, _: usize,
+                                           _: i32, _: *const c_char, _: *const c_char, _: *mut ()) -> vk::VkBool32 {
+            0
+        }
+
+        assert_eq!(size_of::<vk::PFN_vkDebugReportCallbackEXT>(), size_of::<*const ()>());
+        let mut create_info = vk::VkDebugReportCallbackCreateInfoEXT {
+            s_type: vk::VkStructureType::DebugReportCallbackCreateInfoExt,
+            p_next: ptr::null(),
+            flags: 0,
+            pfn_callback: None,
+            p_user_data: ptr::null_mut()
+        };
+        create_info.pfn_callback = Some(callback);
+        assert!(format!("{:?}", create_info).contains("pfn_callback: Some("));
+
+        // Callbacks that the registry marks as optional don't get wrapped twice.
+        let callbacks: vk::VkAllocationCallbacks = unsafe{ mem::zeroed() };
+        let _: vk::PFN_vkInternalAllocationNotification = callbacks.pfn_internal_allocation;
+        assert!(callbacks.pfn_allocation.is_none());
+    "#);
+
+    let mut out = Vec::new();
+    VkRegistry::new(vk_api::VK_XML)
+        .gen_global(&mut out, VkVersion(1, 0), &["VK_EXT_debug_report"], GenConfig::default()).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("pub type PFN_vkDebugReportCallbackEXT = Option<unsafe extern \"system\" fn(\n"));
+    assert!(out.contains("pub type PFN_vkFreeFunction = Option<unsafe extern \"system\" fn(\n    *mut c_void,\n    *mut c_void,\n)>;"));
+}
+
+#[test]
+fn diagnostics() {
+    const REGISTRY: &[u8] = br#"<registry>
+        <types>
+            <type name="vk_platform" category="include">#include "vk_platform.h"</type>
+            <type category="include">#include &lt;<name>X11/Xlib.h</name>&gt;</type>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type name="VkResult" category="enum"/>
+        </types>
+        <enums name="VkResult" type="enum">
+            <enum value="0" name="VK_SUCCESS"/>
+            <enum alias="VK_DONE" name="VK_DONE_KHR"/>
+        </enums>
+        <commands>
+            <command>
+                <proto><type>VkResult</type> <name>vkDoThing</name></proto>
+                <param><type>uint32_t</type> <name>count</name></param>
+                <param/>
+                <param/>
+            </command>
+            <command name="vkDoOtherThingKHR" alias="vkDoOtherThing"/>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkDoThing"/>
+            </require>
+        </feature>
+    </registry>"#;
+
+    let mut diagnostics = Vec::new();
+    let registry = VkRegistry::new_with_diagnostics(REGISTRY, |d| diagnostics.push(d));
+    registry.gen_global(&mut Vec::new(), VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    assert_eq!(registry.lookup_type("VkResult").unwrap().variants, ["VK_SUCCESS"]);
+    drop(registry);
+
+    let unhandled: Vec<_> = diagnostics.iter().filter_map(|d| match *d {
+        Diagnostic::UnhandledType{ref name, ..} => Some(&name[..]),
+        _                                       => None
+    }).collect();
+    assert_eq!(unhandled, ["vk_platform", "X11/Xlib.h", "VkResult"]);
+    assert!(diagnostics.contains(&Diagnostic::TruncatedParams{ name: "vkDoThing".to_owned(), dropped: 2 }));
+    assert!(diagnostics.contains(&Diagnostic::DanglingAlias{
+        name: "vkDoOtherThingKHR".to_owned(),
+        alias_of: "vkDoOtherThing".to_owned()
+    }));
+    assert!(diagnostics.contains(&Diagnostic::DanglingAlias{
+        name: "VK_DONE_KHR".to_owned(),
+        alias_of: "VK_DONE".to_owned()
+    }));
+    assert!(diagnostics.iter().any(|d| match *d {
+        Diagnostic::Crawled{types, commands: 1, ..} => types > 0,
+        _                                           => false
+    }));
+    assert!(diagnostics.contains(&Diagnostic::Selected{ types: 2, commands: 1 }));
+    match diagnostics.last() {
+        Some(&Diagnostic::Generated(stats)) => assert_eq!(stats.commands, 1),
+        d                                   => panic!("{:?}", d)
+    }
+}
+
+#[test]
+fn gen_constructors() {
+    run_generated("gen_constructors", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+                gen_constructors: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use std::os::raw::c_char;
+        use std::ptr;
+
+        let priorities = [1.0, 0.5];
+        let queue_info = vk::VkDeviceQueueCreateInfo::new(3, &priorities);
+        assert_eq!(queue_info.s_type, vk::VkStructureType::DeviceQueueCreateInfo);
+        assert!(queue_info.p_next.is_null());
+        assert_eq!(queue_info.flags, 0);
+        assert_eq!(queue_info.queue_family_index, 3);
+        assert_eq!(queue_info.queue_count, 2);
+        assert_eq!(queue_info.p_queue_priorities, priorities.as_ptr());
+
+        let layers = [b"VK_LAYER_LUNARG_standard_validation\0".as_ptr() as *const c_char];
+        let instance_info = vk::VkInstanceCreateInfo::new(&layers, &[]);
+        assert!(instance_info.p_application_info.is_null());
+        assert_eq!(instance_info.enabled_layer_count, 1);
+        assert_eq!(instance_info.pp_enabled_layer_names, layers.as_ptr());
+        assert_eq!(instance_info.enabled_extension_count, 0);
+        assert_eq!(instance_info.pp_enabled_extension_names, ptr::null());
+
+        // Optional counts and pointers are left out together.
+        let _: fn(&[vk::VkSemaphore], &[vk::VkPipelineStageFlags], &[vk::VkCommandBuffer], &[vk::VkSemaphore]) -> vk::VkSubmitInfo =
+            vk::VkSubmitInfo::new;
+
+        // `pWaitDstStageMask` shares its count with `pWaitSemaphores`.
+        let semaphores: [vk::VkSemaphore; 2] = unsafe{ std::mem::zeroed() };
+        let stages = [0, 0];
+        let submit_info = vk::VkSubmitInfo::new(&semaphores, &stages, &[], &[]);
+        assert_eq!(submit_info.wait_semaphore_count, 2);
+        assert_eq!(submit_info.p_wait_dst_stage_mask, stages.as_ptr());
+        assert!(std::panic::catch_unwind(|| vk::VkSubmitInfo::new(&semaphores, &stages[..1], &[], &[])).is_err());
+        let _: fn() -> vk::VkFenceCreateInfo = vk::VkFenceCreateInfo::new;
+    "#);
+    run_generated("gen_constructors_global", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &["VK_KHR_surface", "VK_KHR_swapchain", "VK_EXT_debug_report"], GenConfig {
+                gen_constructors: true,
+                use_native_enums: false,
+                variant_padding: VariantPaddingConfig::Keep,
+                camel_case_variants: false,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        let info = vk::VkCommandBufferBeginInfo::new();
+        assert_eq!(info.s_type, vk::VK_STRUCTURE_TYPE_COMMAND_BUFFER_BEGIN_INFO);
+        let _ = vk::VkDebugReportCallbackCreateInfoEXT::new(None);
+    "#);
+}
+
+#[test]
+fn wrap_dispatchable_handles() {
+    run_generated("wrap_dispatchable_handles", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig {
+                wrap_dispatchable_handles: true,
+                handle_trait: true,
+                derive_default: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        use std::mem::size_of;
+        use vk::VkHandle;
+
+        assert_eq!(size_of::<vk::VkInstance>(), size_of::<usize>());
+        assert!(vk::VkDevice::NULL.is_null());
+        assert_eq!(vk::VkDevice::null().as_raw(), 0);
+
+        let instance = unsafe{ vk::VkInstance::from_raw(0x1000) };
+        assert!(!instance.is_null());
+        assert_eq!(unsafe{ vk::VkInstance::from_raw(instance.as_raw()) }, instance);
+        assert_eq!(VkHandle::as_raw(instance), 0x1000);
+        assert_eq!(format!("{:?}", instance), "VkInstance(0x1000)");
+
+        let submit = vk::VkSubmitInfo::default();
+        assert!(submit.p_command_buffers.is_null());
+    "#);
+    run_generated("wrap_dispatchable_handles_global", |file| {
+        VkRegistry::new(vk_api::VK_XML)
+            .gen_global(file, VkVersion(1, 0), &[], GenConfig {
+                wrap_dispatchable_handles: true,
+                result_wrappers: true,
+                ..GenConfig::default()
+            }).unwrap();
+    }, r#"
+        let _: unsafe extern "system" fn(vk::VkDevice, *const vk::VkAllocationCallbacks) = vk::destroy_device;
+        assert!(vk::VkQueue::NULL.is_null());
+    "#);
+}
+
+#[test]
+fn emit_deprecated() {
+    // `VK_OLD_thing` was replaced by `VK_NEW_thing`, which still needs `vkShared`.
+    const REGISTRY: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type category="struct" name="VkThing">
+                <member><type>uint32_t</type> <name>value</name></member>
+            </type>
+            <type category="struct" name="VkOldThing" alias="VkThing" deprecated="aliased"/>
+        </types>
+        <commands>
+            <command>
+                <proto><type>void</type> <name>vkOldThing</name></proto>
+                <param><type>VkThing</type>* <name>pThing</name></param>
+            </command>
+            <command>
+                <proto><type>void</type> <name>vkShared</name></proto>
+                <param><type>uint32_t</type> <name>value</name></param>
+            </command>
+            <command>
+                <proto><type>void</type> <name>vkNewThing</name></proto>
+                <param><type>uint32_t</type> <name>value</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <type name="VkThing"/>
+                <type name="VkOldThing"/>
+            </require>
+        </feature>
+        <extensions>
+            <extension name="VK_OLD_thing" number="1" supported="vulkan" deprecatedby="VK_NEW_thing">
+                <require>
+                    <enum value="1" name="VK_OLD_THING_SPEC_VERSION"/>
+                    <enum value="&quot;VK_OLD_thing&quot;" name="VK_OLD_THING_EXTENSION_NAME"/>
+                    <command name="vkOldThing"/>
+                    <command name="vkShared"/>
+                </require>
+            </extension>
+            <extension name="VK_NEW_thing" number="2" supported="vulkan">
+                <require>
+                    <enum value="1" name="VK_NEW_THING_SPEC_VERSION"/>
+                    <enum value="&quot;VK_NEW_thing&quot;" name="VK_NEW_THING_EXTENSION_NAME"/>
+                    <command name="vkShared"/>
+                    <command name="vkNewThing"/>
+                </require>
+            </extension>
+        </extensions>
+    </registry>"#;
+
+    let registry = VkRegistry::new(REGISTRY);
+    let config = GenConfig {
+        emit_deprecated: true,
+        group_by_extension: true,
+        ..GenConfig::default()
+    };
+    let mut out = Vec::new();
+    registry.gen_global(&mut out, VkVersion(1, 0), &["VK_OLD_thing", "VK_NEW_thing"], config).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let note = "#[deprecated(note = \"deprecated by VK_NEW_thing\")]";
+    assert!(out.contains(&format!("{}\n    \"vkOldThing\"", note)), "{}", out);
+    assert!(!out.contains(&format!("{}\n    \"vkShared\"", note)));
+    assert!(!out.contains(&format!("{}\n    \"vkNewThing\"", note)));
+    assert!(out.contains(&format!("{}\npub const OLD_THING_EXTENSION_NAME", note)));
+    assert!(out.contains(&format!("{}\npub const OLD_THING_SPEC_VERSION", note)));
+    assert!(!out.contains(&format!("{}\npub const NEW_THING", note)));
+    assert!(out.contains(&format!("{}\npub mod vk_old_thing", note)));
+    assert!(out.contains("#[deprecated(note = \"use VkThing instead\")]\npub type VkOldThing = VkThing;"));
+
+    run_generated("emit_deprecated_global", |file| {
+        registry.gen_global(file, VkVersion(1, 0), &["VK_OLD_thing", "VK_NEW_thing"], GenConfig {
+            result_wrappers: true,
+            ..config
+        }).unwrap();
+    }, r#"
+        assert_eq!(vk::ALL_EXTENSION_NAMES.len(), 2);
+        assert_eq!(vk::cmds::shared::RAW_NAME, "vkShared");
+    "#);
+    run_generated("emit_deprecated_struct", |file| {
+        registry.gen_struct(file, VkVersion(1, 0), &["VK_OLD_thing", "VK_NEW_thing"], GenConfig {
+            wrap_commands_in_trait: true,
+            safe_slice_wrappers: true,
+            ..config
+        }).unwrap();
+    }, r#"
+        assert_eq!(vk::NEW_THING_EXTENSION_NAME, "VK_NEW_thing");
+    "#);
+}
+
+#[test]
+fn load_with_loader() {
+    run_generated("load_with_loader", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_global(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        use std::ffi::CStr;
+        use std::os::raw::c_char;
+        use std::ptr;
+
+        // Hands out commands the way the loader does, which is only with a null instance for the
+        // global commands and only with a real instance for the rest.
+        unsafe extern "system" fn stub() {}
+        unsafe extern "system" fn get_instance_proc_addr(instance: vk::VkInstance, name: *const c_char) -> vk::PFN_vkVoidFunction {
+            let name = CStr::from_ptr(name).to_str().unwrap();
+            let global = "vkCreateInstance" == name || "vkGetInstanceProcAddr" == name || name.starts_with("vkEnumerateInstance");
+            if global == instance.is_null() {Some(stub)} else {None}
+        }
+
+        let unloaded = vk::load_with_loader(get_instance_proc_addr, ptr::null_mut()).unwrap_err();
+        assert!(!unloaded.contains(&"vkCreateInstance"));
+        assert!(!unloaded.contains(&"vkEnumerateInstanceLayerProperties"));
+        assert!(unloaded.contains(&"vkCreateDevice"));
+
+        let instance = 0x10 as vk::VkInstance;
+        assert_eq!(vk::load_with_loader(get_instance_proc_addr, instance), Ok(()));
+    "#);
+}
+
+#[test]
+fn rename() {
+    let uppercase = |name: &str, kind| match kind {
+        IdentKind::Type => name.to_uppercase(),
+        _               => name.to_owned()
+    };
+    let main_body = r#"
+        let info: vk::VKAPPLICATIONINFO = unsafe{ std::mem::zeroed() };
+        assert_eq!(info.s_type, vk::VKSTRUCTURETYPE::ApplicationInfo);
+        let _: vk::VKRESULT = vk::VKRESULT::Success;
+    "#;
+
+    run_generated("rename_global", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_global(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig {
+            rename: Some(Renamer(&uppercase)),
+            ..GenConfig::default()
+        }).unwrap();
+    }, main_body);
+    run_generated("rename_struct", |file| {
+        VkRegistry::new(vk_api::VK_XML).gen_struct(file, VkVersion(1, 0), &["VK_KHR_surface"], GenConfig {
+            rename: Some(Renamer(&uppercase)),
+            ..GenConfig::default()
+        }).unwrap();
+    }, main_body);
+}
+
+#[test]
+#[should_panic(expected = "isn't a legal identifier")]
+fn rename_checks_identifiers() {
+    let config = GenConfig {
+        rename: Some(Renamer(&|name: &str, _| name.replace("Vk", "Vk-"))),
+        ..GenConfig::default()
+    };
+    VkRegistry::new(vk_api::VK_XML).gen_global(&mut Vec::new(), VkVersion(1, 0), &[], config).unwrap();
+}
+
+#[test]
+fn keyword_members() {
+    const REGISTRY: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type category="struct" name="VkKeywords">
+                <member><type>uint32_t</type> <name>type</name></member>
+                <member><type>uint32_t</type> <name>match</name></member>
+                <member><type>uint32_t</type> <name>self</name></member>
+                <member><type>uint32_t</type> <name>values</name>[2]</member>
+            </type>
+            <type category="funcpointer">typedef void (VKAPI_PTR *<name>PFN_vkVoidFunction</name>)(void);</type>
+            <type category="union" name="VkKeywordUnion">
+                <member><type>PFN_vkVoidFunction</type> <name>fn</name></member>
+            </type>
+        </types>
+        <commands>
+            <command>
+                <proto><type>void</type> <name>vkUseKeywords</name></proto>
+                <param>const <type>VkKeywords</type>* <name>ref</name></param>
+                <param><type>uint32_t</type> <name>crate</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkUseKeywords"/>
+                <type name="VkKeywordUnion"/>
+            </require>
+        </feature>
+    </registry>"#;
+    let main_body = r#"
+        let keywords = vk::VkKeywords{ typ: 1, r#match: 2, self_: 3, values: [4, 5] };
+        assert_eq!(format!("{:?}", keywords), "VkKeywords { typ: 1, match: 2, self_: 3, values: [4, 5] }");
+        let keyword_union = vk::VkKeywordUnion{ r#fn: None };
+        assert_eq!(format!("{:?}", keyword_union), "VkKeywordUnion { fn: None }");
+    "#;
+
+    run_generated("keyword_members_global", |file| {
+        VkRegistry::new(REGISTRY).gen_global(file, VkVersion(1, 0), &[], GenConfig {
+            use_native_unions: true,
+            ..GenConfig::default()
+        }).unwrap();
+    }, main_body);
+    run_generated("keyword_members_struct", |file| {
+        VkRegistry::new(REGISTRY).gen_struct(file, VkVersion(1, 0), &[], GenConfig {
+            field_offsets: true,
+            use_native_unions: true,
+            ..GenConfig::default()
+        }).unwrap();
+    }, main_body);
+}
+
+#[test]
+fn api_variants() {
+    // `VkThing` has a member and `vkDoThing` a parameter that differ between Vulkan and Vulkan SC.
+    const REGISTRY: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type requires="vk_platform" name="uint64_t"/>
+            <type category="struct" name="VkThing">
+                <member><type>uint32_t</type> <name>shared</name></member>
+                <member api="vulkansc"><type>uint64_t</type> <name>scOnly</name></member>
+            </type>
+        </types>
+        <commands>
+            <command api="vulkan">
+                <proto><type>void</type> <name>vkDoThing</name></proto>
+                <param><type>uint32_t</type> <name>value</name></param>
+            </command>
+            <command api="vulkansc">
+                <proto><type>void</type> <name>vkDoThing</name></proto>
+                <param><type>uint64_t</type> <name>value</name></param>
+            </command>
+            <command api="vulkansc">
+                <proto><type>void</type> <name>vkScOnly</name></proto>
+                <param><type>uint32_t</type> <name>value</name></param>
+            </command>
+            <command api="vulkan,vulkansc" name="vkScOnlyKHR" alias="vkScOnly"/>
+            <command api="vulkan,vulkansc">
+                <proto><type>void</type> <name>vkUseThing</name></proto>
+                <param><type>VkThing</type>* <name>pThing</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan,vulkansc" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkDoThing"/>
+                <command name="vkUseThing"/>
+            </require>
+            <require api="vulkansc">
+                <command name="vkScOnly"/>
+            </require>
+        </feature>
+        <extensions>
+            <extension name="VK_KHR_sc_thing" number="1" supported="vulkansc"/>
+        </extensions>
+    </registry>"#;
+
+    let registry = VkRegistry::new(REGISTRY);
+    assert_eq!(registry.api(), "vulkan");
+    assert!(!registry.command_names().contains(&"vkScOnly"));
+    assert!(!registry.command_names().contains(&"vkScOnlyKHR"));
+    assert!(registry.extension_names().is_empty());
+    run_generated("api_variants_vulkan", |file| {
+        registry.gen_global(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        let _: unsafe extern "system" fn(u32) = vk::do_thing;
+        let vk::VkThing{ shared: _ } = vk::VkThing{ shared: 1 };
+    "#);
+
+    let registry = VkRegistry::new_for_api(REGISTRY, "vulkansc");
+    assert!(registry.command_names().contains(&"vkScOnly"));
+    assert!(registry.command_names().contains(&"vkScOnlyKHR"));
+    assert_eq!(registry.extension_names(), ["VK_KHR_sc_thing"]);
+    run_generated("api_variants_vulkansc", |file| {
+        registry.gen_global(file, VkVersion(1, 0), &[], GenConfig::default()).unwrap();
+    }, r#"
+        let _: unsafe extern "system" fn(u64) = vk::do_thing;
+        let _: unsafe extern "system" fn(u32) = vk::sc_only;
+        let vk::VkThing{ shared: _, sc_only: _ } = vk::VkThing{ shared: 1, sc_only: 2 };
+    "#);
+}
+
+#[test]
+fn skipped_blocks() {
+    // Newer registries end with blocks describing formats, SPIR-V support and synchronization, none
+    // of which bindings are generated from.
+    const REGISTRY: &[u8] = br#"<registry>
+        <types>
+            <type requires="vk_platform" name="void"/>
+            <type requires="vk_platform" name="uint32_t"/>
+            <type category="struct" name="VkThing">
+                <member><type>uint32_t</type> <name>value</name></member>
+            </type>
+        </types>
+        <commands>
+            <command>
+                <proto><type>void</type> <name>vkUseThing</name></proto>
+                <param><type>VkThing</type>* <name>pThing</name></param>
+            </command>
+        </commands>
+        <feature api="vulkan" name="VK_VERSION_1_0" number="1.0">
+            <require>
+                <command name="vkUseThing"/>
+            </require>
+        </feature>
+        <extensions>
+            <extension name="VK_KHR_thing" number="1" supported="vulkan">
+                <require>
+                    <enum value="1" name="VK_KHR_THING_SPEC_VERSION"/>
+                    <enum value="&quot;VK_KHR_thing&quot;" name="VK_KHR_THING_EXTENSION_NAME"/>
+                </require>
+            </extension>
+        </extensions>
+        <formats>
+            <format name="VK_FORMAT_R8_UNORM" class="8-bit" blockSize="1" texelsPerBlock="1">
+                <component name="R" bits="8" numericFormat="UNORM"/>
+            </format>
+        </formats>
+        <spirvextensions>
+            <spirvextension name="SPV_KHR_thing">
+                <enable extension="VK_KHR_thing"/>
+            </spirvextension>
+        </spirvextensions>
+        <spirvcapabilities>
+            <spirvcapability name="Thing">
+                <enable version="VK_VERSION_1_0"/>
+                <enable struct="VkThing" feature="value" requires="VK_VERSION_1_0"/>
+                <enable property="VkThing" member="value" value="VK_TRUE" requires="VK_KHR_thing"/>
+            </spirvcapability>
+        </spirvcapabilities>
+        <sync>
+            <syncstage name="VK_PIPELINE_STAGE_2_NONE">
+                <syncsupport queues="VK_QUEUE_GRAPHICS_BIT"/>
+            </syncstage>
+        </sync>
+    </registry>"#;
+
+    let registry = VkRegistry::new(REGISTRY);
+    assert_eq!(registry.extension_names(), ["VK_KHR_thing"]);
+    run_generated("skipped_blocks", |file| {
+        registry.gen_global(file, VkVersion(1, 0), &["VK_KHR_thing"], GenConfig::default()).unwrap();
+    }, r#"
+        let thing = vk::VkThing{ value: 1 };
+        let _: unsafe extern "system" fn(*mut vk::VkThing) = vk::use_thing;
+        assert_eq!(thing.value, 1);
+        assert_eq!(vk::KHR_THING_EXTENSION_NAME, "VK_KHR_thing");
+    "#);
+}